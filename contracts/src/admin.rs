@@ -0,0 +1,272 @@
+//! Shared admin/ownership helpers.
+//!
+//! Contracts call [`set_admin`] from their `initialize` entrypoint and gate
+//! privileged entrypoints with [`require_admin`]. Ownership moves in two
+//! steps: the current admin proposes a successor with [`transfer_admin`] and
+//! the successor confirms with [`accept_admin`]. Until the proposal is
+//! accepted the current admin stays in charge.
+//!
+//! Besides the admin, a contract can record a handful of single-holder key
+//! roles (see [`KeyRole`]) which are reported together by [`get_roles`].
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+use crate::errors::CommonError;
+
+/// Single-holder operational roles configured by the admin.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum KeyRole {
+    /// May veto queued governance actions
+    Veto = 0,
+    /// Backup address used to recover control of the contract
+    Recovery = 1,
+    /// Receives platform fees
+    FeeCollector = 2,
+    /// Holds platform funds (stakes, slashed bonds, sponsorship budget)
+    Treasury = 3,
+}
+
+/// Snapshot of the admin and every key role, unset roles being `None`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Roles {
+    pub admin: Address,
+    pub veto: Option<Address>,
+    pub recovery: Option<Address>,
+    pub fee_collector: Option<Address>,
+    pub treasury: Option<Address>,
+}
+
+#[contracttype]
+enum AdminKey {
+    Admin,
+    PendingAdmin,
+    KeyRole(KeyRole),
+}
+
+/// Records the initial admin.
+///
+/// # Panics
+/// With `AlreadyInitialized` if an admin has already been set
+pub fn set_admin(env: &Env, admin: &Address) {
+    if has_admin(env) {
+        panic_with_error!(env, CommonError::AlreadyInitialized);
+    }
+    env.storage().instance().set(&AdminKey::Admin, admin);
+}
+
+/// Returns `true` once the contract has been initialized with an admin.
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&AdminKey::Admin)
+}
+
+/// Returns the active admin.
+///
+/// # Panics
+/// With `NotInitialized` if no admin has been set
+pub fn get_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&AdminKey::Admin)
+        .unwrap_or_else(|| panic_with_error!(env, CommonError::NotInitialized))
+}
+
+/// Requires authorization from the active admin and returns its address.
+pub fn require_admin(env: &Env) -> Address {
+    let admin = get_admin(env);
+    admin.require_auth();
+    admin
+}
+
+/// Requires `caller` to be the active admin and to have authorized the call.
+///
+/// Used by entrypoints that take the acting admin as an explicit argument.
+///
+/// # Panics
+/// With `NotAdmin` if `caller` is not the active admin
+pub fn require_admin_caller(env: &Env, caller: &Address) {
+    caller.require_auth();
+    if *caller != get_admin(env) {
+        panic_with_error!(env, CommonError::NotAdmin);
+    }
+}
+
+/// Proposes `new_admin` as the next admin. Requires the current admin's auth.
+///
+/// The active admin is unchanged until `new_admin` calls [`accept_admin`].
+/// Proposing again replaces any earlier, unaccepted proposal.
+pub fn transfer_admin(env: &Env, new_admin: &Address) {
+    require_admin(env);
+    env.storage()
+        .instance()
+        .set(&AdminKey::PendingAdmin, new_admin);
+}
+
+/// Completes an ownership transfer. Requires `new_admin`'s auth.
+///
+/// # Panics
+/// With `NotPendingAdmin` if `new_admin` is not the proposed successor
+pub fn accept_admin(env: &Env, new_admin: &Address) {
+    new_admin.require_auth();
+
+    let pending: Option<Address> = env.storage().instance().get(&AdminKey::PendingAdmin);
+    if pending.as_ref() != Some(new_admin) {
+        panic_with_error!(env, CommonError::NotPendingAdmin);
+    }
+
+    env.storage().instance().set(&AdminKey::Admin, new_admin);
+    env.storage().instance().remove(&AdminKey::PendingAdmin);
+}
+
+/// Returns the proposed successor of an in-flight ownership transfer, if any.
+pub fn pending_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&AdminKey::PendingAdmin)
+}
+
+/// Assigns `role` to `address`. Requires the current admin's auth.
+pub fn set_role(env: &Env, role: KeyRole, address: &Address) {
+    require_admin(env);
+    env.storage()
+        .instance()
+        .set(&AdminKey::KeyRole(role), address);
+}
+
+/// Returns the holder of `role`, if one has been assigned.
+pub fn get_role(env: &Env, role: KeyRole) -> Option<Address> {
+    env.storage().instance().get(&AdminKey::KeyRole(role))
+}
+
+/// Returns the admin together with every key role in one read.
+pub fn get_roles(env: &Env) -> Roles {
+    Roles {
+        admin: get_admin(env),
+        veto: get_role(env, KeyRole::Veto),
+        recovery: get_role(env, KeyRole::Recovery),
+        fee_collector: get_role(env, KeyRole::FeeCollector),
+        treasury: get_role(env, KeyRole::Treasury),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env};
+
+    #[contract]
+    struct AdminHarness;
+
+    #[contractimpl]
+    impl AdminHarness {
+        pub fn initialize(env: Env, admin: Address) {
+            set_admin(&env, &admin);
+        }
+
+        pub fn admin(env: Env) -> Address {
+            get_admin(&env)
+        }
+
+        pub fn pending(env: Env) -> Option<Address> {
+            pending_admin(&env)
+        }
+
+        pub fn transfer_admin(env: Env, new_admin: Address) {
+            transfer_admin(&env, &new_admin);
+        }
+
+        pub fn accept_admin(env: Env, new_admin: Address) {
+            accept_admin(&env, &new_admin);
+        }
+
+        pub fn set_role(env: Env, role: KeyRole, address: Address) {
+            set_role(&env, role, &address);
+        }
+
+        pub fn get_roles(env: Env) -> Roles {
+            get_roles(&env)
+        }
+    }
+
+    fn setup(env: &Env) -> AdminHarnessClient<'_> {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AdminHarness);
+        AdminHarnessClient::new(env, &contract_id)
+    }
+
+    #[test]
+    fn test_initialize_only_once() {
+        let env = Env::default();
+        let client = setup(&env);
+        let admin = Address::generate(&env);
+
+        client.initialize(&admin);
+        assert_eq!(client.admin(), admin);
+        assert!(client.try_initialize(&Address::generate(&env)).is_err());
+        assert_eq!(client.admin(), admin);
+    }
+
+    #[test]
+    fn test_propose_then_accept() {
+        let env = Env::default();
+        let client = setup(&env);
+        let admin = Address::generate(&env);
+        let successor = Address::generate(&env);
+        client.initialize(&admin);
+
+        client.transfer_admin(&successor);
+        // An unaccepted proposal leaves the active admin unchanged.
+        assert_eq!(client.admin(), admin);
+        assert_eq!(client.pending(), Some(successor.clone()));
+
+        client.accept_admin(&successor);
+        assert_eq!(client.admin(), successor);
+        assert_eq!(client.pending(), None);
+    }
+
+    #[test]
+    fn test_accept_from_unproposed_address_rejected() {
+        let env = Env::default();
+        let client = setup(&env);
+        let admin = Address::generate(&env);
+        let successor = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        client.initialize(&admin);
+
+        // Nothing proposed yet.
+        assert!(client.try_accept_admin(&stranger).is_err());
+
+        client.transfer_admin(&successor);
+        assert!(client.try_accept_admin(&stranger).is_err());
+        assert_eq!(client.admin(), admin);
+        assert_eq!(client.pending(), Some(successor));
+    }
+
+    #[test]
+    fn test_get_roles_reports_configured_addresses() {
+        let env = Env::default();
+        let client = setup(&env);
+        let admin = Address::generate(&env);
+        let veto = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        client.initialize(&admin);
+
+        client.set_role(&KeyRole::Veto, &veto);
+        client.set_role(&KeyRole::Treasury, &treasury);
+
+        assert_eq!(
+            client.get_roles(),
+            Roles {
+                admin: admin.clone(),
+                veto: Some(veto),
+                recovery: None,
+                fee_collector: None,
+                treasury: Some(treasury.clone()),
+            }
+        );
+
+        // Reassigning a role replaces the previous holder.
+        let new_treasury = Address::generate(&env);
+        client.set_role(&KeyRole::Treasury, &new_treasury);
+        assert_eq!(client.get_roles().treasury, Some(new_treasury));
+        assert_eq!(client.get_roles().admin, admin);
+    }
+}
@@ -0,0 +1,81 @@
+//! Admin-managed registry of supported assets.
+//!
+//! Maps canonical asset codes ("USDC", "EURC", ...) to the issuer and token
+//! contract on the configured network, so contracts resolve a code to the
+//! real asset instead of trusting caller-supplied addresses. Each contract
+//! keeps its own registry in instance storage; only the admin or an operator
+//! (see [`crate::rbac`]) may change it.
+use soroban_sdk::{contracttype, panic_with_error, Address, Env, Map, String, Vec};
+
+use crate::errors::CommonError;
+
+use crate::rbac::{self, Role};
+
+/// Where a supported asset lives on the network.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AssetInfo {
+    /// Classic issuer account
+    pub issuer: Address,
+    /// Stellar Asset Contract for the asset
+    pub token: Address,
+}
+
+#[contracttype]
+enum DataKey {
+    SupportedAssets,
+}
+
+/// Registers (or re-points) `code`.
+///
+/// # Panics
+/// If `caller` is neither the contract admin nor an operator, or has not
+/// authorized the call
+pub fn register_asset(env: &Env, caller: &Address, code: String, info: AssetInfo) {
+    rbac::require_admin_or_role(env, Role::Operator, caller);
+    let mut assets = registry(env);
+    assets.set(code, info);
+    env.storage()
+        .instance()
+        .set(&DataKey::SupportedAssets, &assets);
+}
+
+/// Returns a supported asset, if registered.
+pub fn get_asset(env: &Env, code: String) -> Option<AssetInfo> {
+    registry(env).get(code)
+}
+
+/// # Panics
+/// If `code` is not registered
+pub fn require_asset(env: &Env, code: String) -> AssetInfo {
+    get_asset(env, code).unwrap_or_else(|| panic_with_error!(env, CommonError::UnsupportedAsset))
+}
+
+/// Returns every supported `(code, asset)`, ordered by code.
+pub fn supported_assets(env: &Env) -> Vec<(String, AssetInfo)> {
+    let mut assets = Vec::new(env);
+    for asset in registry(env).iter() {
+        assets.push_back(asset);
+    }
+    assets
+}
+
+/// Returns `true` if nothing is registered yet.
+pub fn is_empty(env: &Env) -> bool {
+    registry(env).is_empty()
+}
+
+/// Returns `true` if `token` is the contract of a supported asset.
+pub fn is_supported_token(env: &Env, token: &Address) -> bool {
+    registry(env)
+        .values()
+        .iter()
+        .any(|info| info.token == *token)
+}
+
+fn registry(env: &Env) -> Map<String, AssetInfo> {
+    env.storage()
+        .instance()
+        .get(&DataKey::SupportedAssets)
+        .unwrap_or_else(|| Map::new(env))
+}
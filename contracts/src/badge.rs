@@ -0,0 +1,248 @@
+//! Soulbound completion badges.
+//!
+//! Issuers the admin has registered, such as the escrow contract, mint a
+//! badge to a freelancer when a project completes or a ruling goes their
+//! way. Badges record what the work was and who it was for, so freelancers
+//! can point to them as portable proof of delivered work. They cannot be
+//! transferred or burned: there is no entrypoint that moves a badge off the
+//! address it was minted to. Issuing contracts go through [`mint`], which
+//! discards failures so a misconfigured badge contract never blocks a payout.
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, Address, BytesN, Env,
+    Vec,
+};
+
+use crate::admin;
+use crate::{events, ttl};
+
+/// One whole token at the 7 decimals Stellar assets use.
+const UNIT: i128 = 10_000_000;
+
+/// Why a badge was minted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum BadgeKind {
+    /// Every milestone of the project was released
+    Completed = 0,
+    /// An arbiter ruled the larger share of a dispute to the holder
+    DisputeWon = 1,
+}
+
+/// Coarse size of the project, so badges prove scale without disclosing the
+/// exact amount. Bounds are in whole tokens.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum AmountBracket {
+    /// Under 100
+    Small = 0,
+    /// 100 to under 1,000
+    Medium = 1,
+    /// 1,000 to under 10,000
+    Large = 2,
+    /// 10,000 and above
+    Major = 3,
+}
+
+impl AmountBracket {
+    /// Returns the bracket `amount`, in base units, falls into.
+    pub fn of(amount: i128) -> Self {
+        match amount / UNIT {
+            n if n < 100 => Self::Small,
+            n if n < 1_000 => Self::Medium,
+            n if n < 10_000 => Self::Large,
+            _ => Self::Major,
+        }
+    }
+}
+
+/// The work a badge is minted for, as reported by the issuer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Delivery {
+    /// Escrow the work was delivered under, in the issuer's numbering
+    pub escrow_id: u64,
+    /// Commitment to the project's agreed scope
+    pub project_hash: BytesN<32>,
+    /// Client the work was delivered to
+    pub counterparty: Address,
+    /// Amount earned, in base units; only its bracket is stored
+    pub amount: i128,
+}
+
+/// A non-transferable record of delivered work.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Badge {
+    /// Contract that minted the badge
+    pub issuer: Address,
+    /// Escrow the work was delivered under, in the issuer's numbering
+    pub escrow_id: u64,
+    pub kind: BadgeKind,
+    /// Commitment to the project's agreed scope
+    pub project_hash: BytesN<32>,
+    /// Client the work was delivered to
+    pub counterparty: Address,
+    pub bracket: AmountBracket,
+    /// Ledger timestamp the badge was minted at
+    pub issued_at: u64,
+}
+
+/// Errors raised by the badge contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum BadgeError {
+    /// The caller is not a registered issuer
+    NotIssuer = 100,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Contract allowed to mint badges
+    Issuer(Address),
+    /// Badges held by an address, oldest first
+    Badges(Address),
+}
+
+/// Mints a badge to `holder` with `badges` on behalf of the current
+/// contract, ignoring any failure.
+pub fn mint(env: &Env, badges: &Address, holder: &Address, kind: BadgeKind, delivery: &Delivery) {
+    let _ = CompletionBadgesClient::new(env, badges).try_mint(
+        &env.current_contract_address(),
+        holder,
+        &kind,
+        delivery,
+    );
+}
+
+#[contract]
+pub struct CompletionBadges;
+
+#[contractimpl]
+impl CompletionBadges {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Allows `issuer` to mint badges. Admin only.
+    pub fn add_issuer(env: Env, admin: Address, issuer: Address) {
+        admin::require_admin_caller(&env, &admin);
+        ttl::set(&env, &DataKey::Issuer(issuer), &true);
+    }
+
+    /// Stops `issuer` from minting badges. Badges it already minted are
+    /// kept. Admin only.
+    pub fn remove_issuer(env: Env, admin: Address, issuer: Address) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage().persistent().remove(&DataKey::Issuer(issuer));
+    }
+
+    /// Returns `true` if `issuer` may mint badges.
+    pub fn is_issuer(env: Env, issuer: Address) -> bool {
+        ttl::get(&env, &DataKey::Issuer(issuer)).unwrap_or(false)
+    }
+
+    /// Mints a badge to `holder` for `delivery`. Requires `issuer`'s auth,
+    /// which a contract calling in directly has implicitly.
+    ///
+    /// # Panics
+    /// With `NotIssuer` if `issuer` is not registered
+    pub fn mint(env: Env, issuer: Address, holder: Address, kind: BadgeKind, delivery: Delivery) {
+        issuer.require_auth();
+        if !Self::is_issuer(env.clone(), issuer.clone()) {
+            panic_with_error!(env, BadgeError::NotIssuer);
+        }
+
+        let mut badges = Self::badges(env.clone(), holder.clone());
+        badges.push_back(Badge {
+            issuer: issuer.clone(),
+            escrow_id: delivery.escrow_id,
+            kind,
+            project_hash: delivery.project_hash,
+            counterparty: delivery.counterparty,
+            bracket: AmountBracket::of(delivery.amount),
+            issued_at: env.ledger().timestamp(),
+        });
+        ttl::set(&env, &DataKey::Badges(holder.clone()), &badges);
+        events::emit_badge_minted(&env, &holder, &issuer, delivery.escrow_id, kind);
+    }
+
+    /// Returns every badge `holder` has been minted, oldest first.
+    pub fn badges(env: Env, holder: Address) -> Vec<Badge> {
+        ttl::get(&env, &DataKey::Badges(holder)).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns how many badges `holder` has been minted.
+    pub fn badge_count(env: Env, holder: Address) -> u32 {
+        Self::badges(env, holder).len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_only_issuers_mint() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let badges =
+            CompletionBadgesClient::new(&env, &env.register_contract(None, CompletionBadges));
+        let admin = Address::generate(&env);
+        badges.initialize(&admin);
+        let escrow = Address::generate(&env);
+        let freelancer = Address::generate(&env);
+        let client = Address::generate(&env);
+        let project_hash = BytesN::from_array(&env, &[3; 32]);
+        let delivery = Delivery {
+            escrow_id: 1,
+            project_hash: project_hash.clone(),
+            counterparty: client.clone(),
+            amount: 250 * UNIT,
+        };
+
+        assert_eq!(
+            badges.try_mint(&escrow, &freelancer, &BadgeKind::Completed, &delivery),
+            Err(Ok(BadgeError::NotIssuer.into()))
+        );
+        badges.add_issuer(&admin, &escrow);
+        badges.mint(&escrow, &freelancer, &BadgeKind::Completed, &delivery);
+        assert_eq!(
+            badges.badges(&freelancer),
+            Vec::from_array(
+                &env,
+                [Badge {
+                    issuer: escrow.clone(),
+                    escrow_id: 1,
+                    kind: BadgeKind::Completed,
+                    project_hash,
+                    counterparty: client.clone(),
+                    bracket: AmountBracket::Medium,
+                    issued_at: env.ledger().timestamp(),
+                }]
+            )
+        );
+
+        badges.remove_issuer(&admin, &escrow);
+        assert_eq!(badges.badge_count(&freelancer), 1);
+        assert_eq!(badges.badge_count(&client), 0);
+    }
+}
@@ -0,0 +1,260 @@
+//! Batch payroll payouts.
+//!
+//! Agencies paying many contractors at once pull the batch total from the
+//! payer in a single transfer and fan it out from here. A recipient who
+//! cannot receive the asset yet, for want of a trustline or because their
+//! balance is deauthorized, does not abort the batch: their share is
+//! credited to a claimable balance they withdraw with `claim` once ready.
+use soroban_sdk::{
+    contract, contractimpl, contracttype, panic_with_error, token, Address, Env, Vec,
+};
+
+use crate::errors::CommonError;
+use crate::{admin, claimable, events, pausable, ttl};
+
+/// Maximum number of payments accepted by a single batch.
+pub const MAX_PAYOUT_BATCH: u32 = 100;
+
+/// Outcome of one payment in a batch.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum PayoutStatus {
+    /// The amount was transferred to the recipient
+    Paid = 0,
+    /// The transfer failed and the amount awaits the recipient's claim
+    Credited = 1,
+    /// The amount was not positive; nothing was pulled for it
+    InvalidAmount = 2,
+}
+
+/// A batch as recorded for reconciliation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Batch {
+    pub payer: Address,
+    pub token: Address,
+    /// Sum of every valid payment, pulled from the payer
+    pub total: i128,
+    pub paid: u32,
+    pub credited: u32,
+    pub rejected: u32,
+    pub ledger: u32,
+}
+
+/// What `pay_batch` returns: the batch id and each payment's outcome, in
+/// the same order as the input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BatchReceipt {
+    pub batch_id: u64,
+    pub results: Vec<PayoutStatus>,
+}
+
+#[contracttype]
+pub enum DataKey {
+    BatchCount,
+    Batch(u64),
+}
+
+#[contract]
+pub struct BatchPayout;
+
+#[contractimpl]
+impl BatchPayout {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Pauses every state-changing entrypoint. Admin or guardian only.
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause. Admin or guardian only.
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Pays every `(recipient, amount)` in `payments` from `payer` in
+    /// `asset`, pulling the total in one transfer. Payments that cannot be
+    /// transferred are credited to the recipient's claimable balance and
+    /// non-positive amounts are skipped, so one bad entry never fails the
+    /// rest of the batch.
+    ///
+    /// # Panics
+    /// If the payer has not authorized the call or cannot cover the total,
+    /// or more than `MAX_PAYOUT_BATCH` payments are given
+    pub fn pay_batch(
+        env: Env,
+        payer: Address,
+        asset: Address,
+        payments: Vec<(Address, i128)>,
+    ) -> BatchReceipt {
+        pausable::require_not_paused(&env);
+        payer.require_auth();
+        if payments.len() > MAX_PAYOUT_BATCH {
+            panic_with_error!(env, CommonError::BatchTooLarge);
+        }
+
+        let total: i128 = payments
+            .iter()
+            .map(|(_, amount)| amount)
+            .filter(|amount| *amount > 0)
+            .sum();
+        let token_client = token::Client::new(&env, &asset);
+        let this = env.current_contract_address();
+        if total > 0 {
+            token_client.transfer(&payer, &this, &total);
+        }
+
+        let mut batch = Batch {
+            payer: payer.clone(),
+            token: asset.clone(),
+            total,
+            paid: 0,
+            credited: 0,
+            rejected: 0,
+            ledger: env.ledger().sequence(),
+        };
+        let mut results = Vec::new(&env);
+        for (recipient, amount) in payments.iter() {
+            let status = if amount <= 0 {
+                batch.rejected += 1;
+                PayoutStatus::InvalidAmount
+            } else if token_client
+                .try_transfer(&this, &recipient, &amount)
+                .is_ok_and(|r| r.is_ok())
+            {
+                batch.paid += 1;
+                PayoutStatus::Paid
+            } else {
+                claimable::credit(&env, &recipient, &asset, amount);
+                batch.credited += 1;
+                PayoutStatus::Credited
+            };
+            results.push_back(status);
+        }
+
+        let batch_id = Self::batch_count(env.clone()) + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::BatchCount, &batch_id);
+        ttl::set(&env, &DataKey::Batch(batch_id), &batch);
+        events::emit_batch_paid(&env, batch_id, &batch);
+        BatchReceipt { batch_id, results }
+    }
+
+    /// Returns a recorded batch, if it exists.
+    pub fn get_batch(env: Env, batch_id: u64) -> Option<Batch> {
+        ttl::get(&env, &DataKey::Batch(batch_id))
+    }
+
+    /// Returns how many batches have been paid; batch ids run from 1 to it.
+    pub fn batch_count(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::BatchCount)
+            .unwrap_or(0)
+    }
+
+    /// Withdraws everything batches have credited to `recipient` in `asset`,
+    /// and returns the amount.
+    pub fn claim(env: Env, recipient: Address, asset: Address) -> i128 {
+        claimable::claim(&env, &recipient, &asset)
+    }
+
+    /// Returns what `user` may claim in `asset`.
+    pub fn claimable_balance(env: Env, user: Address, asset: Address) -> i128 {
+        claimable::balance(&env, &user, &asset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, IssuerFlags};
+    use soroban_sdk::token::StellarAssetClient;
+    use soroban_sdk::{vec, Env};
+
+    #[test]
+    fn test_failed_transfers_are_credited_not_aborted() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let batches = BatchPayoutClient::new(&env, &env.register_contract(None, BatchPayout));
+        batches.initialize(&Address::generate(&env));
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+        let token = token::Client::new(&env, &sac.address());
+        let asset = StellarAssetClient::new(&env, &sac.address());
+        let payer = Address::generate(&env);
+        asset.mint(&payer, &1_000);
+        let paid = Address::generate(&env);
+        let blocked = Address::generate(&env);
+        asset.set_authorized(&blocked, &false);
+
+        let receipt = batches.pay_batch(
+            &payer,
+            &token.address,
+            &vec![
+                &env,
+                (paid.clone(), 300),
+                (blocked.clone(), 200),
+                (Address::generate(&env), 0),
+            ],
+        );
+        assert_eq!(
+            receipt,
+            BatchReceipt {
+                batch_id: 1,
+                results: vec![
+                    &env,
+                    PayoutStatus::Paid,
+                    PayoutStatus::Credited,
+                    PayoutStatus::InvalidAmount,
+                ],
+            }
+        );
+        assert_eq!(token.balance(&payer), 500);
+        assert_eq!(token.balance(&paid), 300);
+        assert_eq!(batches.claimable_balance(&blocked, &token.address), 200);
+        let batch = batches.get_batch(&1).unwrap();
+        assert_eq!(
+            (batch.total, batch.paid, batch.credited, batch.rejected),
+            (500, 1, 1, 1)
+        );
+
+        asset.set_authorized(&blocked, &true);
+        assert_eq!(batches.claim(&blocked, &token.address), 200);
+        assert_eq!(token.balance(&blocked), 200);
+
+        let mut oversized = Vec::new(&env);
+        for _ in 0..=MAX_PAYOUT_BATCH {
+            oversized.push_back((paid.clone(), 1));
+        }
+        assert_eq!(
+            batches.try_pay_batch(&payer, &token.address, &oversized),
+            Err(Ok(CommonError::BatchTooLarge.into()))
+        );
+    }
+}
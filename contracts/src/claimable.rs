@@ -0,0 +1,56 @@
+//! Pull-based payouts.
+//!
+//! A direct transfer to a recipient without a trustline, or to an unfunded
+//! account, fails and reverts the whole release with it. Payout paths
+//! therefore [`credit`] the recipient's claimable balance in the paying
+//! contract instead, and recipients withdraw it with [`claim`] once they are
+//! ready to receive the asset.
+use soroban_sdk::{contracttype, token, Address, Env};
+
+use crate::{events, pausable, ttl};
+
+#[contracttype]
+enum DataKey {
+    /// Amount of an asset a recipient may claim, by recipient and asset
+    Claimable(Address, Address),
+}
+
+/// Returns what `user` may claim in `asset`.
+pub fn balance(env: &Env, user: &Address, asset: &Address) -> i128 {
+    ttl::get(env, &DataKey::Claimable(user.clone(), asset.clone())).unwrap_or(0)
+}
+
+/// Adds `amount` of `asset`, already held by the current contract, to
+/// `to`'s claimable balance. Zero amounts are skipped.
+pub fn credit(env: &Env, to: &Address, asset: &Address, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    let balance = balance(env, to, asset) + amount;
+    ttl::set(
+        env,
+        &DataKey::Claimable(to.clone(), asset.clone()),
+        &balance,
+    );
+    events::emit_payout_credited(env, to, asset, amount, balance);
+}
+
+/// Transfers `user`'s whole claimable balance in `asset` to them and
+/// returns the amount. Requires `user`'s auth; claiming nothing is a no-op.
+///
+/// # Panics
+/// With `ContractPaused` if the contract is paused
+pub fn claim(env: &Env, user: &Address, asset: &Address) -> i128 {
+    pausable::require_not_paused(env);
+    user.require_auth();
+    let amount = balance(env, user, asset);
+    if amount == 0 {
+        return 0;
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Claimable(user.clone(), asset.clone()));
+    token::Client::new(env, asset).transfer(&env.current_contract_address(), user, &amount);
+    events::emit_payout_claimed(env, user, asset, amount);
+    amount
+}
@@ -1,13 +1,381 @@
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, Env,
+    Map, String, Vec,
+};
+
+use crate::admin::{self, KeyRole, Roles};
+use crate::errors::CommonError;
+use crate::rbac::{self, Role};
+use crate::reputation::{self, Outcome};
+use crate::{claimable, events, pausable, treasury, ttl};
+
+/// Maximum number of disputes in one linked group.
+pub const MAX_GROUP_SIZE: u32 = 20;
+
+/// Ledgers parties have to submit evidence when no window is configured
+/// (about a week).
+pub const DEFAULT_EVIDENCE_WINDOW: u32 = 7 * 17_280;
+
+/// Maximum number of evidence entries one party may file for a dispute,
+/// across both rounds.
+pub const MAX_EVIDENCE_PER_PARTY: u32 = 20;
+
+/// Evidence round numbers: the initial window, then the optional rebuttal
+/// round that follows it.
+pub const INITIAL_ROUND: u32 = 1;
+pub const REBUTTAL_ROUND: u32 = 2;
+
+/// Bounds on the number of arbiters drawn for an appeal panel.
+pub const MIN_PANEL_SIZE: u32 = 3;
+pub const MAX_PANEL_SIZE: u32 = 5;
+
+/// Denominator for basis-point rates.
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Number of dispute ids held by each page of a party's dispute index.
+pub const PARTY_INDEX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of disputes returned by one call to
+/// `list_disputes_by_party`.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Staking rules for the arbiter registry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ArbiterConfig {
+    /// Token arbiters stake their bond in (USDC)
+    pub token: Address,
+    /// Minimum bond required to register
+    pub min_stake: i128,
+    /// Share of the stake slashed per offence, in basis points
+    pub slash_bps: u32,
+    /// Ledgers an assigned arbiter has to rule once evidence closes
+    pub ruling_period: u32,
+}
+
+/// A registered arbiter and its remaining bond.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Arbiter {
+    pub stake: i128,
+    /// Set once the admin approves the arbiter; only approved arbiters rule
+    pub approved: bool,
+}
+
+/// Rules for appealing a ruling to a panel of registered arbiters.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AppealConfig {
+    /// Ledgers after a ruling during which it may be appealed
+    pub period: u32,
+    /// Fee the appellant pays in the registry token; refunded if the ruling
+    /// is overturned, otherwise forfeited to the treasury
+    pub fee: i128,
+    /// Number of arbiters on the panel (`MIN_PANEL_SIZE`..=`MAX_PANEL_SIZE`)
+    pub panel_size: u32,
+}
+
+/// An appeal against a ruling and the panel reviewing it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Appeal {
+    pub appellant: Address,
+    pub fee: i128,
+    /// Split of the ruling under appeal
+    pub original_split: u32,
+    /// Voting weight of each panelist, in multiples of the minimum stake
+    pub weights: Map<Address, u32>,
+    /// Split proposed by each panelist who has voted
+    pub votes: Map<Address, u32>,
+    pub resolved: bool,
+}
+
+/// Related disputes an arbiter rules on together.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputeGroup {
+    pub arbiter: Address,
+    pub disputes: Vec<String>,
+    pub resolved: bool,
+}
 
 /// Represents the state of a dispute in the resolution process.
-#[derive(Clone, Copy)]
+///
+/// Disputes only move forward: Active → EvidenceClosed → Resolved. A ruling
+/// may be appealed once, moving it to Appealed until the panel resolves it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum DisputeState {
-    /// Dispute is actively being reviewed
+    /// Dispute is actively being reviewed and accepts evidence
     Active = 1,
-    /// Dispute has been resolved with a final judgment
-    Resolved = 2,
+    /// Evidence is closed; the dispute awaits a ruling
+    EvidenceClosed = 2,
+    /// Dispute has been ruled on; the ruling is final once its appeal
+    /// period passes
+    Resolved = 3,
+    /// The ruling is under review by an appeal panel
+    Appealed = 4,
+}
+
+/// A dispute as stored by the court.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Dispute {
+    pub disputer: Address,
+    pub state: DisputeState,
+    /// Split awarded to the freelancer, once resolved
+    pub split_ratio: Option<u32>,
+    pub opened_ledger: u32,
+    /// Last ledger of the initial evidence round
+    pub evidence_deadline: u32,
+    /// Last ledger of the rebuttal round that follows it; equal to
+    /// `evidence_deadline` when the dispute has no rebuttal round
+    pub rebuttal_deadline: u32,
+    /// Ledger of the latest ruling; the appeal period runs from here
+    pub ruled_ledger: u32,
+    /// Arbiter assigned from the registry, who closes evidence and must rule
+    /// by `ruling_deadline`
+    pub arbiter: Option<Address>,
+    pub ruling_deadline: u32,
+    /// Last ledger the respondent has to answer by filing evidence or
+    /// offering a settlement before the disputer can claim default judgment
+    pub response_deadline: u32,
+    /// Set once the respondent has offered a settlement
+    pub responded: bool,
+    /// Set when the dispute was resolved without an arbiter, by agreement or
+    /// default judgment; such outcomes cannot be appealed
+    pub settled: bool,
+    /// Filing fee the disputer deposited, held until the outcome is final
+    pub filing_fee: i128,
+}
+
+/// The fields of a dispute a listing needs, keyed by its id.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputeSummary {
+    pub dispute_id: String,
+    pub disputer: Address,
+    pub state: DisputeState,
+    pub split_ratio: Option<u32>,
+    pub opened_ledger: u32,
+}
+
+/// One piece of evidence a party has filed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EvidenceEntry {
+    /// IPFS hash or cryptographic hash of the evidence
+    pub hash: String,
+    pub submitted_ledger: u32,
+    /// `INITIAL_ROUND` or `REBUTTAL_ROUND`
+    pub round: u32,
+}
+
+/// A split one party has offered to settle a dispute on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SettlementOffer {
+    pub proposer: Address,
+    pub split_ratio: u32,
+}
+
+/// Funds the court holds for a dispute and the parties a ruling splits them
+/// between.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputeTerms {
+    pub client: Address,
+    pub freelancer: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// How a jury resolves a weighted median that falls exactly between two splits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum TiePolicy {
+    /// Take the lower of the two middle splits (favours the client)
+    Lower = 0,
+    /// Take the higher of the two middle splits (favours the freelancer)
+    Upper = 1,
+    /// Take the midpoint of the two middle splits, rounded down
+    Midpoint = 2,
+}
+
+/// A weighted jury convened for a single dispute.
+#[derive(Clone)]
+#[contracttype]
+pub struct Jury {
+    /// Voting weight of each juror
+    pub weights: Map<Address, u32>,
+    /// Split proposed by each juror who has voted
+    pub votes: Map<Address, u32>,
+    /// Amount at stake in the dispute
+    pub amount: i128,
+    /// Set when `amount` reaches the unanimity threshold: every juror must
+    /// propose the same split, and jurors may revise their proposals
+    pub unanimous: bool,
+    /// Last ledger on which jurors may vote
+    pub deadline: u32,
+    pub resolved: bool,
+    /// Set when the jury failed to resolve by the deadline
+    pub escalated: bool,
+}
+
+/// Errors raised by the dispute court.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum DisputeError {
+    /// The split ratio is outside 0-100
+    InvalidSplitRatio = 100,
+    /// No dispute with this id
+    DisputeNotFound = 101,
+    /// A dispute with this id already exists
+    DisputeExists = 102,
+    /// The dispute or dispute group has already been ruled on or settled
+    AlreadyResolved = 103,
+    /// The dispute has not been ruled on yet
+    NotResolved = 104,
+    /// The arbiter is not approved
+    UnauthorizedArbiter = 105,
+    /// The dispute is assigned to another arbiter, or the respondent has
+    /// answered and it must be ruled on by one
+    AssignedElsewhere = 106,
+    /// The caller is not a party to the dispute
+    NotParty = 107,
+    /// An amount is not positive, a fee is negative or a rate exceeds 100%
+    InvalidAmount = 108,
+    /// Funds were already deposited for the dispute
+    AlreadyFunded = 109,
+    /// No funds are held for the dispute
+    NoFunds = 110,
+    /// There is no settlement offer to accept
+    NoSettlementOffer = 111,
+    /// The proposer cannot accept their own offer
+    OwnOffer = 112,
+    /// The evidence rounds have closed or the party has filed the maximum
+    /// number of entries
+    EvidenceClosed = 113,
+    /// The evidence window, rebuttal round or response window is still open
+    EvidenceOpen = 114,
+    /// The arbiter registry is not configured
+    ArbitersNotConfigured = 115,
+    /// The arbiter or appeal configuration is invalid
+    InvalidArbiterConfig = 116,
+    /// The arbiter is already registered
+    ArbiterRegistered = 117,
+    /// The arbiter is not registered
+    ArbiterNotRegistered = 118,
+    /// The stake is below the minimum
+    StakeTooLow = 119,
+    /// No ruling is pending from the arbiter
+    NoRulingPending = 120,
+    /// The ruling deadline has not passed yet
+    RulingDeadlineNotPassed = 121,
+    /// The treasury role is not set
+    TreasuryNotSet = 122,
+    /// Appeals are not enabled
+    AppealsDisabled = 123,
+    /// The ruling was already appealed
+    AlreadyAppealed = 125,
+    /// No appeal exists for the dispute
+    AppealNotFound = 127,
+    /// The appeal was already resolved
+    AppealResolved = 128,
+    /// The appeal period has ended
+    AppealPeriodEnded = 129,
+    /// The appeal period is still open
+    AppealPeriodOpen = 130,
+    /// Too few approved arbiters to draw a panel
+    NotEnoughArbiters = 131,
+    /// The caller is not on the appeal panel
+    NotPanelist = 132,
+    /// The voter has already voted
+    AlreadyVoted = 133,
+    /// The dispute group is empty or too large
+    InvalidGroupSize = 135,
+    /// The dispute already belongs to a group
+    AlreadyLinked = 136,
+    /// No dispute group with this id
+    GroupNotFound = 137,
+    /// The caller is not the group's arbiter
+    NotGroupArbiter = 139,
+    /// A jury was already convened
+    JuryConvened = 140,
+    /// The jury is empty
+    EmptyJury = 141,
+    /// A juror weight is zero
+    ZeroJurorWeight = 142,
+    /// No jury was convened for the dispute
+    JuryNotFound = 143,
+    /// The caller is not a juror for the dispute
+    NotJuror = 144,
+    /// The voting period has ended
+    VotingClosed = 145,
+    /// The voting period is still open
+    VotingOpen = 146,
+    /// Not every panelist or juror has voted
+    VotesMissing = 147,
+    /// The jurors have not reached the unanimity threshold
+    NoUnanimity = 148,
+    /// No votes carry any weight
+    NoWeightedVotes = 149,
+    /// A party has not posted its bond
+    BondMissing = 150,
+    /// The party's bond was already posted
+    BondPosted = 151,
+    /// No bond is due on the dispute
+    NoBondDue = 152,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Dispute(String),
+    Terms(String),
+    FilingFee,
+    /// Bond each party posts before arbitration, in basis points of the
+    /// deposited funds
+    BondBps,
+    /// Bond a party has posted on a dispute
+    Bond(String, Address),
+    /// Platform fee on the freelancer's share of payouts, in basis points
+    PlatformFeeBps,
+    /// Reputation contract missed ruling deadlines are reported to
+    Reputation,
+    /// Latest settlement offer on a dispute
+    SettlementOffer(String),
+    EvidenceWindow,
+    /// Ledgers the rebuttal round runs for after the evidence window
+    RebuttalWindow,
+    /// Ledgers the respondent has to answer a dispute
+    ResponseWindow,
+    ArbiterConfig,
+    Arbiter(Address),
+    /// Approved arbiters eligible for appeal panels
+    ApprovedArbiters,
+    /// Position in `ApprovedArbiters` the next assignment starts from
+    NextArbiter,
+    AppealConfig,
+    Appeal(String),
+    TiePolicy,
+    Jury(String),
+    /// Disputed amount at or above which jurors must agree unanimously
+    UnanimityThreshold,
+    /// Evidence entries filed by one party to a dispute, in order
+    Evidence(String, Address),
+    /// Parties that have filed evidence for a dispute, in order of first filing
+    EvidenceSubmitters(String),
+    GroupCount,
+    Group(u64),
+    /// Group a dispute has been linked into
+    DisputeGroupOf(String),
+    /// One page of the disputes an address has opened or is a funded party
+    /// to, oldest first, by address and page number
+    PartyDisputes(Address, u32),
+    /// Number of disputes an address has opened or is a funded party to
+    PartyDisputeCount(Address),
 }
 
 /// DisputeResolutionCourt contract for handling payment disputes in escrow scenarios.
@@ -16,11 +384,115 @@ pub enum DisputeState {
 /// - Initiating disputes on milestone/escrow payments
 /// - Submitting evidence (IPFS hashes) from parties
 /// - Arbiter adjudication with configurable fund splits
+/// - Weighted jury resolution using the median proposed split
 #[contract]
 pub struct DisputeResolutionCourt;
 
 #[contractimpl]
 impl DisputeResolutionCourt {
+    /// Initializes the contract with its admin.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage the contract's configuration
+    ///
+    /// # Panics
+    /// If the contract has already been initialized
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin.
+    ///
+    /// Ownership only moves once `new_admin` calls `accept_admin`.
+    ///
+    /// # Panics
+    /// If the current admin has not authorized the call
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer.
+    ///
+    /// # Panics
+    /// If `new_admin` is not the proposed successor
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Assigns a key role (veto, recovery, fee collector, treasury).
+    ///
+    /// # Panics
+    /// If the current admin has not authorized the call
+    pub fn set_role(env: Env, role: KeyRole, address: Address) {
+        admin::set_role(&env, role, &address);
+    }
+
+    /// Returns the admin and every key role in a single read.
+    pub fn get_roles(env: Env) -> Roles {
+        admin::get_roles(&env)
+    }
+
+    /// Grants `role` to `account`. `caller` must be the role admin.
+    pub fn grant_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::grant_role(&env, &caller, role, &account);
+    }
+
+    /// Revokes `role` from `account`. `caller` must be the role admin.
+    pub fn revoke_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::revoke_role(&env, &caller, role, &account);
+    }
+
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        rbac::has_role(&env, role, &account)
+    }
+
+    /// Returns the account that grants and revokes roles.
+    pub fn role_admin(env: Env) -> Address {
+        rbac::role_admin(&env)
+    }
+
+    /// Hands role administration to `new_admin`, e.g. the multisig
+    /// governance contract. `caller` must be the current role admin.
+    pub fn transfer_role_admin(env: Env, caller: Address, new_admin: Address) {
+        rbac::transfer_role_admin(&env, &caller, &new_admin);
+    }
+
+    /// Extends the TTL of each persistent entry in `keys` that is running
+    /// low, so idle disputes are not archived. Meant for keepers; anyone may
+    /// call it. Returns how many of the entries exist.
+    pub fn bump_entries(env: Env, keys: Vec<DataKey>) -> u32 {
+        ttl::bump_all(&env, &keys)
+    }
+
+    /// Pauses every state-changing entrypoint.
+    ///
+    /// # Panics
+    /// If `caller` is neither the contract admin nor a guardian, or has not
+    /// authorized the call
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause.
+    ///
+    /// # Panics
+    /// If `caller` is neither the contract admin nor a guardian, or has not
+    /// authorized the call
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
     /// Initiates a dispute for a specific milestone/escrow payment.
     ///
     /// # Arguments
@@ -32,19 +504,58 @@ impl DisputeResolutionCourt {
     /// `true` if the dispute was successfully initiated
     ///
     /// # Panics
-    /// If the disputer is not authorized to initiate disputes
+    /// If the disputer is not authorized to initiate disputes or cannot pay
+    /// the filing fee, or a dispute already exists for `escrow_id`
     pub fn initiate_dispute(env: Env, escrow_id: String, disputer: Address) -> bool {
+        pausable::require_not_paused(&env);
         disputer.require_auth();
 
-        env.events().publish(
-            (String::from_str(&env, "dispute_started"), escrow_id),
-            disputer,
+        let key = DataKey::Dispute(escrow_id.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, DisputeError::DisputeExists);
+        }
+        let filing_fee = Self::filing_fee(env.clone());
+        if filing_fee > 0 {
+            token::Client::new(&env, &Self::arbiter_config(&env).token).transfer(
+                &disputer,
+                &env.current_contract_address(),
+                &filing_fee,
+            );
+        }
+        let evidence_deadline = env.ledger().sequence() + Self::evidence_window(env.clone());
+        let arbiter = Self::next_arbiter(&env, None);
+        ttl::set(
+            &env,
+            &key,
+            &Dispute {
+                disputer: disputer.clone(),
+                state: DisputeState::Active,
+                split_ratio: None,
+                opened_ledger: env.ledger().sequence(),
+                evidence_deadline,
+                rebuttal_deadline: evidence_deadline + Self::rebuttal_window(env.clone()),
+                ruled_ledger: 0,
+                arbiter: arbiter.clone(),
+                ruling_deadline: 0,
+                response_deadline: env.ledger().sequence() + Self::response_window(env.clone()),
+                responded: false,
+                settled: false,
+                filing_fee,
+            },
         );
 
+        Self::index_dispute(&env, &disputer, &escrow_id);
+        events::emit_dispute_started(&env, &escrow_id, &disputer);
+        if let Some(arbiter) = arbiter {
+            events::emit_arbiter_assigned(&env, &escrow_id, &arbiter);
+        }
+
         true
     }
 
-    /// Submits evidence (e.g., IPFS hash) for an ongoing dispute.
+    /// Submits evidence (e.g., IPFS hash) for an ongoing dispute. Entries
+    /// filed after the evidence window, while the rebuttal round is open,
+    /// are recorded as `REBUTTAL_ROUND`.
     ///
     /// # Arguments
     /// * `env` - The Soroban environment
@@ -53,41 +564,3182 @@ impl DisputeResolutionCourt {
     /// * `submitter` - Address of the party submitting evidence
     ///
     /// # Panics
-    /// If the submitter is not authorized
+    /// If the submitter is not authorized, the dispute does not exist or is
+    /// not active, its rebuttal round has closed, or the submitter has
+    /// already filed `MAX_EVIDENCE_PER_PARTY` entries
     pub fn submit_evidence(
         env: Env,
         dispute_id: String,
         evidence_hash: String,
         submitter: Address,
     ) {
+        pausable::require_not_paused(&env);
         submitter.require_auth();
 
-        env.events().publish(
-            (String::from_str(&env, "evidence_submitted"), dispute_id),
-            evidence_hash,
+        let dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        if dispute.state != DisputeState::Active {
+            panic_with_error!(env, DisputeError::EvidenceClosed);
+        }
+        let ledger = env.ledger().sequence();
+        if ledger > dispute.rebuttal_deadline {
+            panic_with_error!(env, DisputeError::EvidenceClosed);
+        }
+        let round = if ledger > dispute.evidence_deadline {
+            REBUTTAL_ROUND
+        } else {
+            INITIAL_ROUND
+        };
+
+        let mut evidence =
+            Self::get_party_evidence(env.clone(), dispute_id.clone(), submitter.clone());
+        if evidence.len() >= MAX_EVIDENCE_PER_PARTY {
+            panic_with_error!(env, DisputeError::EvidenceClosed);
+        }
+        if evidence.is_empty() {
+            let key = DataKey::EvidenceSubmitters(dispute_id.clone());
+            let mut submitters: Vec<Address> =
+                ttl::get(&env, &key).unwrap_or_else(|| Vec::new(&env));
+            submitters.push_back(submitter.clone());
+            ttl::set(&env, &key, &submitters);
+        }
+        evidence.push_back(EvidenceEntry {
+            hash: evidence_hash.clone(),
+            submitted_ledger: ledger,
+            round,
+        });
+        ttl::set(
+            &env,
+            &DataKey::Evidence(dispute_id.clone(), submitter.clone()),
+            &evidence,
         );
+
+        events::emit_evidence_submitted(&env, &dispute_id, &submitter, &evidence_hash, round);
     }
 
-    /// Adjudicates a dispute and distributes funds based on the split ratio.
+    /// Returns every evidence entry filed for a dispute, keyed by party.
+    pub fn get_evidence(env: Env, dispute_id: String) -> Map<Address, Vec<EvidenceEntry>> {
+        let submitters: Vec<Address> =
+            ttl::get(&env, &DataKey::EvidenceSubmitters(dispute_id.clone()))
+                .unwrap_or_else(|| Vec::new(&env));
+        let mut evidence = Map::new(&env);
+        for submitter in submitters.iter() {
+            let entries =
+                Self::get_party_evidence(env.clone(), dispute_id.clone(), submitter.clone());
+            evidence.set(submitter, entries);
+        }
+        evidence
+    }
+
+    /// Returns every evidence entry `submitter` has filed for a dispute.
+    pub fn get_party_evidence(
+        env: Env,
+        dispute_id: String,
+        submitter: Address,
+    ) -> Vec<EvidenceEntry> {
+        ttl::get(&env, &DataKey::Evidence(dispute_id, submitter)).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns how many evidence entries `submitter` has filed for a dispute.
+    pub fn evidence_count(env: Env, dispute_id: String, submitter: Address) -> u32 {
+        Self::get_party_evidence(env, dispute_id, submitter).len()
+    }
+
+    /// Returns a stored dispute.
     ///
-    /// # Arguments
-    /// * `env` - The Soroban environment
-    /// * `dispute_id` - Unique identifier of the dispute
-    /// * `split_ratio` - Percentage (0-100) of funds to award to freelancer
-    /// * `arbiter` - Address of the authorized arbiter making the decision
+    /// # Panics
+    /// If the dispute does not exist
+    pub fn get_dispute(env: Env, dispute_id: String) -> Dispute {
+        ttl::get(&env, &DataKey::Dispute(dispute_id))
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::DisputeNotFound))
+    }
+
+    /// Returns up to `limit` summaries of the disputes `party` opened or is a
+    /// funded party to, oldest first, skipping the first `start`.
     ///
     /// # Panics
-    /// If split_ratio > 100 or arbiter is not authorized
-    pub fn adjudicate(env: Env, dispute_id: String, split_ratio: u32, arbiter: Address) {
-        arbiter.require_auth();
+    /// If `limit` exceeds `MAX_PAGE_SIZE`
+    pub fn list_disputes_by_party(
+        env: Env,
+        party: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<DisputeSummary> {
+        if limit > MAX_PAGE_SIZE {
+            panic_with_error!(env, CommonError::BatchTooLarge);
+        }
+        let end = Self::count_disputes_by_party(env.clone(), party.clone())
+            .min(start.saturating_add(limit));
+        let mut summaries = Vec::new(&env);
+        let mut page = Vec::new(&env);
+        for i in start..end {
+            if i == start || i % PARTY_INDEX_PAGE_SIZE == 0 {
+                page = Self::party_disputes(&env, &party, i / PARTY_INDEX_PAGE_SIZE);
+            }
+            let dispute_id = page.get_unchecked(i % PARTY_INDEX_PAGE_SIZE);
+            let dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+            summaries.push_back(DisputeSummary {
+                dispute_id,
+                disputer: dispute.disputer,
+                state: dispute.state,
+                split_ratio: dispute.split_ratio,
+                opened_ledger: dispute.opened_ledger,
+            });
+        }
+        summaries
+    }
+
+    /// Returns how many disputes `party` opened or is a funded party to.
+    pub fn count_disputes_by_party(env: Env, party: Address) -> u32 {
+        ttl::get(&env, &DataKey::PartyDisputeCount(party)).unwrap_or(0)
+    }
+
+    /// Deposits the disputed escrow balance with the court. The ruling pays
+    /// `split_ratio`% of it to the freelancer and the rest to the client.
+    /// `funder` must be one of the two parties, the disputer must be the
+    /// other or the same one, and both parties must authorize the terms.
+    ///
+    /// # Panics
+    /// If either party has not authorized the call, `funder` or the disputer
+    /// is not a party to `terms`, `amount` is not positive, the dispute does
+    /// not exist or is resolved, or funds are already deposited
+    pub fn deposit_funds(env: Env, dispute_id: String, funder: Address, terms: DisputeTerms) {
+        pausable::require_not_paused(&env);
+        let counterparty = if funder == terms.client {
+            &terms.freelancer
+        } else if funder == terms.freelancer {
+            &terms.client
+        } else {
+            panic_with_error!(env, DisputeError::NotParty)
+        };
+        funder.require_auth();
+        if *counterparty != funder {
+            counterparty.require_auth();
+        }
+
+        if terms.amount <= 0 {
+            panic_with_error!(env, DisputeError::InvalidAmount);
+        }
+        let dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        if Self::is_ruled(&dispute) {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+        if dispute.disputer != terms.client && dispute.disputer != terms.freelancer {
+            panic_with_error!(env, DisputeError::NotParty);
+        }
+        let key = DataKey::Terms(dispute_id.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, DisputeError::AlreadyFunded);
+        }
+        token::Client::new(&env, &terms.token).transfer(
+            &funder,
+            &env.current_contract_address(),
+            &terms.amount,
+        );
+        ttl::set(&env, &key, &terms);
+        // The disputer was indexed when the dispute was raised.
+        let other = if dispute.disputer == terms.client {
+            &terms.freelancer
+        } else {
+            &terms.client
+        };
+        if *other != dispute.disputer {
+            Self::index_dispute(&env, other, &dispute_id);
+        }
+    }
+
+    /// Returns the funds the court still holds for a dispute, if any.
+    pub fn get_terms(env: Env, dispute_id: String) -> Option<DisputeTerms> {
+        ttl::get(&env, &DataKey::Terms(dispute_id))
+    }
+
+    /// Posts `party`'s bond, the configured share of the deposited funds in
+    /// their token. Bonds are due by the end of the rebuttal round; if only
+    /// one party posts by then, `resolve_bond_default` rules for that party.
+    ///
+    /// # Panics
+    /// If the party is not authorized or is not a party to the deposited
+    /// funds, no bond is due, the party has already posted, or the rebuttal
+    /// round has closed
+    pub fn post_bond(env: Env, dispute_id: String, party: Address) {
+        pausable::require_not_paused(&env);
+        party.require_auth();
+        Self::require_party(&env, &dispute_id, &party);
+        let dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        if dispute.state != DisputeState::Active
+            || env.ledger().sequence() > dispute.rebuttal_deadline
+        {
+            panic_with_error!(env, DisputeError::EvidenceClosed);
+        }
+        let terms = Self::get_terms(env.clone(), dispute_id.clone()).unwrap();
+        let amount = Self::bond_amount(&env, &terms);
+        if amount == 0 {
+            panic_with_error!(env, DisputeError::NoBondDue);
+        }
+        let key = DataKey::Bond(dispute_id.clone(), party.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, DisputeError::BondPosted);
+        }
+        token::Client::new(&env, &terms.token).transfer(
+            &party,
+            &env.current_contract_address(),
+            &amount,
+        );
+        ttl::set(&env, &key, &amount);
+        events::emit_bond_posted(&env, &dispute_id, &party, amount);
+    }
+
+    /// Returns the bond `party` has posted on a dispute (zero if none).
+    pub fn get_bond(env: Env, dispute_id: String, party: Address) -> i128 {
+        ttl::get(&env, &DataKey::Bond(dispute_id, party)).unwrap_or(0)
+    }
+
+    /// Resolves a dispute fully in favour of the only party that posted its
+    /// bond once the rebuttal round has closed, so a party cannot stall
+    /// arbitration by withholding theirs. The posted bond is returned and
+    /// the funds are paid out at once. Anyone may call this.
+    ///
+    /// # Panics
+    /// If the dispute is resolved, no funds or no bond are due, the rebuttal
+    /// round is still open, or both or neither party posted
+    pub fn resolve_bond_default(env: Env, dispute_id: String) {
+        pausable::require_not_paused(&env);
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        if Self::is_ruled(&dispute) {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+        let terms = Self::get_terms(env.clone(), dispute_id.clone())
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::NoFunds));
+        if Self::bond_amount(&env, &terms) == 0 {
+            panic_with_error!(env, DisputeError::NoBondDue);
+        }
+        if env.ledger().sequence() <= dispute.rebuttal_deadline {
+            panic_with_error!(env, DisputeError::EvidenceOpen);
+        }
+        let bonded =
+            |party: &Address| Self::get_bond(env.clone(), dispute_id.clone(), party.clone()) > 0;
+        let (claimant, split_ratio) = match (bonded(&terms.client), bonded(&terms.freelancer)) {
+            (true, false) => (terms.client.clone(), 0),
+            (false, true) => (terms.freelancer.clone(), 100),
+            (true, true) => panic_with_error!(env, DisputeError::BondPosted),
+            (false, false) => panic_with_error!(env, DisputeError::BondMissing),
+        };
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SettlementOffer(dispute_id.clone()));
+        dispute.state = DisputeState::Resolved;
+        dispute.split_ratio = Some(split_ratio);
+        dispute.ruled_ledger = env.ledger().sequence();
+        dispute.settled = true;
+        ttl::set(&env, &DataKey::Dispute(dispute_id.clone()), &dispute);
+
+        events::emit_default_judgment(&env, &dispute_id, &claimant);
+        events::emit_dispute_resolved(&env, &dispute_id, split_ratio);
+        Self::pay_out(&env, &dispute_id, split_ratio).unwrap_or_else(|e| panic_with_error!(env, e));
+    }
+
+    /// Offers to settle a dispute on `split_ratio`, replacing any earlier
+    /// offer. The other party may accept it until the dispute is ruled on.
+    ///
+    /// # Panics
+    /// If the proposer is not authorized or is not the client or freelancer
+    /// of the deposited funds, `split_ratio` > 100, or the dispute is resolved
+    pub fn propose_settlement(env: Env, dispute_id: String, split_ratio: u32, proposer: Address) {
+        pausable::require_not_paused(&env);
+        proposer.require_auth();
 
         if split_ratio > 100 {
-            panic!("split_ratio must be 0-100");
+            panic_with_error!(env, DisputeError::InvalidSplitRatio);
+        }
+        if Self::is_ruled(&Self::get_dispute(env.clone(), dispute_id.clone())) {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+        Self::require_party(&env, &dispute_id, &proposer);
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        if proposer != dispute.disputer && !dispute.responded {
+            dispute.responded = true;
+            ttl::set(&env, &DataKey::Dispute(dispute_id.clone()), &dispute);
+        }
+        ttl::set(
+            &env,
+            &DataKey::SettlementOffer(dispute_id),
+            &SettlementOffer {
+                proposer,
+                split_ratio,
+            },
+        );
+    }
+
+    /// Accepts the other party's settlement offer. The dispute resolves on
+    /// the offered split and the deposited funds are paid out at once; no
+    /// arbiter is involved and the outcome cannot be appealed.
+    ///
+    /// # Panics
+    /// If the acceptor is not authorized, is not a party or made the offer,
+    /// no offer exists, or the dispute is resolved
+    pub fn accept_settlement(env: Env, dispute_id: String, acceptor: Address) {
+        pausable::require_not_paused(&env);
+        acceptor.require_auth();
+
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        if Self::is_ruled(&dispute) {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+        Self::require_party(&env, &dispute_id, &acceptor);
+        let offer = Self::get_settlement_offer(env.clone(), dispute_id.clone())
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::NoSettlementOffer));
+        if offer.proposer == acceptor {
+            panic_with_error!(env, DisputeError::OwnOffer);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SettlementOffer(dispute_id.clone()));
+        dispute.state = DisputeState::Resolved;
+        dispute.split_ratio = Some(offer.split_ratio);
+        dispute.ruled_ledger = env.ledger().sequence();
+        dispute.settled = true;
+        ttl::set(&env, &DataKey::Dispute(dispute_id.clone()), &dispute);
+
+        events::emit_dispute_resolved(&env, &dispute_id, offer.split_ratio);
+        Self::pay_out(&env, &dispute_id, offer.split_ratio)
+            .unwrap_or_else(|e| panic_with_error!(env, e));
+    }
+
+    /// Resolves a dispute fully in the disputer's favour when the other party
+    /// to the deposited funds let the response window pass without filing
+    /// evidence or offering a settlement. The funds are paid out at once; no
+    /// arbiter is involved and the outcome cannot be appealed.
+    ///
+    /// # Panics
+    /// If the disputer is not authorized or is not a party to the deposited
+    /// funds, the dispute is resolved, the response window is still open, or
+    /// the respondent has answered
+    pub fn claim_default_judgment(env: Env, dispute_id: String) {
+        pausable::require_not_paused(&env);
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        dispute.disputer.require_auth();
+        if Self::is_ruled(&dispute) {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+        let terms = Self::get_terms(env.clone(), dispute_id.clone())
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::NoFunds));
+        let (respondent, split_ratio) = if dispute.disputer == terms.freelancer {
+            (terms.client, 100)
+        } else if dispute.disputer == terms.client {
+            (terms.freelancer, 0)
+        } else {
+            panic_with_error!(env, DisputeError::NotParty)
+        };
+        if env.ledger().sequence() <= dispute.response_deadline {
+            panic_with_error!(env, DisputeError::EvidenceOpen);
+        }
+        if dispute.responded
+            || Self::evidence_count(env.clone(), dispute_id.clone(), respondent) > 0
+        {
+            panic_with_error!(env, DisputeError::AssignedElsewhere);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SettlementOffer(dispute_id.clone()));
+        dispute.state = DisputeState::Resolved;
+        dispute.split_ratio = Some(split_ratio);
+        dispute.ruled_ledger = env.ledger().sequence();
+        dispute.settled = true;
+        ttl::set(&env, &DataKey::Dispute(dispute_id.clone()), &dispute);
+
+        events::emit_default_judgment(&env, &dispute_id, &dispute.disputer);
+        events::emit_dispute_resolved(&env, &dispute_id, split_ratio);
+        Self::pay_out(&env, &dispute_id, split_ratio).unwrap_or_else(|e| panic_with_error!(env, e));
+    }
+
+    /// Withdraws everything dispute payouts and filing-fee refunds have
+    /// credited to `recipient` in `asset`, and returns the amount.
+    pub fn claim(env: Env, recipient: Address, asset: Address) -> i128 {
+        claimable::claim(&env, &recipient, &asset)
+    }
+
+    /// Returns what `user` may claim in `asset`.
+    pub fn claimable_balance(env: Env, user: Address, asset: Address) -> i128 {
+        claimable::balance(&env, &user, &asset)
+    }
+
+    /// Returns the open settlement offer on a dispute, if any.
+    pub fn get_settlement_offer(env: Env, dispute_id: String) -> Option<SettlementOffer> {
+        ttl::get(&env, &DataKey::SettlementOffer(dispute_id))
+    }
+
+    /// Sets the fee, in the registry token, a disputer deposits to open a
+    /// dispute. Once the outcome is final the disputer is refunded in
+    /// proportion to the share they won and the rest goes to the treasury.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or `fee` is negative
+    pub fn set_filing_fee(env: Env, admin: Address, fee: i128) {
+        admin::require_admin_caller(&env, &admin);
+        if fee < 0 {
+            panic_with_error!(env, DisputeError::InvalidAmount);
+        }
+        env.storage().instance().set(&DataKey::FilingFee, &fee);
+    }
+
+    /// Returns the filing fee (zero by default).
+    pub fn filing_fee(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::FilingFee)
+            .unwrap_or(0)
+    }
+
+    /// Sets the bond, in basis points of the deposited funds, that both
+    /// parties post before arbitration. An arbiter's ruling forfeits the
+    /// bond of the party awarded the smaller share to the arbiter, or to the
+    /// appeal panel if it overturns the ruling; both bonds are returned on an
+    /// even split or a settlement. A party that alone fails to post loses by
+    /// default; if neither posts, arbitration goes ahead without bonds. Zero
+    /// disables bonds.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or `bond_bps` exceeds 100%
+    pub fn set_bond_rate(env: Env, admin: Address, bond_bps: u32) {
+        admin::require_admin_caller(&env, &admin);
+        if bond_bps as i128 > BPS_DENOMINATOR {
+            panic_with_error!(env, DisputeError::InvalidAmount);
+        }
+        env.storage().instance().set(&DataKey::BondBps, &bond_bps);
+    }
+
+    /// Returns the bond rate in basis points (zero by default).
+    pub fn bond_rate(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::BondBps).unwrap_or(0)
+    }
+
+    /// Sets the platform fee deducted from the freelancer's share of every
+    /// payout of deposited funds and deposited with the treasury contract.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or the rate exceeds 100%
+    pub fn set_platform_fee(env: Env, admin: Address, fee_bps: u32) {
+        admin::require_admin_caller(&env, &admin);
+        if fee_bps as i128 > BPS_DENOMINATOR {
+            panic_with_error!(env, DisputeError::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::PlatformFeeBps, &fee_bps);
+    }
+
+    /// Registers the reputation contract that arbiters' missed ruling
+    /// deadlines are reported to. This contract must be one of its
+    /// reporters; failed reports are ignored.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_reputation(env: Env, admin: Address, reputation: Address) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::Reputation, &reputation);
+    }
+
+    /// Returns the registered reputation contract, if any.
+    pub fn reputation(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Reputation)
+    }
+
+    /// Returns the platform fee rate in basis points (zero by default).
+    pub fn platform_fee(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::PlatformFeeBps)
+            .unwrap_or(0)
+    }
+
+    /// Sets how many ledgers parties have to submit evidence. Applies to
+    /// disputes opened afterwards.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or has not authorized the call
+    pub fn set_evidence_window(env: Env, admin: Address, ledgers: u32) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::EvidenceWindow, &ledgers);
+    }
+
+    /// Returns the evidence window (`DEFAULT_EVIDENCE_WINDOW` by default).
+    pub fn evidence_window(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::EvidenceWindow)
+            .unwrap_or(DEFAULT_EVIDENCE_WINDOW)
+    }
+
+    /// Sets how many ledgers the rebuttal round runs for once the evidence
+    /// window ends; zero disables it. Applies to disputes opened afterwards.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or has not authorized the call
+    pub fn set_rebuttal_window(env: Env, admin: Address, ledgers: u32) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::RebuttalWindow, &ledgers);
+    }
+
+    /// Returns the rebuttal window (zero, no rebuttal round, by default).
+    pub fn rebuttal_window(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RebuttalWindow)
+            .unwrap_or(0)
+    }
+
+    /// Sets how many ledgers the respondent has, from the dispute opening,
+    /// to file evidence or offer a settlement before the disputer may claim
+    /// default judgment. Applies to disputes opened afterwards.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or has not authorized the call
+    pub fn set_response_window(env: Env, admin: Address, ledgers: u32) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::ResponseWindow, &ledgers);
+    }
+
+    /// Returns the response window (the evidence window by default).
+    pub fn response_window(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ResponseWindow)
+            .unwrap_or_else(|| Self::evidence_window(env.clone()))
+    }
+
+    /// Configures the arbiter registry.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin, `min_stake` is not positive or
+    /// `slash_bps` exceeds 100%
+    pub fn configure_arbiters(env: Env, admin: Address, config: ArbiterConfig) {
+        admin::require_admin_caller(&env, &admin);
+        if config.min_stake <= 0 || config.slash_bps as i128 > BPS_DENOMINATOR {
+            panic_with_error!(env, DisputeError::InvalidArbiterConfig);
         }
+        env.storage()
+            .instance()
+            .set(&DataKey::ArbiterConfig, &config);
+    }
+
+    /// Registers `arbiter` by staking `stake` of the registry token. The
+    /// arbiter may rule once the admin approves it.
+    ///
+    /// # Panics
+    /// If the registry is not configured, `stake` is below the minimum or the
+    /// arbiter is already registered
+    pub fn register_arbiter(env: Env, arbiter: Address, stake: i128) {
+        pausable::require_not_paused(&env);
+        arbiter.require_auth();
 
-        env.events().publish(
-            (String::from_str(&env, "dispute_resolved"), dispute_id),
-            split_ratio,
+        let config = Self::arbiter_config(&env);
+        if stake < config.min_stake {
+            panic_with_error!(env, DisputeError::StakeTooLow);
+        }
+        let key = DataKey::Arbiter(arbiter.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, DisputeError::ArbiterRegistered);
+        }
+        token::Client::new(&env, &config.token).transfer(
+            &arbiter,
+            &env.current_contract_address(),
+            &stake,
+        );
+        ttl::set(
+            &env,
+            &key,
+            &Arbiter {
+                stake,
+                approved: false,
+            },
         );
     }
+
+    /// Approves a registered arbiter to rule on disputes.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or `arbiter` is not registered
+    pub fn approve_arbiter(env: Env, admin: Address, arbiter: Address) {
+        admin::require_admin_caller(&env, &admin);
+        let mut record = Self::load_arbiter(&env, &arbiter);
+        if !record.approved {
+            let mut approved = Self::approved_arbiters(&env);
+            approved.push_back(arbiter.clone());
+            ttl::set(&env, &DataKey::ApprovedArbiters, &approved);
+        }
+        record.approved = true;
+        ttl::set(&env, &DataKey::Arbiter(arbiter), &record);
+    }
+
+    /// Removes an arbiter from the registry and returns its remaining stake.
+    /// Disputes still assigned to it become vacant: any approved arbiter may
+    /// take them with `close_evidence` or `assign_arbiter`.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or `arbiter` is not registered
+    pub fn remove_arbiter(env: Env, admin: Address, arbiter: Address) {
+        admin::require_admin_caller(&env, &admin);
+        let record = Self::load_arbiter(&env, &arbiter);
+        token::Client::new(&env, &Self::arbiter_config(&env).token).transfer(
+            &env.current_contract_address(),
+            &arbiter,
+            &record.stake,
+        );
+        let mut approved = Self::approved_arbiters(&env);
+        if let Some(index) = approved.first_index_of(&arbiter) {
+            approved.remove(index);
+            ttl::set(&env, &DataKey::ApprovedArbiters, &approved);
+            // Keep the rotation on the arbiter that was next in turn.
+            let next: u32 = env
+                .storage()
+                .instance()
+                .get(&DataKey::NextArbiter)
+                .unwrap_or(0);
+            if index < next {
+                env.storage()
+                    .instance()
+                    .set(&DataKey::NextArbiter, &(next - 1));
+            }
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Arbiter(arbiter));
+    }
+
+    /// Returns a registered arbiter, if any.
+    pub fn get_arbiter(env: Env, arbiter: Address) -> Option<Arbiter> {
+        ttl::get(&env, &DataKey::Arbiter(arbiter))
+    }
+
+    /// Assigns an arbiter from the registry to a dispute that has none,
+    /// because none was approved when it opened, none was left to take over
+    /// a missed ruling or its arbiter was removed, and returns it. Arbiters
+    /// are assigned in turn, in the order they were approved. Anyone may
+    /// call this.
+    ///
+    /// # Panics
+    /// If the dispute already has an arbiter or has been ruled on, or no
+    /// arbiter is approved
+    pub fn assign_arbiter(env: Env, dispute_id: String) -> Address {
+        pausable::require_not_paused(&env);
+
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        if Self::is_ruled(&dispute) {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+        if Self::assigned_arbiter(&env, &dispute).is_some() {
+            panic_with_error!(env, DisputeError::AssignedElsewhere);
+        }
+        let arbiter = Self::next_arbiter(&env, None)
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::NotEnoughArbiters));
+        if dispute.state == DisputeState::EvidenceClosed {
+            dispute.ruling_deadline =
+                env.ledger().sequence() + Self::arbiter_config(&env).ruling_period;
+        }
+        dispute.arbiter = Some(arbiter.clone());
+        ttl::set(&env, &DataKey::Dispute(dispute_id.clone()), &dispute);
+        events::emit_arbiter_assigned(&env, &dispute_id, &arbiter);
+        arbiter
+    }
+
+    /// Slashes the assigned arbiter of a dispute that missed its ruling
+    /// deadline, reports the miss to the reputation contract and reassigns
+    /// the dispute to the next arbiter in turn, with a fresh ruling period.
+    /// If no other arbiter is approved the dispute is left for any approved
+    /// arbiter to take. An arbiter that has since been removed has no stake
+    /// left to slash. Anyone may call this.
+    ///
+    /// # Panics
+    /// If the dispute has no assigned arbiter or the deadline has not passed
+    pub fn report_missed_deadline(env: Env, dispute_id: String) {
+        pausable::require_not_paused(&env);
+
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        let arbiter = match (&dispute.state, &dispute.arbiter) {
+            (DisputeState::EvidenceClosed, Some(arbiter)) => arbiter.clone(),
+            _ => panic_with_error!(env, DisputeError::NoRulingPending),
+        };
+        if env.ledger().sequence() <= dispute.ruling_deadline {
+            panic_with_error!(env, DisputeError::RulingDeadlineNotPassed);
+        }
+
+        if Self::get_arbiter(env.clone(), arbiter.clone()).is_some() {
+            Self::slash(&env, &arbiter);
+        }
+        if let Some(reputation) = Self::reputation(env.clone()) {
+            reputation::report(&env, &reputation, &arbiter, Outcome::DeadlineMissed);
+        }
+        dispute.arbiter = Self::next_arbiter(&env, Some(&arbiter));
+        if let Some(replacement) = &dispute.arbiter {
+            dispute.ruling_deadline =
+                env.ledger().sequence() + Self::arbiter_config(&env).ruling_period;
+            events::emit_arbiter_assigned(&env, &dispute_id, replacement);
+        }
+        ttl::set(&env, &DataKey::Dispute(dispute_id), &dispute);
+    }
+
+    /// Closes evidence submission once the evidence window and any rebuttal
+    /// round have elapsed, moving the dispute to `EvidenceClosed` and
+    /// starting the ruling period of its arbiter. Only the assigned arbiter
+    /// may close evidence; an unassigned dispute is assigned to `arbiter`.
+    ///
+    /// # Panics
+    /// If the arbiter is not authorized or approved, another arbiter is
+    /// assigned, the dispute does not exist or is not active, its evidence
+    /// rounds are still open, or only one party has posted its bond, in
+    /// which case `resolve_bond_default` applies
+    pub fn close_evidence(env: Env, dispute_id: String, arbiter: Address) {
+        pausable::require_not_paused(&env);
+        arbiter.require_auth();
+        Self::require_approved_arbiter(&env, &arbiter);
+        Self::require_assignable(&env, &dispute_id, &arbiter);
+
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        if dispute.state != DisputeState::Active {
+            panic_with_error!(env, DisputeError::EvidenceClosed);
+        }
+        if env.ledger().sequence() <= dispute.rebuttal_deadline {
+            panic_with_error!(env, DisputeError::EvidenceOpen);
+        }
+        if let Some(terms) = Self::get_terms(env.clone(), dispute_id.clone()) {
+            let bonded = |party: &Address| {
+                env.storage()
+                    .persistent()
+                    .has(&DataKey::Bond(dispute_id.clone(), party.clone()))
+            };
+            if Self::bond_amount(&env, &terms) > 0
+                && bonded(&terms.client) != bonded(&terms.freelancer)
+            {
+                panic_with_error!(env, DisputeError::BondMissing);
+            }
+        }
+        dispute.state = DisputeState::EvidenceClosed;
+        dispute.ruling_deadline =
+            env.ledger().sequence() + Self::arbiter_config(&env).ruling_period;
+        dispute.arbiter = Some(arbiter);
+        ttl::set(&env, &DataKey::Dispute(dispute_id), &dispute);
+    }
+
+    /// Adjudicates a dispute and distributes funds based on the split ratio.
+    /// Only disputes whose evidence window has elapsed and been closed can
+    /// be ruled on, and only by their assigned arbiter.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dispute_id` - Unique identifier of the dispute
+    /// * `split_ratio` - Percentage (0-100) of funds to award to freelancer
+    /// * `arbiter` - Address of the authorized arbiter making the decision
+    ///
+    /// # Errors
+    /// `DisputeError::InvalidSplitRatio` if split_ratio > 100; other
+    /// `DisputeError`s if the arbiter is not approved, another arbiter is
+    /// assigned, the dispute does not exist or is not awaiting a ruling, or
+    /// a fee is due on the payout and the treasury role is not set
+    pub fn adjudicate(
+        env: Env,
+        dispute_id: String,
+        split_ratio: u32,
+        arbiter: Address,
+    ) -> Result<(), DisputeError> {
+        pausable::require_not_paused(&env);
+        arbiter.require_auth();
+        if split_ratio > 100 {
+            return Err(DisputeError::InvalidSplitRatio);
+        }
+        Self::check_approved_arbiter(&env, &arbiter)?;
+        let dispute: Dispute = ttl::get(&env, &DataKey::Dispute(dispute_id.clone()))
+            .ok_or(DisputeError::DisputeNotFound)?;
+        Self::check_assignable(&env, &dispute, &arbiter)?;
+
+        Self::record_ruling(&env, &dispute_id, split_ratio)
+    }
+
+    /// Enables appeals. While enabled, deposited funds are paid out only once
+    /// a ruling becomes final.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin, the fee is negative or the panel
+    /// size is outside `MIN_PANEL_SIZE..=MAX_PANEL_SIZE`
+    pub fn configure_appeals(env: Env, admin: Address, config: AppealConfig) {
+        admin::require_admin_caller(&env, &admin);
+        if config.fee < 0 || !(MIN_PANEL_SIZE..=MAX_PANEL_SIZE).contains(&config.panel_size) {
+            panic_with_error!(env, DisputeError::InvalidArbiterConfig);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::AppealConfig, &config);
+    }
+
+    /// Returns the appeal rules, if appeals are enabled.
+    pub fn appeal_config(env: Env) -> Option<AppealConfig> {
+        env.storage().instance().get(&DataKey::AppealConfig)
+    }
+
+    /// Appeals a ruling within the appeal period. The appellant pays the
+    /// appeal fee and a random panel of approved arbiters, excluding the one
+    /// who ruled, is drawn to review it.
+    ///
+    /// # Returns
+    /// The panel
+    ///
+    /// # Panics
+    /// If appeals are disabled, the appellant is not the disputer or a party
+    /// to the deposited funds, the dispute is not resolved, was already
+    /// appealed or is past its appeal period, or too few arbiters are
+    /// approved to form a panel
+    pub fn appeal(env: Env, dispute_id: String, appellant: Address) -> Vec<Address> {
+        pausable::require_not_paused(&env);
+        appellant.require_auth();
+
+        let config = Self::appeal_config(env.clone())
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::AppealsDisabled));
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        let is_party = appellant == dispute.disputer
+            || Self::get_terms(env.clone(), dispute_id.clone())
+                .is_some_and(|t| appellant == t.client || appellant == t.freelancer);
+        if !is_party {
+            panic_with_error!(env, DisputeError::NotParty);
+        }
+        let key = DataKey::Appeal(dispute_id.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, DisputeError::AlreadyAppealed);
+        }
+        if dispute.settled {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+        if dispute.state != DisputeState::Resolved {
+            panic_with_error!(env, DisputeError::NotResolved);
+        }
+        if env.ledger().sequence() > dispute.ruled_ledger + config.period {
+            panic_with_error!(env, DisputeError::AppealPeriodEnded);
+        }
+
+        let mut candidates = Self::approved_arbiters(&env);
+        if let Some(index) = dispute
+            .arbiter
+            .as_ref()
+            .and_then(|a| candidates.first_index_of(a))
+        {
+            candidates.remove(index);
+        }
+        if candidates.len() < config.panel_size {
+            panic_with_error!(env, DisputeError::NotEnoughArbiters);
+        }
+        env.prng().shuffle(&mut candidates);
+        let panel = candidates.slice(0..config.panel_size);
+
+        let arbiters = Self::arbiter_config(&env);
+        if config.fee > 0 {
+            token::Client::new(&env, &arbiters.token).transfer(
+                &appellant,
+                &env.current_contract_address(),
+                &config.fee,
+            );
+        }
+        let mut weights = Map::new(&env);
+        for panelist in panel.iter() {
+            let bonds = Self::load_arbiter(&env, &panelist).stake / arbiters.min_stake;
+            weights.set(panelist, bonds.clamp(1, u32::MAX as i128) as u32);
+        }
+        ttl::set(
+            &env,
+            &key,
+            &Appeal {
+                appellant: appellant.clone(),
+                fee: config.fee,
+                original_split: dispute.split_ratio.unwrap(),
+                weights,
+                votes: Map::new(&env),
+                resolved: false,
+            },
+        );
+        dispute.state = DisputeState::Appealed;
+        ttl::set(&env, &DataKey::Dispute(dispute_id.clone()), &dispute);
+
+        events::emit_dispute_appealed(&env, &dispute_id, &appellant, &panel);
+
+        panel
+    }
+
+    /// Records a panelist's proposed split for an appealed dispute.
+    ///
+    /// # Panics
+    /// If the panelist is not authorized or not on the panel, has already
+    /// voted, `split_ratio` > 100, or the appeal is resolved
+    pub fn cast_panel_vote(env: Env, dispute_id: String, panelist: Address, split_ratio: u32) {
+        pausable::require_not_paused(&env);
+        panelist.require_auth();
+
+        if split_ratio > 100 {
+            panic_with_error!(env, DisputeError::InvalidSplitRatio);
+        }
+        let mut appeal = Self::load_appeal(&env, &dispute_id);
+        if appeal.resolved {
+            panic_with_error!(env, DisputeError::AppealResolved);
+        }
+        if !appeal.weights.contains_key(panelist.clone()) {
+            panic_with_error!(env, DisputeError::NotPanelist);
+        }
+        if appeal.votes.contains_key(panelist.clone()) {
+            panic_with_error!(env, DisputeError::AlreadyVoted);
+        }
+        appeal.votes.set(panelist, split_ratio);
+        ttl::set(&env, &DataKey::Appeal(dispute_id), &appeal);
+    }
+
+    /// Resolves an appeal once every panelist has voted. The split backed by
+    /// a weighted majority of the panel replaces the original ruling, which
+    /// is final; funds are paid out. If the ruling is overturned, the
+    /// original arbiter is slashed and the appeal fee refunded, otherwise the
+    /// fee goes to the treasury.
+    ///
+    /// # Returns
+    /// The final split
+    ///
+    /// # Panics
+    /// If the appeal is unknown or resolved, not every panelist has voted, or
+    /// the treasury role is not set
+    pub fn resolve_appeal(env: Env, dispute_id: String) -> u32 {
+        pausable::require_not_paused(&env);
+
+        let mut appeal = Self::load_appeal(&env, &dispute_id);
+        if appeal.resolved {
+            panic_with_error!(env, DisputeError::AppealResolved);
+        }
+        if appeal.votes.len() < appeal.weights.len() {
+            panic_with_error!(env, DisputeError::VotesMissing);
+        }
+        let mut votes = Vec::new(&env);
+        for (panelist, split) in appeal.votes.iter() {
+            votes.push_back((split, appeal.weights.get(panelist).unwrap()));
+        }
+        let split_ratio = weighted_median(&votes, Self::tie_policy(env.clone()));
+
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        let overturned = split_ratio != appeal.original_split;
+        if overturned {
+            if let Some(arbiter) = &dispute.arbiter {
+                if Self::get_arbiter(env.clone(), arbiter.clone()).is_some() {
+                    Self::slash(&env, arbiter);
+                }
+            }
+        }
+        if appeal.fee > 0 {
+            let recipient = if overturned {
+                appeal.appellant.clone()
+            } else {
+                admin::get_role(&env, KeyRole::Treasury)
+                    .unwrap_or_else(|| panic_with_error!(env, DisputeError::TreasuryNotSet))
+            };
+            token::Client::new(&env, &Self::arbiter_config(&env).token).transfer(
+                &env.current_contract_address(),
+                &recipient,
+                &appeal.fee,
+            );
+        }
+
+        appeal.resolved = true;
+        ttl::set(&env, &DataKey::Appeal(dispute_id.clone()), &appeal);
+        dispute.state = DisputeState::Resolved;
+        dispute.split_ratio = Some(split_ratio);
+        dispute.ruled_ledger = env.ledger().sequence();
+        ttl::set(&env, &DataKey::Dispute(dispute_id.clone()), &dispute);
+
+        events::emit_dispute_resolved(&env, &dispute_id, split_ratio);
+        Self::pay_out(&env, &dispute_id, split_ratio).unwrap_or_else(|e| panic_with_error!(env, e));
+
+        split_ratio
+    }
+
+    /// Returns the appeal lodged against a dispute's ruling, if any.
+    pub fn get_appeal(env: Env, dispute_id: String) -> Option<Appeal> {
+        ttl::get(&env, &DataKey::Appeal(dispute_id))
+    }
+
+    /// Pays out a ruling's deposited funds once its appeal period has passed
+    /// without an appeal. Anyone may call this.
+    ///
+    /// # Panics
+    /// If the dispute is not resolved, no funds or filing fee are held, or
+    /// the appeal period is still open
+    pub fn finalize_ruling(env: Env, dispute_id: String) {
+        pausable::require_not_paused(&env);
+
+        let dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        if dispute.state != DisputeState::Resolved {
+            panic_with_error!(env, DisputeError::NotResolved);
+        }
+        if dispute.filing_fee == 0 && Self::get_terms(env.clone(), dispute_id.clone()).is_none() {
+            panic_with_error!(env, DisputeError::NoFunds);
+        }
+        let period = Self::appeal_config(env.clone()).map_or(0, |c| c.period);
+        if env.ledger().sequence() <= dispute.ruled_ledger + period {
+            panic_with_error!(env, DisputeError::AppealPeriodOpen);
+        }
+        Self::pay_out(&env, &dispute_id, dispute.split_ratio.unwrap())
+            .unwrap_or_else(|e| panic_with_error!(env, e));
+    }
+
+    /// Links related disputes so `adjudicate_group` rules on all of them
+    /// with a single split.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dispute_ids` - Disputes to group; none may already be in a group
+    /// * `arbiter` - Arbiter who will rule on the group
+    ///
+    /// # Returns
+    /// The new group's id
+    ///
+    /// # Panics
+    /// If the arbiter is not authorized or approved, the group is empty or
+    /// larger than `MAX_GROUP_SIZE`, or a dispute is unknown, resolved,
+    /// already linked or assigned to another arbiter
+    pub fn link_disputes(env: Env, dispute_ids: Vec<String>, arbiter: Address) -> u64 {
+        pausable::require_not_paused(&env);
+        arbiter.require_auth();
+        Self::require_approved_arbiter(&env, &arbiter);
+
+        if dispute_ids.is_empty() || dispute_ids.len() > MAX_GROUP_SIZE {
+            panic_with_error!(env, DisputeError::InvalidGroupSize);
+        }
+
+        let group_id = env
+            .storage()
+            .instance()
+            .get::<DataKey, u64>(&DataKey::GroupCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::GroupCount, &group_id);
+
+        for dispute_id in dispute_ids.iter() {
+            if Self::is_ruled(&Self::get_dispute(env.clone(), dispute_id.clone())) {
+                panic_with_error!(env, DisputeError::AlreadyResolved);
+            }
+            Self::require_assignable(&env, &dispute_id, &arbiter);
+            let key = DataKey::DisputeGroupOf(dispute_id);
+            if env.storage().persistent().has(&key) {
+                panic_with_error!(env, DisputeError::AlreadyLinked);
+            }
+            ttl::set(&env, &key, &group_id);
+        }
+        ttl::set(
+            &env,
+            &DataKey::Group(group_id),
+            &DisputeGroup {
+                arbiter,
+                disputes: dispute_ids,
+                resolved: false,
+            },
+        );
+        group_id
+    }
+
+    /// Returns a linked dispute group, if it exists.
+    pub fn get_group(env: Env, group_id: u64) -> Option<DisputeGroup> {
+        ttl::get(&env, &DataKey::Group(group_id))
+    }
+
+    /// Adjudicates every dispute in a linked group with the same split.
+    ///
+    /// # Panics
+    /// If the group does not exist or is resolved, `arbiter` is not the
+    /// group's arbiter, any dispute in it is not awaiting a ruling, or
+    /// split_ratio > 100
+    pub fn adjudicate_group(env: Env, group_id: u64, split_ratio: u32, arbiter: Address) {
+        pausable::require_not_paused(&env);
+        arbiter.require_auth();
+
+        let mut group = Self::get_group(env.clone(), group_id)
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::GroupNotFound));
+        if group.arbiter != arbiter {
+            panic_with_error!(env, DisputeError::NotGroupArbiter);
+        }
+        if group.resolved {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+
+        group.resolved = true;
+        ttl::set(&env, &DataKey::Group(group_id), &group);
+
+        Self::require_approved_arbiter(&env, &arbiter);
+        for dispute_id in group.disputes.iter() {
+            Self::require_assignable(&env, &dispute_id, &arbiter);
+            Self::record_ruling(&env, &dispute_id, split_ratio)
+                .unwrap_or_else(|e| panic_with_error!(env, e));
+        }
+    }
+
+    /// Sets how jury ties are broken.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or has not authorized the call
+    pub fn set_tie_policy(env: Env, admin: Address, policy: TiePolicy) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage().instance().set(&DataKey::TiePolicy, &policy);
+    }
+
+    /// Returns the configured tie policy (`Lower` by default).
+    pub fn tie_policy(env: Env) -> TiePolicy {
+        env.storage()
+            .instance()
+            .get(&DataKey::TiePolicy)
+            .unwrap_or(TiePolicy::Lower)
+    }
+
+    /// Sets the disputed amount at or above which a jury must agree
+    /// unanimously instead of by weighted median.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or has not authorized the call
+    pub fn set_unanimity_threshold(env: Env, admin: Address, amount: i128) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::UnanimityThreshold, &amount);
+    }
+
+    /// Returns the unanimity threshold, if one is configured.
+    pub fn unanimity_threshold(env: Env) -> Option<i128> {
+        env.storage().instance().get(&DataKey::UnanimityThreshold)
+    }
+
+    /// Convenes a weighted jury for a dispute.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `dispute_id` - Unique identifier of the dispute
+    /// * `jurors` - `(juror, weight)` pairs; every weight must be non-zero
+    /// * `amount` - Amount at stake, compared against the unanimity threshold
+    /// * `voting_period` - Number of ledgers jurors have to vote
+    ///
+    /// # Panics
+    /// If the admin has not authorized the call, a jury already exists for
+    /// the dispute, the jury is empty or a weight is zero
+    pub fn convene_jury(
+        env: Env,
+        dispute_id: String,
+        jurors: Vec<(Address, u32)>,
+        amount: i128,
+        voting_period: u32,
+    ) {
+        pausable::require_not_paused(&env);
+        admin::require_admin(&env);
+
+        let key = DataKey::Jury(dispute_id);
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, DisputeError::JuryConvened);
+        }
+        if jurors.is_empty() {
+            panic_with_error!(env, DisputeError::EmptyJury);
+        }
+        let mut weights = Map::new(&env);
+        for (juror, weight) in jurors.iter() {
+            if weight == 0 {
+                panic_with_error!(env, DisputeError::ZeroJurorWeight);
+            }
+            weights.set(juror, weight);
+        }
+        let jury = Jury {
+            weights,
+            votes: Map::new(&env),
+            amount,
+            unanimous: Self::unanimity_threshold(env.clone())
+                .is_some_and(|threshold| amount >= threshold),
+            deadline: env.ledger().sequence() + voting_period,
+            resolved: false,
+            escalated: false,
+        };
+        ttl::set(&env, &key, &jury);
+    }
+
+    /// Records a juror's proposed split for a dispute.
+    ///
+    /// # Panics
+    /// If the juror is not on the dispute's jury, has already voted on a
+    /// non-unanimous jury, voting has closed or `split_ratio` > 100
+    pub fn cast_vote(env: Env, dispute_id: String, juror: Address, split_ratio: u32) {
+        pausable::require_not_paused(&env);
+        juror.require_auth();
+
+        if split_ratio > 100 {
+            panic_with_error!(env, DisputeError::InvalidSplitRatio);
+        }
+        let mut jury = Self::load_jury(&env, &dispute_id);
+        if jury.resolved || jury.escalated || env.ledger().sequence() > jury.deadline {
+            panic_with_error!(env, DisputeError::VotingClosed);
+        }
+        if !jury.weights.contains_key(juror.clone()) {
+            panic_with_error!(env, DisputeError::NotJuror);
+        }
+        if !jury.unanimous && jury.votes.contains_key(juror.clone()) {
+            panic_with_error!(env, DisputeError::AlreadyVoted);
+        }
+        jury.votes.set(juror, split_ratio);
+        ttl::set(&env, &DataKey::Jury(dispute_id), &jury);
+    }
+
+    /// Resolves a dispute with the weighted median of the jury's votes, or
+    /// with their common split when the jury must be unanimous.
+    ///
+    /// # Returns
+    /// The split awarded to the freelancer
+    ///
+    /// # Panics
+    /// If no jury exists, it has already resolved or escalated, not every
+    /// juror voted, a unanimous jury has not agreed, or the dispute is not
+    /// awaiting a ruling
+    pub fn resolve_by_jury(env: Env, dispute_id: String) -> u32 {
+        pausable::require_not_paused(&env);
+
+        let mut jury = Self::load_jury(&env, &dispute_id);
+        if jury.resolved || jury.escalated {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+        if jury.votes.len() < jury.weights.len() {
+            panic_with_error!(env, DisputeError::VotesMissing);
+        }
+        if jury.unanimous && Self::common_split(&jury).is_none() {
+            panic_with_error!(env, DisputeError::NoUnanimity);
+        }
+
+        let split_ratio = weighted_median(
+            &Self::weighted_votes(&env, &jury),
+            Self::tie_policy(env.clone()),
+        );
+        jury.resolved = true;
+        ttl::set(&env, &DataKey::Jury(dispute_id.clone()), &jury);
+
+        Self::record_ruling(&env, &dispute_id, split_ratio)
+            .unwrap_or_else(|e| panic_with_error!(env, e));
+        split_ratio
+    }
+
+    /// Escalates a dispute whose jury failed to resolve it by the deadline.
+    /// Anyone may call this. The ruling falls back to the arbiter: through
+    /// `adjudicate` once the dispute is `EvidenceClosed` and not linked into
+    /// a group, or through `adjudicate_group` if it is linked.
+    ///
+    /// # Panics
+    /// If the deadline has not passed or the jury has already resolved
+    pub fn escalate(env: Env, dispute_id: String) {
+        pausable::require_not_paused(&env);
+
+        let mut jury = Self::load_jury(&env, &dispute_id);
+        if jury.resolved || jury.escalated {
+            panic_with_error!(env, DisputeError::AlreadyResolved);
+        }
+        if env.ledger().sequence() <= jury.deadline {
+            panic_with_error!(env, DisputeError::VotingOpen);
+        }
+        jury.escalated = true;
+        ttl::set(&env, &DataKey::Jury(dispute_id.clone()), &jury);
+
+        events::emit_dispute_escalated(&env, &dispute_id, jury.deadline);
+    }
+
+    /// Returns the jury convened for a dispute, if any.
+    pub fn get_jury(env: Env, dispute_id: String) -> Option<Jury> {
+        ttl::get(&env, &DataKey::Jury(dispute_id))
+    }
+
+    /// Previews the split a jury would award for `votes`, given as
+    /// `(split, weight)` pairs, under the configured tie policy.
+    ///
+    /// # Panics
+    /// If a split exceeds 100 or the total weight is zero
+    pub fn weighted_median_split(env: Env, votes: Vec<(u32, u32)>) -> u32 {
+        weighted_median(&votes, Self::tie_policy(env))
+    }
+
+    fn arbiter_config(env: &Env) -> ArbiterConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::ArbiterConfig)
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::ArbitersNotConfigured))
+    }
+
+    fn load_arbiter(env: &Env, arbiter: &Address) -> Arbiter {
+        Self::get_arbiter(env.clone(), arbiter.clone())
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::ArbiterNotRegistered))
+    }
+
+    /// Panics unless `arbiter` is approved in the registry or holds the
+    /// arbiter role.
+    fn require_approved_arbiter(env: &Env, arbiter: &Address) {
+        Self::check_approved_arbiter(env, arbiter).unwrap_or_else(|e| panic_with_error!(env, e));
+    }
+
+    fn check_approved_arbiter(env: &Env, arbiter: &Address) -> Result<(), DisputeError> {
+        if !rbac::has_role(env, Role::Arbiter, arbiter)
+            && !Self::get_arbiter(env.clone(), arbiter.clone()).is_some_and(|a| a.approved)
+        {
+            return Err(DisputeError::UnauthorizedArbiter);
+        }
+        Ok(())
+    }
+
+    /// Panics unless `arbiter` is the dispute's assigned arbiter, or none is.
+    fn require_assignable(env: &Env, dispute_id: &String, arbiter: &Address) {
+        let dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        Self::check_assignable(env, &dispute, arbiter)
+            .unwrap_or_else(|e| panic_with_error!(env, e));
+    }
+
+    fn check_assignable(
+        env: &Env,
+        dispute: &Dispute,
+        arbiter: &Address,
+    ) -> Result<(), DisputeError> {
+        if Self::assigned_arbiter(env, dispute).is_some_and(|assigned| assigned != *arbiter) {
+            return Err(DisputeError::AssignedElsewhere);
+        }
+        Ok(())
+    }
+
+    /// Returns the dispute's assigned arbiter, treating one that is neither
+    /// registered nor holds the arbiter role any more as no assignment.
+    fn assigned_arbiter(env: &Env, dispute: &Dispute) -> Option<Address> {
+        dispute.arbiter.clone().filter(|arbiter| {
+            Self::get_arbiter(env.clone(), arbiter.clone()).is_some()
+                || rbac::has_role(env, Role::Arbiter, arbiter)
+        })
+    }
+
+    /// Moves `slash_bps` of the arbiter's stake to the treasury.
+    fn slash(env: &Env, arbiter: &Address) {
+        let config = Self::arbiter_config(env);
+        let treasury = admin::get_role(env, KeyRole::Treasury)
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::TreasuryNotSet));
+        let mut record = Self::load_arbiter(env, arbiter);
+        let penalty = record.stake * config.slash_bps as i128 / BPS_DENOMINATOR;
+        if penalty > 0 {
+            token::Client::new(env, &config.token).transfer(
+                &env.current_contract_address(),
+                &treasury,
+                &penalty,
+            );
+        }
+        record.stake -= penalty;
+        ttl::set(env, &DataKey::Arbiter(arbiter.clone()), &record);
+    }
+
+    fn approved_arbiters(env: &Env) -> Vec<Address> {
+        ttl::get(env, &DataKey::ApprovedArbiters).unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Picks the next approved arbiter in round-robin order, skipping
+    /// `exclude`, and advances the rotation past it.
+    fn next_arbiter(env: &Env, exclude: Option<&Address>) -> Option<Address> {
+        let approved = Self::approved_arbiters(env);
+        let start: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextArbiter)
+            .unwrap_or(0);
+        for offset in 0..approved.len() {
+            let index = (start + offset) % approved.len();
+            let candidate = approved.get_unchecked(index);
+            if exclude != Some(&candidate) {
+                env.storage()
+                    .instance()
+                    .set(&DataKey::NextArbiter, &(index + 1));
+                return Some(candidate);
+            }
+        }
+        None
+    }
+
+    fn party_disputes(env: &Env, party: &Address, page: u32) -> Vec<String> {
+        ttl::get(env, &DataKey::PartyDisputes(party.clone(), page)).unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Appends a dispute to the last page of `party`'s index, starting a new
+    /// page once it is full.
+    fn index_dispute(env: &Env, party: &Address, dispute_id: &String) {
+        let count = Self::count_disputes_by_party(env.clone(), party.clone());
+        let page = count / PARTY_INDEX_PAGE_SIZE;
+        let mut ids = Self::party_disputes(env, party, page);
+        ids.push_back(dispute_id.clone());
+        ttl::set(env, &DataKey::PartyDisputes(party.clone(), page), &ids);
+        ttl::set(
+            env,
+            &DataKey::PartyDisputeCount(party.clone()),
+            &(count + 1),
+        );
+    }
+
+    fn load_appeal(env: &Env, dispute_id: &String) -> Appeal {
+        Self::get_appeal(env.clone(), dispute_id.clone())
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::AppealNotFound))
+    }
+
+    /// Panics unless `party` is the client or freelancer of a dispute's
+    /// deposited funds.
+    fn require_party(env: &Env, dispute_id: &String, party: &Address) {
+        if !Self::get_terms(env.clone(), dispute_id.clone())
+            .is_some_and(|t| *party == t.client || *party == t.freelancer)
+        {
+            panic_with_error!(env, DisputeError::NotParty);
+        }
+    }
+
+    /// Whether a dispute has received a ruling, final or under appeal.
+    fn is_ruled(dispute: &Dispute) -> bool {
+        matches!(
+            dispute.state,
+            DisputeState::Resolved | DisputeState::Appealed
+        )
+    }
+
+    /// Moves a dispute from `EvidenceClosed` to `Resolved` with `split_ratio`.
+    /// Deposited funds are paid out immediately unless appeals are enabled,
+    /// in which case they wait for `finalize_ruling` or `resolve_appeal`.
+    fn record_ruling(env: &Env, dispute_id: &String, split_ratio: u32) -> Result<(), DisputeError> {
+        if split_ratio > 100 {
+            return Err(DisputeError::InvalidSplitRatio);
+        }
+        let mut dispute: Dispute = ttl::get(env, &DataKey::Dispute(dispute_id.clone()))
+            .ok_or(DisputeError::DisputeNotFound)?;
+        match dispute.state {
+            DisputeState::Active => return Err(DisputeError::EvidenceOpen),
+            DisputeState::Resolved | DisputeState::Appealed => {
+                return Err(DisputeError::AlreadyResolved)
+            }
+            DisputeState::EvidenceClosed => {}
+        }
+        dispute.state = DisputeState::Resolved;
+        dispute.split_ratio = Some(split_ratio);
+        dispute.ruled_ledger = env.ledger().sequence();
+        ttl::set(env, &DataKey::Dispute(dispute_id.clone()), &dispute);
+
+        events::emit_dispute_resolved(env, dispute_id, split_ratio);
+
+        if Self::appeal_config(env.clone()).is_none() {
+            Self::pay_out(env, dispute_id, split_ratio)?;
+        }
+        Ok(())
+    }
+
+    /// Credits a dispute's deposited funds according to `split_ratio`, less
+    /// the platform fee on the freelancer's share, and settles its filing
+    /// fee and bonds.
+    ///
+    /// # Errors
+    /// `TreasuryNotSet` if a fee is due and the treasury role is not set
+    fn pay_out(env: &Env, dispute_id: &String, split_ratio: u32) -> Result<(), DisputeError> {
+        let key = DataKey::Terms(dispute_id.clone());
+        let terms: Option<DisputeTerms> = ttl::get(env, &key);
+        Self::settle_filing_fee(env, dispute_id, split_ratio, terms.as_ref())?;
+        if let Some(terms) = terms {
+            env.storage().persistent().remove(&key);
+            Self::settle_bonds(env, dispute_id, split_ratio, &terms);
+            let freelancer_amount = terms.amount * split_ratio as i128 / 100;
+            let client_amount = terms.amount - freelancer_amount;
+            let platform_fee =
+                freelancer_amount * Self::platform_fee(env.clone()) as i128 / BPS_DENOMINATOR;
+            if platform_fee > 0 {
+                let treasury =
+                    admin::get_role(env, KeyRole::Treasury).ok_or(DisputeError::TreasuryNotSet)?;
+                treasury::pay_fee(env, &treasury, &terms.token, platform_fee);
+            }
+            claimable::credit(
+                env,
+                &terms.freelancer,
+                &terms.token,
+                freelancer_amount - platform_fee,
+            );
+            claimable::credit(env, &terms.client, &terms.token, client_amount);
+            events::emit_dispute_payout(env, dispute_id, freelancer_amount, client_amount);
+        }
+        Ok(())
+    }
+
+    /// Credits the disputer's share of the filing fee, as won under
+    /// `split_ratio`, to their claimable balance and sends the rest to the
+    /// treasury. If no funds were deposited the disputer's side is unknown
+    /// and the fee is refunded in full.
+    fn settle_filing_fee(
+        env: &Env,
+        dispute_id: &String,
+        split_ratio: u32,
+        terms: Option<&DisputeTerms>,
+    ) -> Result<(), DisputeError> {
+        let mut dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        let fee = dispute.filing_fee;
+        if fee == 0 {
+            return Ok(());
+        }
+        let won = match terms {
+            Some(t) if dispute.disputer == t.freelancer => split_ratio,
+            Some(t) if dispute.disputer == t.client => 100 - split_ratio,
+            _ => 100,
+        };
+        let refund = fee * won as i128 / 100;
+        let token = Self::arbiter_config(env).token;
+        claimable::credit(env, &dispute.disputer, &token, refund);
+        if refund < fee {
+            let treasury =
+                admin::get_role(env, KeyRole::Treasury).ok_or(DisputeError::TreasuryNotSet)?;
+            token::Client::new(env, &token).transfer(
+                &env.current_contract_address(),
+                &treasury,
+                &(fee - refund),
+            );
+        }
+        dispute.filing_fee = 0;
+        ttl::set(env, &DataKey::Dispute(dispute_id.clone()), &dispute);
+        Ok(())
+    }
+
+    /// Bond each party owes on `terms` at the current rate.
+    fn bond_amount(env: &Env, terms: &DisputeTerms) -> i128 {
+        terms.amount * Self::bond_rate(env.clone()) as i128 / BPS_DENOMINATOR
+    }
+
+    /// Credits the posted bonds back to the parties, except that when an
+    /// arbiter has ruled the larger share to one side, the other side's bond
+    /// is credited to the arbiter instead. If an appeal panel overturned the
+    /// ruling, the panel that decided the outcome shares the bond in place
+    /// of the slashed arbiter.
+    fn settle_bonds(env: &Env, dispute_id: &String, split_ratio: u32, terms: &DisputeTerms) {
+        let dispute = Self::get_dispute(env.clone(), dispute_id.clone());
+        let loser = match split_ratio.cmp(&50) {
+            core::cmp::Ordering::Greater if !dispute.settled => Some(&terms.client),
+            core::cmp::Ordering::Less if !dispute.settled => Some(&terms.freelancer),
+            _ => None,
+        };
+        let panel = Self::get_appeal(env.clone(), dispute_id.clone())
+            .filter(|appeal| appeal.resolved && appeal.original_split != split_ratio)
+            .map(|appeal| appeal.weights.keys());
+        for party in [&terms.client, &terms.freelancer] {
+            let key = DataKey::Bond(dispute_id.clone(), party.clone());
+            let bond: i128 = match ttl::get(env, &key) {
+                Some(bond) => bond,
+                None => continue,
+            };
+            env.storage().persistent().remove(&key);
+            if loser != Some(party) {
+                claimable::credit(env, party, &terms.token, bond);
+                continue;
+            }
+            match (&panel, &dispute.arbiter) {
+                (Some(panel), _) => {
+                    // The first panelist takes any indivisible remainder.
+                    let share = bond / panel.len() as i128;
+                    for (i, panelist) in panel.iter().enumerate() {
+                        let amount = if i == 0 {
+                            bond - share * (panel.len() as i128 - 1)
+                        } else {
+                            share
+                        };
+                        claimable::credit(env, &panelist, &terms.token, amount);
+                        events::emit_bond_forfeited(env, dispute_id, party, &panelist, amount);
+                    }
+                }
+                (None, Some(arbiter)) => {
+                    claimable::credit(env, arbiter, &terms.token, bond);
+                    events::emit_bond_forfeited(env, dispute_id, party, arbiter, bond);
+                }
+                (None, None) => claimable::credit(env, party, &terms.token, bond),
+            }
+        }
+    }
+
+    fn load_jury(env: &Env, dispute_id: &String) -> Jury {
+        ttl::get(env, &DataKey::Jury(dispute_id.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, DisputeError::JuryNotFound))
+    }
+
+    /// Returns the split every juror proposed, if they all agree.
+    fn common_split(jury: &Jury) -> Option<u32> {
+        let first = jury.votes.values().first()?;
+        jury.votes
+            .values()
+            .iter()
+            .all(|split| split == first)
+            .then_some(first)
+    }
+
+    /// Pairs every cast vote with its juror's weight.
+    fn weighted_votes(env: &Env, jury: &Jury) -> Vec<(u32, u32)> {
+        let mut votes = Vec::new(env);
+        for (juror, split) in jury.votes.iter() {
+            votes.push_back((split, jury.weights.get(juror).unwrap_or(0)));
+        }
+        votes
+    }
+}
+
+/// Returns the weighted median of `(split, weight)` pairs.
+///
+/// The median is the smallest split at which the cumulative weight reaches
+/// half of the total. When it lands exactly on half, the median sits between
+/// that split and the next one cast, and `policy` decides which is used.
+pub fn weighted_median(votes: &Vec<(u32, u32)>, policy: TiePolicy) -> u32 {
+    let mut buckets = [0u64; 101];
+    let mut total: u64 = 0;
+    for (split, weight) in votes.iter() {
+        if split > 100 {
+            panic_with_error!(votes.env(), DisputeError::InvalidSplitRatio);
+        }
+        buckets[split as usize] += weight as u64;
+        total += weight as u64;
+    }
+    if total == 0 {
+        panic_with_error!(votes.env(), DisputeError::NoWeightedVotes);
+    }
+
+    let mut cumulative: u64 = 0;
+    for (split, weight) in buckets.iter().enumerate() {
+        cumulative += weight;
+        if cumulative * 2 < total || *weight == 0 {
+            continue;
+        }
+        let lower = split as u32;
+        if cumulative * 2 > total {
+            return lower;
+        }
+        // Exactly half the weight sits at or below `lower`: tie with the next split.
+        let upper = (split + 1..buckets.len())
+            .find(|&s| buckets[s] > 0)
+            .map(|s| s as u32)
+            .unwrap_or(lower);
+        return match policy {
+            TiePolicy::Lower => lower,
+            TiePolicy::Upper => upper,
+            TiePolicy::Midpoint => (lower + upper) / 2,
+        };
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::{
+        ArbiterAssignedEvent, DisputeEscalatedEvent, DisputePayoutEvent, DisputeResolvedEvent,
+        DisputeStartedEvent, EvidenceSubmittedEvent, EVENT_VERSION,
+    };
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger},
+        token::{Client as TokenClient, StellarAssetClient},
+        vec, Address, Env, IntoVal, Symbol, Val, Vec,
+    };
+
+    struct Setup<'a> {
+        court: DisputeResolutionCourtClient<'a>,
+        token: TokenClient<'a>,
+        admin: Address,
+        /// Registered and approved with a stake of 500
+        arbiter: Address,
+    }
+
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, DisputeResolutionCourt);
+        let court = DisputeResolutionCourtClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+        court.initialize(&admin);
+        court.set_evidence_window(&admin, &10);
+
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        court.configure_arbiters(
+            &admin,
+            &ArbiterConfig {
+                token: token.clone(),
+                min_stake: 100,
+                slash_bps: 2_000,
+                ruling_period: 100,
+            },
+        );
+        let arbiter = Address::generate(env);
+        StellarAssetClient::new(env, &token).mint(&arbiter, &1_000);
+        court.register_arbiter(&arbiter, &500);
+        court.approve_arbiter(&admin, &arbiter);
+
+        Setup {
+            court,
+            token: TokenClient::new(env, &token),
+            admin,
+            arbiter,
+        }
+    }
+
+    /// Claims what the court has credited to `who` and returns their token
+    /// balance.
+    fn claimed(s: &Setup, who: &Address) -> i128 {
+        s.court.claim(who, &s.token.address);
+        s.token.balance(who)
+    }
+
+    /// Moves the ledger past the evidence window configured in `setup`.
+    fn pass_evidence_window(env: &Env) {
+        env.ledger().with_mut(|l| l.sequence_number += 11);
+    }
+
+    /// Opens a dispute and closes its evidence so it is ready for a ruling.
+    fn open_for_ruling(env: &Env, s: &Setup, id: &str) -> String {
+        let dispute_id = String::from_str(env, id);
+        s.court
+            .initiate_dispute(&dispute_id, &Address::generate(env));
+        pass_evidence_window(env);
+        s.court.close_evidence(&dispute_id, &s.arbiter);
+        dispute_id
+    }
+
+    /// Enables appeals and approves `count` more arbiters staking the minimum.
+    fn enable_appeals(env: &Env, s: &Setup, count: u32) -> Address {
+        let treasury = Address::generate(env);
+        s.court.set_role(&KeyRole::Treasury, &treasury);
+        s.court.configure_appeals(
+            &s.admin,
+            &AppealConfig {
+                period: 50,
+                fee: 100,
+                panel_size: 3,
+            },
+        );
+        for _ in 0..count {
+            let arbiter = Address::generate(env);
+            StellarAssetClient::new(env, &s.token.address).mint(&arbiter, &100);
+            s.court.register_arbiter(&arbiter, &100);
+            s.court.approve_arbiter(&s.admin, &arbiter);
+        }
+        treasury
+    }
+
+    /// Opens a dispute with 1,000 deposited by a fresh client and rules on it.
+    fn funded_ruling(env: &Env, s: &Setup, id: &str, split_ratio: u32) -> DisputeTerms {
+        let terms = DisputeTerms {
+            client: Address::generate(env),
+            freelancer: Address::generate(env),
+            token: s.token.address.clone(),
+            amount: 1_000,
+        };
+        StellarAssetClient::new(env, &s.token.address).mint(&terms.client, &1_100);
+        let dispute_id = String::from_str(env, id);
+        s.court.initiate_dispute(&dispute_id, &terms.client);
+        s.court.deposit_funds(&dispute_id, &terms.client, &terms);
+        pass_evidence_window(env);
+        s.court.close_evidence(&dispute_id, &s.arbiter);
+        s.court.adjudicate(&dispute_id, &split_ratio, &s.arbiter);
+        terms
+    }
+
+    #[test]
+    fn test_list_disputes_by_party() {
+        let env = Env::default();
+        let s = setup(&env);
+        let terms = funded_ruling(&env, &s, "a", 60);
+        let second = String::from_str(&env, "b");
+        s.court.initiate_dispute(&second, &terms.freelancer);
+
+        let client_disputes = s.court.list_disputes_by_party(&terms.client, &0, &10);
+        assert_eq!(client_disputes.len(), 1, "the disputer is indexed once");
+        assert_eq!(
+            client_disputes.get(0).unwrap().state,
+            DisputeState::Resolved
+        );
+        assert_eq!(client_disputes.get(0).unwrap().split_ratio, Some(60));
+
+        assert_eq!(s.court.count_disputes_by_party(&terms.freelancer), 2);
+        let page = s.court.list_disputes_by_party(&terms.freelancer, &1, &10);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page.get(0).unwrap().dispute_id, second);
+        assert_eq!(page.get(0).unwrap().disputer, terms.freelancer);
+
+        // A full storage page rolls the index over onto the next one; reads
+        // run across the boundary.
+        for i in 2..=PARTY_INDEX_PAGE_SIZE {
+            let id =
+                String::from_bytes(&env, &[b'c', b'0' + (i / 10) as u8, b'0' + (i % 10) as u8]);
+            s.court.initiate_dispute(&id, &terms.freelancer);
+        }
+        assert_eq!(
+            s.court.count_disputes_by_party(&terms.freelancer),
+            PARTY_INDEX_PAGE_SIZE + 1
+        );
+        let first = s
+            .court
+            .list_disputes_by_party(&terms.freelancer, &0, &MAX_PAGE_SIZE);
+        assert_eq!(first.len(), MAX_PAGE_SIZE);
+        let tail =
+            s.court
+                .list_disputes_by_party(&terms.freelancer, &(PARTY_INDEX_PAGE_SIZE - 1), &10);
+        assert_eq!(tail.len(), 2);
+        assert_eq!(
+            tail.get(0).unwrap().dispute_id,
+            first.get(PARTY_INDEX_PAGE_SIZE - 1).unwrap().dispute_id
+        );
+        assert!(s
+            .court
+            .list_disputes_by_party(&terms.freelancer, &(PARTY_INDEX_PAGE_SIZE + 1), &10)
+            .is_empty());
+        assert_eq!(
+            s.court
+                .try_list_disputes_by_party(&terms.freelancer, &0, &(MAX_PAGE_SIZE + 1)),
+            Err(Ok(CommonError::BatchTooLarge.into()))
+        );
+    }
+
+    /// Returns the most recent event as a one-element vec for comparison.
+    fn last_event(env: &Env) -> Vec<(Address, Vec<Val>, Val)> {
+        let all = env.events().all();
+        all.slice(all.len() - 1..)
+    }
+
+    #[test]
+    fn test_dispute_lifecycle_events() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let disputer = Address::generate(&env);
+        let arbiter = s.arbiter.clone();
+        let dispute_id = String::from_str(&env, "escrow-42");
+        let evidence = String::from_str(&env, "ipfs://QmEvidence");
+        env.ledger().with_mut(|l| l.timestamp = 1_700_000_000);
+
+        court.initiate_dispute(&dispute_id, &disputer);
+        let all = env.events().all();
+        assert_eq!(
+            all.slice(all.len() - 2..),
+            vec![
+                &env,
+                (
+                    court.address.clone(),
+                    (Symbol::new(&env, "dispute_started"), dispute_id.clone()).into_val(&env),
+                    DisputeStartedEvent {
+                        disputer: disputer.clone(),
+                        timestamp: 1_700_000_000,
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                ),
+                (
+                    court.address.clone(),
+                    (Symbol::new(&env, "arbiter_assigned"), dispute_id.clone()).into_val(&env),
+                    ArbiterAssignedEvent {
+                        arbiter: arbiter.clone(),
+                        timestamp: 1_700_000_000,
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+
+        court.submit_evidence(&dispute_id, &evidence, &disputer);
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    court.address.clone(),
+                    (Symbol::new(&env, "evidence_submitted"), dispute_id.clone()).into_val(&env),
+                    EvidenceSubmittedEvent {
+                        submitter: disputer.clone(),
+                        evidence_hash: evidence.clone(),
+                        round: INITIAL_ROUND,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+
+        // No ruling while evidence is still open.
+        assert_eq!(
+            court.try_adjudicate(&dispute_id, &70, &arbiter),
+            Err(Ok(DisputeError::EvidenceOpen))
+        );
+        pass_evidence_window(&env);
+        court.close_evidence(&dispute_id, &arbiter);
+        court.adjudicate(&dispute_id, &70, &arbiter);
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    court.address.clone(),
+                    (Symbol::new(&env, "dispute_resolved"), dispute_id.clone()).into_val(&env),
+                    DisputeResolvedEvent {
+                        split_ratio: 70,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dispute_state_transitions() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let disputer = Address::generate(&env);
+        let arbiter = s.arbiter.clone();
+        let dispute_id = String::from_str(&env, "escrow-43");
+        let evidence = String::from_str(&env, "ipfs://QmEvidence");
+
+        assert_eq!(
+            court.try_get_dispute(&dispute_id),
+            Err(Ok(DisputeError::DisputeNotFound.into()))
+        );
+        assert_eq!(
+            court.try_submit_evidence(&dispute_id, &evidence, &disputer),
+            Err(Ok(DisputeError::DisputeNotFound.into()))
+        );
+
+        court.initiate_dispute(&dispute_id, &disputer);
+        let dispute = court.get_dispute(&dispute_id);
+        assert_eq!(dispute.state, DisputeState::Active);
+        assert_eq!(dispute.disputer, disputer);
+        assert_eq!(
+            court.try_initiate_dispute(&dispute_id, &disputer),
+            Err(Ok(DisputeError::DisputeExists.into()))
+        );
+
+        pass_evidence_window(&env);
+        court.close_evidence(&dispute_id, &arbiter);
+        assert_eq!(
+            court.get_dispute(&dispute_id).state,
+            DisputeState::EvidenceClosed
+        );
+        assert_eq!(
+            court.try_submit_evidence(&dispute_id, &evidence, &disputer),
+            Err(Ok(DisputeError::EvidenceClosed.into()))
+        );
+        assert_eq!(
+            court.try_close_evidence(&dispute_id, &arbiter),
+            Err(Ok(DisputeError::EvidenceClosed.into()))
+        );
+
+        assert_eq!(
+            court.try_adjudicate(&dispute_id, &101, &arbiter),
+            Err(Ok(DisputeError::InvalidSplitRatio))
+        );
+        court.adjudicate(&dispute_id, &25, &arbiter);
+        let dispute = court.get_dispute(&dispute_id);
+        assert_eq!(dispute.state, DisputeState::Resolved);
+        assert_eq!(dispute.split_ratio, Some(25));
+        assert_eq!(
+            court.try_adjudicate(&dispute_id, &25, &arbiter),
+            Err(Ok(DisputeError::AlreadyResolved))
+        );
+    }
+
+    #[test]
+    fn test_arbiter_role_rules_without_stake() {
+        let env = Env::default();
+        let s = setup(&env);
+        let appointed = Address::generate(&env);
+        let dispute_id = String::from_str(&env, "escrow-role");
+        // Leave the dispute unassigned so a role holder can take it.
+        s.court.remove_arbiter(&s.admin, &s.arbiter);
+        s.court
+            .initiate_dispute(&dispute_id, &Address::generate(&env));
+        pass_evidence_window(&env);
+
+        assert_eq!(
+            s.court.try_close_evidence(&dispute_id, &appointed),
+            Err(Ok(DisputeError::UnauthorizedArbiter.into()))
+        );
+        s.court.grant_role(&s.admin, &Role::Arbiter, &appointed);
+        s.court.close_evidence(&dispute_id, &appointed);
+        s.court.adjudicate(&dispute_id, &40, &appointed);
+        assert_eq!(s.court.get_dispute(&dispute_id).split_ratio, Some(40));
+
+        s.court.revoke_role(&s.admin, &Role::Arbiter, &appointed);
+        let next = String::from_str(&env, "escrow-role-2");
+        s.court.initiate_dispute(&next, &Address::generate(&env));
+        pass_evidence_window(&env);
+        assert_eq!(
+            s.court.try_adjudicate(&next, &40, &appointed),
+            Err(Ok(DisputeError::UnauthorizedArbiter))
+        );
+    }
+
+    #[test]
+    fn test_removed_arbiter_leaves_disputes_vacant() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let treasury = Address::generate(&env);
+        court.set_role(&KeyRole::Treasury, &treasury);
+        let second = Address::generate(&env);
+        let third = Address::generate(&env);
+        for arbiter in [&second, &third] {
+            StellarAssetClient::new(&env, &s.token.address).mint(arbiter, &500);
+            court.register_arbiter(arbiter, &500);
+            court.approve_arbiter(&s.admin, arbiter);
+        }
+
+        // Disputes are assigned in turn.
+        let open = String::from_str(&env, "escrow-1100");
+        court.initiate_dispute(&open, &Address::generate(&env));
+        let closed = String::from_str(&env, "escrow-1101");
+        court.initiate_dispute(&closed, &Address::generate(&env));
+        assert_eq!(court.get_dispute(&open).arbiter, Some(s.arbiter.clone()));
+        assert_eq!(court.get_dispute(&closed).arbiter, Some(second.clone()));
+        pass_evidence_window(&env);
+        court.close_evidence(&closed, &second);
+
+        court.remove_arbiter(&s.admin, &s.arbiter);
+        court.remove_arbiter(&s.admin, &second);
+        // Another arbiter can take the open dispute and rule on it.
+        court.close_evidence(&open, &third);
+        court.adjudicate(&open, &50, &third);
+        assert_eq!(court.get_dispute(&open).split_ratio, Some(50));
+
+        // The removed arbiter's missed ruling is reassigned without a slash.
+        env.ledger().with_mut(|l| l.sequence_number += 101);
+        court.report_missed_deadline(&closed);
+        assert_eq!(court.get_dispute(&closed).arbiter, Some(third.clone()));
+        assert_eq!(s.token.balance(&treasury), 0);
+        assert_eq!(
+            court.try_assign_arbiter(&closed),
+            Err(Ok(DisputeError::AssignedElsewhere.into()))
+        );
+        court.adjudicate(&closed, &50, &third);
+
+        // The rotation still hands out the remaining arbiter.
+        let later = String::from_str(&env, "escrow-1102");
+        court.initiate_dispute(&later, &Address::generate(&env));
+        assert_eq!(court.get_dispute(&later).arbiter, Some(third));
+    }
+
+    #[test]
+    fn test_get_roles() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let admin = s.admin.clone();
+        let treasury = Address::generate(&env);
+        court.set_role(&KeyRole::Treasury, &treasury);
+
+        let roles = court.get_roles();
+        assert_eq!(roles.admin, admin);
+        assert_eq!(roles.treasury, Some(treasury));
+        assert_eq!(roles.veto, None);
+    }
+
+    #[test]
+    fn test_pause_blocks_state_changes() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let admin = s.admin.clone();
+        let party = Address::generate(&env);
+        let dispute_id = String::from_str(&env, "escrow-7");
+
+        // Only the admin or a guardian may pause.
+        assert_eq!(
+            court.try_pause(&party),
+            Err(Ok(CommonError::MissingRole.into()))
+        );
+        let guardian = Address::generate(&env);
+        court.grant_role(&admin, &Role::Guardian, &guardian);
+        court.pause(&guardian);
+        court.unpause(&guardian);
+
+        court.pause(&admin);
+        assert!(court.is_paused());
+        assert_eq!(
+            court.try_initiate_dispute(&dispute_id, &party),
+            Err(Ok(CommonError::ContractPaused.into()))
+        );
+        assert!(court.try_adjudicate(&dispute_id, &50, &party).is_err());
+        assert_eq!(
+            court.try_submit_evidence(&dispute_id, &String::from_str(&env, "QmHash"), &party),
+            Err(Ok(CommonError::ContractPaused.into()))
+        );
+        // Getters keep working while paused.
+        assert_eq!(court.get_roles().admin, admin);
+
+        court.unpause(&admin);
+        assert!(!court.is_paused());
+        assert!(court.initiate_dispute(&dispute_id, &party));
+        pass_evidence_window(&env);
+        court.close_evidence(&dispute_id, &s.arbiter);
+        court.adjudicate(&dispute_id, &50, &s.arbiter);
+    }
+
+    #[test]
+    fn test_weighted_median_split() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let admin = s.admin.clone();
+
+        // Odd number of equal votes: the middle split wins.
+        assert_eq!(
+            court.weighted_median_split(&vec![&env, (20, 1), (80, 1), (50, 1)]),
+            50
+        );
+        // A heavy juror outweighs the rest.
+        assert_eq!(
+            court.weighted_median_split(&vec![&env, (10, 1), (90, 5), (40, 1)]),
+            90
+        );
+        // Weight imbalance drags an even jury off the midpoint.
+        assert_eq!(
+            court.weighted_median_split(&vec![&env, (30, 4), (60, 1), (70, 1), (90, 1)]),
+            30
+        );
+
+        // Even split of weight is a tie, resolved per policy.
+        let tie = vec![&env, (40, 2), (60, 1), (80, 1)];
+        assert_eq!(court.tie_policy(), TiePolicy::Lower);
+        assert_eq!(court.weighted_median_split(&tie), 40);
+        court.set_tie_policy(&admin, &TiePolicy::Upper);
+        assert_eq!(court.weighted_median_split(&tie), 60);
+        court.set_tie_policy(&admin, &TiePolicy::Midpoint);
+        assert_eq!(court.weighted_median_split(&tie), 50);
+
+        assert_eq!(
+            court.try_weighted_median_split(&vec![&env]),
+            Err(Ok(DisputeError::NoWeightedVotes.into()))
+        );
+        assert_eq!(
+            court.try_weighted_median_split(&vec![&env, (101, 1)]),
+            Err(Ok(DisputeError::InvalidSplitRatio.into()))
+        );
+    }
+
+    #[test]
+    fn test_resolve_by_jury() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let dispute_id = open_for_ruling(&env, &s, "escrow-9");
+
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let c = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        court.convene_jury(
+            &dispute_id,
+            &vec![&env, (a.clone(), 1), (b.clone(), 2), (c.clone(), 1)],
+            &1_000,
+            &100,
+        );
+
+        court.cast_vote(&dispute_id, &a, &20);
+        assert_eq!(
+            court.try_cast_vote(&dispute_id, &a, &30),
+            Err(Ok(DisputeError::AlreadyVoted.into()))
+        );
+        assert_eq!(
+            court.try_cast_vote(&dispute_id, &outsider, &30),
+            Err(Ok(DisputeError::NotJuror.into()))
+        );
+        court.cast_vote(&dispute_id, &b, &60);
+        assert_eq!(
+            court.try_resolve_by_jury(&dispute_id),
+            Err(Ok(DisputeError::VotesMissing.into()))
+        );
+        court.cast_vote(&dispute_id, &c, &90);
+
+        assert_eq!(court.resolve_by_jury(&dispute_id), 60);
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    court.address.clone(),
+                    (Symbol::new(&env, "dispute_resolved"), dispute_id.clone()).into_val(&env),
+                    DisputeResolvedEvent {
+                        split_ratio: 60,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+        assert!(court.get_jury(&dispute_id).unwrap().resolved);
+        assert_eq!(
+            court.try_resolve_by_jury(&dispute_id),
+            Err(Ok(DisputeError::AlreadyResolved.into()))
+        );
+    }
+
+    #[test]
+    fn test_evidence_count_per_party() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let client = Address::generate(&env);
+        let freelancer = Address::generate(&env);
+        let dispute_id = String::from_str(&env, "escrow-11");
+
+        court.initiate_dispute(&dispute_id, &client);
+        court.submit_evidence(&dispute_id, &String::from_str(&env, "ipfs://a"), &client);
+        court.submit_evidence(&dispute_id, &String::from_str(&env, "ipfs://b"), &client);
+        court.submit_evidence(
+            &dispute_id,
+            &String::from_str(&env, "ipfs://c"),
+            &freelancer,
+        );
+
+        assert_eq!(court.evidence_count(&dispute_id, &client), 2);
+        assert_eq!(court.evidence_count(&dispute_id, &freelancer), 1);
+        assert_eq!(
+            court.evidence_count(&String::from_str(&env, "escrow-12"), &client),
+            0
+        );
+        let entries = court.get_party_evidence(&dispute_id, &client);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries.get(0).unwrap().hash,
+            String::from_str(&env, "ipfs://a")
+        );
+        assert_eq!(
+            entries.get(1).unwrap().hash,
+            String::from_str(&env, "ipfs://b")
+        );
+        let all = court.get_evidence(&dispute_id);
+        assert_eq!(all.keys(), vec![&env, client.clone(), freelancer.clone()]);
+        assert_eq!(all.get(freelancer).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rebuttal_round_follows_evidence_window() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        court.set_rebuttal_window(&s.admin, &5);
+        let client = Address::generate(&env);
+        let dispute_id = String::from_str(&env, "escrow-13");
+        let evidence = String::from_str(&env, "ipfs://a");
+
+        court.initiate_dispute(&dispute_id, &client);
+        court.submit_evidence(&dispute_id, &evidence, &client);
+        pass_evidence_window(&env);
+        assert_eq!(
+            court.try_close_evidence(&dispute_id, &s.arbiter),
+            Err(Ok(DisputeError::EvidenceOpen.into()))
+        );
+        court.submit_evidence(&dispute_id, &evidence, &client);
+
+        let entries = court.get_party_evidence(&dispute_id, &client);
+        assert_eq!(entries.get(0).unwrap().round, INITIAL_ROUND);
+        let rebuttal = entries.get(1).unwrap();
+        assert_eq!(rebuttal.round, REBUTTAL_ROUND);
+        assert_eq!(rebuttal.submitted_ledger, env.ledger().sequence());
+
+        for _ in 2..MAX_EVIDENCE_PER_PARTY {
+            court.submit_evidence(&dispute_id, &evidence, &client);
+        }
+        assert_eq!(
+            court.try_submit_evidence(&dispute_id, &evidence, &client),
+            Err(Ok(DisputeError::EvidenceClosed.into()))
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number += 5);
+        assert_eq!(
+            court.try_submit_evidence(&dispute_id, &evidence, &Address::generate(&env)),
+            Err(Ok(DisputeError::EvidenceClosed.into()))
+        );
+        court.close_evidence(&dispute_id, &s.arbiter);
+    }
+
+    #[test]
+    fn test_high_value_dispute_requires_unanimity() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let admin = s.admin.clone();
+        court.set_unanimity_threshold(&admin, &10_000);
+
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let jurors = vec![&env, (a.clone(), 1), (b.clone(), 3)];
+
+        let large = open_for_ruling(&env, &s, "escrow-100");
+        court.convene_jury(&large, &jurors, &10_000, &100);
+        assert!(court.get_jury(&large).unwrap().unanimous);
+
+        court.cast_vote(&large, &a, &30);
+        court.cast_vote(&large, &b, &70);
+        assert_eq!(
+            court.try_resolve_by_jury(&large),
+            Err(Ok(DisputeError::NoUnanimity.into()))
+        );
+        // Jurors on a unanimous jury may revise their proposals.
+        court.cast_vote(&large, &a, &70);
+        assert_eq!(court.resolve_by_jury(&large), 70);
+
+        // Below the threshold the weighted median applies as usual.
+        let small = open_for_ruling(&env, &s, "escrow-101");
+        court.convene_jury(&small, &jurors, &9_999, &100);
+        court.cast_vote(&small, &a, &30);
+        court.cast_vote(&small, &b, &70);
+        assert_eq!(
+            court.try_cast_vote(&small, &a, &70),
+            Err(Ok(DisputeError::AlreadyVoted.into()))
+        );
+        assert_eq!(court.resolve_by_jury(&small), 70);
+    }
+
+    #[test]
+    fn test_deadlocked_jury_escalates_after_deadline() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let admin = s.admin.clone();
+        court.set_unanimity_threshold(&admin, &10_000);
+
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let dispute_id = String::from_str(&env, "escrow-200");
+        court.convene_jury(
+            &dispute_id,
+            &vec![&env, (a.clone(), 1), (b.clone(), 1)],
+            &50_000,
+            &100,
+        );
+        court.cast_vote(&dispute_id, &a, &20);
+        court.cast_vote(&dispute_id, &b, &80);
+        assert_eq!(
+            court.try_escalate(&dispute_id),
+            Err(Ok(DisputeError::VotingOpen.into()))
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number += 101);
+        assert_eq!(
+            court.try_cast_vote(&dispute_id, &a, &80),
+            Err(Ok(DisputeError::VotingClosed.into()))
+        );
+        court.escalate(&dispute_id);
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    court.address.clone(),
+                    (Symbol::new(&env, "dispute_escalated"), dispute_id.clone()).into_val(&env),
+                    DisputeEscalatedEvent {
+                        deadline: 100,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+        assert!(court.get_jury(&dispute_id).unwrap().escalated);
+        assert_eq!(
+            court.try_resolve_by_jury(&dispute_id),
+            Err(Ok(DisputeError::AlreadyResolved.into()))
+        );
+    }
+
+    #[test]
+    fn test_adjudicate_linked_group() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let arbiter = s.arbiter.clone();
+        let other = Address::generate(&env);
+        let ids = vec![
+            &env,
+            open_for_ruling(&env, &s, "escrow-1"),
+            open_for_ruling(&env, &s, "escrow-2"),
+            open_for_ruling(&env, &s, "escrow-3"),
+        ];
+
+        let rival = Address::generate(&env);
+        StellarAssetClient::new(&env, &s.token.address).mint(&rival, &100);
+        court.register_arbiter(&rival, &100);
+        court.approve_arbiter(&s.admin, &rival);
+        assert_eq!(
+            court.try_link_disputes(&ids, &rival),
+            Err(Ok(DisputeError::AssignedElsewhere.into()))
+        );
+
+        let group_id = court.link_disputes(&ids, &arbiter);
+        assert_eq!(
+            court.try_link_disputes(&vec![&env, ids.get(1).unwrap()], &arbiter),
+            Err(Ok(DisputeError::AlreadyLinked.into()))
+        );
+        assert_eq!(
+            court.try_adjudicate_group(&group_id, &40, &other),
+            Err(Ok(DisputeError::NotGroupArbiter.into()))
+        );
+
+        court.adjudicate_group(&group_id, &40, &arbiter);
+        let all = env.events().all();
+        let resolved = all.slice(all.len() - 3..);
+        let mut expected = Vec::new(&env);
+        for id in ids.iter() {
+            expected.push_back((
+                court.address.clone(),
+                (Symbol::new(&env, "dispute_resolved"), id).into_val(&env),
+                DisputeResolvedEvent {
+                    split_ratio: 40,
+                    timestamp: env.ledger().timestamp(),
+                    version: EVENT_VERSION,
+                }
+                .into_val(&env),
+            ));
+        }
+        assert_eq!(resolved, expected);
+        assert!(court.get_group(&group_id).unwrap().resolved);
+        assert_eq!(
+            court.try_adjudicate_group(&group_id, &40, &arbiter),
+            Err(Ok(DisputeError::AlreadyResolved.into()))
+        );
+    }
+
+    #[test]
+    fn test_arbiter_registry_membership() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let candidate = Address::generate(&env);
+        StellarAssetClient::new(&env, &s.token.address).mint(&candidate, &1_000);
+
+        assert_eq!(
+            court.try_register_arbiter(&candidate, &99),
+            Err(Ok(DisputeError::StakeTooLow.into()))
+        );
+        court.register_arbiter(&candidate, &300);
+        assert_eq!(
+            court.try_register_arbiter(&candidate, &300),
+            Err(Ok(DisputeError::ArbiterRegistered.into()))
+        );
+        assert_eq!(s.token.balance(&candidate), 700);
+        assert_eq!(
+            court.get_arbiter(&candidate),
+            Some(Arbiter {
+                stake: 300,
+                approved: false
+            })
+        );
+
+        // Unapproved arbiters cannot take or rule on disputes.
+        let dispute_id = String::from_str(&env, "escrow-300");
+        court.initiate_dispute(&dispute_id, &Address::generate(&env));
+        pass_evidence_window(&env);
+        assert_eq!(
+            court.try_close_evidence(&dispute_id, &candidate),
+            Err(Ok(DisputeError::UnauthorizedArbiter.into()))
+        );
+        court.close_evidence(&dispute_id, &s.arbiter);
+        assert_eq!(
+            court.try_adjudicate(&dispute_id, &50, &candidate),
+            Err(Ok(DisputeError::UnauthorizedArbiter))
+        );
+
+        // Once approved, the dispute is still reserved for its assigned arbiter.
+        court.approve_arbiter(&s.admin, &candidate);
+        assert_eq!(
+            court.try_adjudicate(&dispute_id, &50, &candidate),
+            Err(Ok(DisputeError::AssignedElsewhere))
+        );
+        court.adjudicate(&dispute_id, &50, &s.arbiter);
+
+        // Removal returns the stake and revokes membership.
+        court.remove_arbiter(&s.admin, &candidate);
+        assert_eq!(s.token.balance(&candidate), 1_000);
+        assert_eq!(court.get_arbiter(&candidate), None);
+    }
+
+    #[test]
+    fn test_missed_ruling_deadline_slashes_stake() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let treasury = Address::generate(&env);
+        court.set_role(&KeyRole::Treasury, &treasury);
+        let dispute_id = open_for_ruling(&env, &s, "escrow-301");
+        assert_eq!(
+            court.get_dispute(&dispute_id).arbiter,
+            Some(s.arbiter.clone())
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number += 100);
+        assert_eq!(
+            court.try_report_missed_deadline(&dispute_id),
+            Err(Ok(DisputeError::RulingDeadlineNotPassed.into()))
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        court.report_missed_deadline(&dispute_id);
+        assert_eq!(s.token.balance(&treasury), 100);
+        assert_eq!(court.get_arbiter(&s.arbiter).unwrap().stake, 400);
+        assert_eq!(court.get_dispute(&dispute_id).arbiter, None);
+        assert_eq!(
+            court.try_report_missed_deadline(&dispute_id),
+            Err(Ok(DisputeError::NoRulingPending.into()))
+        );
+
+        // Any approved arbiter may now take over the ruling.
+        let backup = Address::generate(&env);
+        StellarAssetClient::new(&env, &s.token.address).mint(&backup, &100);
+        court.register_arbiter(&backup, &100);
+        court.approve_arbiter(&s.admin, &backup);
+        court.adjudicate(&dispute_id, &60, &backup);
+        assert_eq!(court.get_dispute(&dispute_id).split_ratio, Some(60));
+    }
+
+    #[test]
+    fn test_arbiters_assigned_in_turn() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        court.set_role(&KeyRole::Treasury, &Address::generate(&env));
+        let second = Address::generate(&env);
+        StellarAssetClient::new(&env, &s.token.address).mint(&second, &100);
+        court.register_arbiter(&second, &100);
+        court.approve_arbiter(&s.admin, &second);
+
+        let first_id = String::from_str(&env, "escrow-a");
+        let second_id = String::from_str(&env, "escrow-b");
+        court.initiate_dispute(&first_id, &Address::generate(&env));
+        court.initiate_dispute(&second_id, &Address::generate(&env));
+        assert_eq!(
+            court.get_dispute(&first_id).arbiter,
+            Some(s.arbiter.clone())
+        );
+        assert_eq!(court.get_dispute(&second_id).arbiter, Some(second.clone()));
+        assert_eq!(
+            court.try_assign_arbiter(&first_id),
+            Err(Ok(DisputeError::AssignedElsewhere.into()))
+        );
+
+        pass_evidence_window(&env);
+        assert_eq!(
+            court.try_close_evidence(&first_id, &second),
+            Err(Ok(DisputeError::AssignedElsewhere.into()))
+        );
+        court.close_evidence(&first_id, &s.arbiter);
+
+        // A missed ruling passes to the next arbiter with a fresh deadline.
+        env.ledger().with_mut(|l| l.sequence_number += 101);
+        court.report_missed_deadline(&first_id);
+        let dispute = court.get_dispute(&first_id);
+        assert_eq!(dispute.arbiter, Some(second.clone()));
+        assert_eq!(dispute.ruling_deadline, env.ledger().sequence() + 100);
+        assert_eq!(
+            court.try_adjudicate(&first_id, &50, &s.arbiter),
+            Err(Ok(DisputeError::AssignedElsewhere))
+        );
+        court.adjudicate(&first_id, &50, &second);
+    }
+
+    #[test]
+    fn test_unassigned_dispute_takes_next_arbiter() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        court.remove_arbiter(&s.admin, &s.arbiter);
+        let dispute_id = String::from_str(&env, "escrow-c");
+        court.initiate_dispute(&dispute_id, &Address::generate(&env));
+        assert_eq!(court.get_dispute(&dispute_id).arbiter, None);
+        assert_eq!(
+            court.try_assign_arbiter(&dispute_id),
+            Err(Ok(DisputeError::NotEnoughArbiters.into()))
+        );
+
+        StellarAssetClient::new(&env, &s.token.address).mint(&s.arbiter, &100);
+        court.register_arbiter(&s.arbiter, &100);
+        court.approve_arbiter(&s.admin, &s.arbiter);
+        assert_eq!(court.assign_arbiter(&dispute_id), s.arbiter);
+        assert_eq!(
+            court.get_dispute(&dispute_id).arbiter,
+            Some(s.arbiter.clone())
+        );
+    }
+
+    #[test]
+    fn test_missed_deadline_reported_to_reputation() {
+        use crate::reputation::{Reputation, ReputationClient};
+
+        let env = Env::default();
+        let s = setup(&env);
+        s.court
+            .set_role(&KeyRole::Treasury, &Address::generate(&env));
+        let reputation = ReputationClient::new(&env, &env.register_contract(None, Reputation));
+        reputation.initialize(&s.admin);
+        reputation.add_reporter(&s.admin, &s.court.address);
+        s.court.set_reputation(&s.admin, &reputation.address);
+
+        let dispute_id = open_for_ruling(&env, &s, "escrow-302");
+        env.ledger().with_mut(|l| l.sequence_number += 101);
+        s.court.report_missed_deadline(&dispute_id);
+        assert_eq!(reputation.get_reputation(&s.arbiter).deadlines_missed, 1);
+    }
+
+    #[test]
+    fn test_evidence_window() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let party = Address::generate(&env);
+        let evidence = String::from_str(&env, "ipfs://QmLate");
+        let dispute_id = String::from_str(&env, "escrow-400");
+        env.ledger().with_mut(|l| l.sequence_number = 1_000);
+
+        court.initiate_dispute(&dispute_id, &party);
+        assert_eq!(court.get_dispute(&dispute_id).evidence_deadline, 1_010);
+
+        // Evidence is accepted up to and including the deadline, and no ruling
+        // can be prepared before it.
+        env.ledger().with_mut(|l| l.sequence_number = 1_010);
+        court.submit_evidence(&dispute_id, &evidence, &party);
+        assert_eq!(
+            court.try_close_evidence(&dispute_id, &s.arbiter),
+            Err(Ok(DisputeError::EvidenceOpen.into()))
+        );
+        assert_eq!(
+            court.try_adjudicate(&dispute_id, &50, &s.arbiter),
+            Err(Ok(DisputeError::EvidenceOpen))
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number = 1_011);
+        assert_eq!(
+            court.try_submit_evidence(&dispute_id, &evidence, &party),
+            Err(Ok(DisputeError::EvidenceClosed.into()))
+        );
+        court.close_evidence(&dispute_id, &s.arbiter);
+        court.adjudicate(&dispute_id, &50, &s.arbiter);
+
+        // The window is snapshotted when a dispute opens.
+        court.set_evidence_window(&s.admin, &DEFAULT_EVIDENCE_WINDOW);
+        assert_eq!(court.evidence_window(), DEFAULT_EVIDENCE_WINDOW);
+        let later = String::from_str(&env, "escrow-401");
+        court.initiate_dispute(&later, &party);
+        assert_eq!(
+            court.get_dispute(&later).evidence_deadline,
+            1_011 + DEFAULT_EVIDENCE_WINDOW
+        );
+    }
+
+    #[test]
+    fn test_only_disputing_parties_deposit_terms() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let client = Address::generate(&env);
+        let freelancer = Address::generate(&env);
+        let attacker = Address::generate(&env);
+        let mint = StellarAssetClient::new(&env, &s.token.address);
+        mint.mint(&client, &1_000);
+        mint.mint(&attacker, &1);
+        let dispute_id = String::from_str(&env, "escrow-1200");
+        court.initiate_dispute(&dispute_id, &client);
+
+        // Front-running with terms naming the attacker's own addresses fails.
+        let decoy = DisputeTerms {
+            client: attacker.clone(),
+            freelancer: Address::generate(&env),
+            token: s.token.address.clone(),
+            amount: 1,
+        };
+        assert_eq!(
+            court.try_deposit_funds(&dispute_id, &attacker, &decoy),
+            Err(Ok(DisputeError::NotParty.into()))
+        );
+        let terms = DisputeTerms {
+            client: client.clone(),
+            freelancer: freelancer.clone(),
+            token: s.token.address.clone(),
+            amount: 1_000,
+        };
+        assert_eq!(
+            court.try_deposit_funds(&dispute_id, &attacker, &terms),
+            Err(Ok(DisputeError::NotParty.into()))
+        );
+
+        court.deposit_funds(&dispute_id, &client, &terms);
+        let auths = env.auths();
+        let signed = |party: &Address| auths.iter().any(|(address, _)| address == party);
+        assert!(signed(&client) && signed(&freelancer));
+        assert_eq!(court.get_terms(&dispute_id), Some(terms));
+    }
+
+    #[test]
+    fn test_adjudicate_pays_out_deposit() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let client = Address::generate(&env);
+        let freelancer = Address::generate(&env);
+        StellarAssetClient::new(&env, &s.token.address).mint(&client, &1_000);
+        let dispute_id = String::from_str(&env, "escrow-500");
+        court.initiate_dispute(&dispute_id, &client);
+
+        let terms = DisputeTerms {
+            client: client.clone(),
+            freelancer: freelancer.clone(),
+            token: s.token.address.clone(),
+            amount: 1_000,
+        };
+        court.deposit_funds(&dispute_id, &client, &terms);
+        assert_eq!(s.token.balance(&court.address), 1_500);
+        assert_eq!(
+            court.try_deposit_funds(&dispute_id, &client, &terms),
+            Err(Ok(DisputeError::AlreadyFunded.into()))
+        );
+
+        pass_evidence_window(&env);
+        court.close_evidence(&dispute_id, &s.arbiter);
+        court.adjudicate(&dispute_id, &70, &s.arbiter);
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    court.address.clone(),
+                    (Symbol::new(&env, "dispute_payout"), dispute_id.clone()).into_val(&env),
+                    DisputePayoutEvent {
+                        freelancer_amount: 700,
+                        client_amount: 300,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+        assert_eq!(claimed(&s, &freelancer), 700);
+        assert_eq!(claimed(&s, &client), 300);
+        // Only the arbiter's stake remains in custody.
+        assert_eq!(s.token.balance(&court.address), 500);
+    }
+
+    #[test]
+    fn test_platform_fee_on_payout_goes_to_treasury() {
+        use crate::treasury::{Treasury, TreasuryClient};
+
+        let env = Env::default();
+        let s = setup(&env);
+        let treasury = TreasuryClient::new(&env, &env.register_contract(None, Treasury));
+        treasury.initialize(&s.admin, &Address::generate(&env));
+        s.court.set_role(&KeyRole::Treasury, &treasury.address);
+        s.court.set_platform_fee(&s.admin, &1_000);
+        assert_eq!(
+            s.court.try_set_platform_fee(&s.admin, &10_001),
+            Err(Ok(DisputeError::InvalidAmount.into()))
+        );
+
+        let terms = funded_ruling(&env, &s, "escrow-900", 70);
+        // 10% of the freelancer's 700; the client's share is untouched.
+        assert_eq!(claimed(&s, &terms.freelancer), 630);
+        assert_eq!(claimed(&s, &terms.client), 100 + 300);
+        assert_eq!(treasury.fees_collected(&s.token.address), 70);
+    }
+
+    #[test]
+    fn test_appeal_panel_overturns_ruling() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let treasury = enable_appeals(&env, &s, 4);
+        let terms = funded_ruling(&env, &s, "escrow-600", 70);
+        let dispute_id = String::from_str(&env, "escrow-600");
+
+        // Payout waits for the ruling to become final.
+        assert_eq!(claimed(&s, &terms.freelancer), 0);
+        assert_eq!(
+            court.try_appeal(&dispute_id, &Address::generate(&env)),
+            Err(Ok(DisputeError::NotParty.into()))
+        );
+
+        let panel = court.appeal(&dispute_id, &terms.client);
+        assert_eq!(panel.len(), 3);
+        assert!(!panel.contains(&s.arbiter));
+        assert_eq!(court.get_dispute(&dispute_id).state, DisputeState::Appealed);
+        assert_eq!(
+            court.try_appeal(&dispute_id, &terms.freelancer),
+            Err(Ok(DisputeError::AlreadyAppealed.into()))
+        );
+        assert_eq!(
+            court.try_finalize_ruling(&dispute_id),
+            Err(Ok(DisputeError::NotResolved.into()))
+        );
+
+        assert_eq!(
+            court.try_cast_panel_vote(&dispute_id, &s.arbiter, &40),
+            Err(Ok(DisputeError::NotPanelist.into()))
+        );
+        court.cast_panel_vote(&dispute_id, &panel.get(0).unwrap(), &40);
+        court.cast_panel_vote(&dispute_id, &panel.get(1).unwrap(), &90);
+        assert_eq!(
+            court.try_resolve_appeal(&dispute_id),
+            Err(Ok(DisputeError::VotesMissing.into()))
+        );
+        court.cast_panel_vote(&dispute_id, &panel.get(2).unwrap(), &40);
+
+        assert_eq!(court.resolve_appeal(&dispute_id), 40);
+        let dispute = court.get_dispute(&dispute_id);
+        assert_eq!(dispute.state, DisputeState::Resolved);
+        assert_eq!(dispute.split_ratio, Some(40));
+        // Overturned: the fee is refunded and the original arbiter slashed.
+        assert_eq!(claimed(&s, &terms.freelancer), 400);
+        assert_eq!(claimed(&s, &terms.client), 700);
+        assert_eq!(s.token.balance(&treasury), 100);
+        assert_eq!(court.get_arbiter(&s.arbiter).unwrap().stake, 400);
+        assert_eq!(
+            court.try_appeal(&dispute_id, &terms.client),
+            Err(Ok(DisputeError::AlreadyAppealed.into()))
+        );
+        assert_eq!(
+            court.try_finalize_ruling(&dispute_id),
+            Err(Ok(DisputeError::NoFunds.into()))
+        );
+    }
+
+    #[test]
+    fn test_overturned_ruling_pays_bond_to_panel() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        enable_appeals(&env, &s, 3);
+        court.set_bond_rate(&s.admin, &1_000);
+        let terms = DisputeTerms {
+            client: Address::generate(&env),
+            freelancer: Address::generate(&env),
+            token: s.token.address.clone(),
+            amount: 1_000,
+        };
+        let mint = StellarAssetClient::new(&env, &s.token.address);
+        mint.mint(&terms.client, &1_200);
+        mint.mint(&terms.freelancer, &100);
+        let dispute_id = String::from_str(&env, "escrow-1300");
+        court.initiate_dispute(&dispute_id, &terms.client);
+        court.deposit_funds(&dispute_id, &terms.client, &terms);
+        court.post_bond(&dispute_id, &terms.client);
+        court.post_bond(&dispute_id, &terms.freelancer);
+        pass_evidence_window(&env);
+        court.close_evidence(&dispute_id, &s.arbiter);
+        court.adjudicate(&dispute_id, &70, &s.arbiter);
+
+        let panel = court.appeal(&dispute_id, &terms.client);
+        for panelist in panel.iter() {
+            court.cast_panel_vote(&dispute_id, &panelist, &30);
+        }
+        assert_eq!(court.resolve_appeal(&dispute_id), 30);
+
+        // The slashed arbiter gets nothing; the panel shares the freelancer's bond.
+        assert_eq!(court.claimable_balance(&s.arbiter, &s.token.address), 0);
+        let mut shared = 0;
+        for panelist in panel.iter() {
+            let share = court.claimable_balance(&panelist, &s.token.address);
+            assert!(share == 33 || share == 34);
+            shared += share;
+        }
+        assert_eq!(shared, 100);
+        assert_eq!(claimed(&s, &terms.client), 700 + 100 + 100);
+        assert_eq!(claimed(&s, &terms.freelancer), 300);
+    }
+
+    #[test]
+    fn test_unappealed_ruling_finalizes_after_period() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let treasury = enable_appeals(&env, &s, 3);
+        let terms = funded_ruling(&env, &s, "escrow-601", 70);
+        let dispute_id = String::from_str(&env, "escrow-601");
+
+        env.ledger().with_mut(|l| l.sequence_number += 50);
+        assert_eq!(
+            court.try_finalize_ruling(&dispute_id),
+            Err(Ok(DisputeError::AppealPeriodOpen.into()))
+        );
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        assert_eq!(
+            court.try_appeal(&dispute_id, &terms.client),
+            Err(Ok(DisputeError::AppealPeriodEnded.into()))
+        );
+
+        court.finalize_ruling(&dispute_id);
+        assert_eq!(claimed(&s, &terms.freelancer), 700);
+        assert_eq!(claimed(&s, &terms.client), 400);
+        assert_eq!(s.token.balance(&treasury), 0);
+        assert_eq!(court.get_terms(&dispute_id), None);
+        assert_eq!(
+            court.try_finalize_ruling(&dispute_id),
+            Err(Ok(DisputeError::NoFunds.into()))
+        );
+    }
+
+    #[test]
+    fn test_silent_respondent_loses_by_default() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let client = Address::generate(&env);
+        let freelancer = Address::generate(&env);
+        StellarAssetClient::new(&env, &s.token.address).mint(&client, &3_000);
+        let open = |id: &str, disputer: &Address| {
+            let dispute_id = String::from_str(&env, id);
+            court.initiate_dispute(&dispute_id, disputer);
+            court.deposit_funds(
+                &dispute_id,
+                &client,
+                &DisputeTerms {
+                    client: client.clone(),
+                    freelancer: freelancer.clone(),
+                    token: s.token.address.clone(),
+                    amount: 1_000,
+                },
+            );
+            dispute_id
+        };
+
+        let ignored = open("escrow-800", &freelancer);
+        let evidenced = open("escrow-801", &client);
+        let negotiated = open("escrow-802", &client);
+        court.submit_evidence(&evidenced, &String::from_str(&env, "QmReply"), &freelancer);
+        court.propose_settlement(&negotiated, &30, &freelancer);
+        assert_eq!(
+            court.try_claim_default_judgment(&ignored),
+            Err(Ok(DisputeError::EvidenceOpen.into()))
+        );
+
+        pass_evidence_window(&env);
+        court.claim_default_judgment(&ignored);
+        let dispute = court.get_dispute(&ignored);
+        assert_eq!(dispute.split_ratio, Some(100));
+        assert!(dispute.settled);
+        assert_eq!(claimed(&s, &freelancer), 1_000);
+        assert_eq!(
+            court.try_claim_default_judgment(&ignored),
+            Err(Ok(DisputeError::AlreadyResolved.into()))
+        );
+
+        // An answer of either kind sends the dispute to the arbiter.
+        for answered in [evidenced, negotiated] {
+            assert_eq!(
+                court.try_claim_default_judgment(&answered),
+                Err(Ok(DisputeError::AssignedElsewhere.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_accepted_settlement_pays_out_without_arbiter() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        enable_appeals(&env, &s, 3);
+        let client = Address::generate(&env);
+        let freelancer = Address::generate(&env);
+        StellarAssetClient::new(&env, &s.token.address).mint(&client, &1_000);
+        let dispute_id = String::from_str(&env, "escrow-700");
+        court.initiate_dispute(&dispute_id, &client);
+        court.deposit_funds(
+            &dispute_id,
+            &client,
+            &DisputeTerms {
+                client: client.clone(),
+                freelancer: freelancer.clone(),
+                token: s.token.address.clone(),
+                amount: 1_000,
+            },
+        );
+
+        let outsider = Address::generate(&env);
+        assert_eq!(
+            court.try_propose_settlement(&dispute_id, &50, &outsider),
+            Err(Ok(DisputeError::NotParty.into()))
+        );
+        assert_eq!(
+            court.try_accept_settlement(&dispute_id, &client),
+            Err(Ok(DisputeError::NoSettlementOffer.into()))
+        );
+
+        court.propose_settlement(&dispute_id, &40, &client);
+        assert_eq!(
+            court.try_accept_settlement(&dispute_id, &client),
+            Err(Ok(DisputeError::OwnOffer.into()))
+        );
+        // A counter-offer replaces the open one.
+        court.propose_settlement(&dispute_id, &60, &freelancer);
+        assert_eq!(
+            court.get_settlement_offer(&dispute_id),
+            Some(SettlementOffer {
+                proposer: freelancer.clone(),
+                split_ratio: 60,
+            })
+        );
+        court.accept_settlement(&dispute_id, &client);
+
+        let dispute = court.get_dispute(&dispute_id);
+        assert_eq!(dispute.state, DisputeState::Resolved);
+        assert_eq!(dispute.split_ratio, Some(60));
+        assert!(dispute.settled);
+        // Paid at once even though appeals are enabled, and not appealable.
+        assert_eq!(claimed(&s, &freelancer), 600);
+        assert_eq!(claimed(&s, &client), 400);
+        assert_eq!(
+            court.try_appeal(&dispute_id, &client),
+            Err(Ok(DisputeError::AlreadyResolved.into()))
+        );
+        assert_eq!(
+            court.try_propose_settlement(&dispute_id, &50, &client),
+            Err(Ok(DisputeError::AlreadyResolved.into()))
+        );
+    }
+
+    #[test]
+    fn test_ruling_forfeits_losing_bond_to_arbiter() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        court.set_bond_rate(&s.admin, &1_000);
+        assert_eq!(
+            court.try_set_bond_rate(&s.admin, &10_001),
+            Err(Ok(DisputeError::InvalidAmount.into()))
+        );
+        let client = Address::generate(&env);
+        let freelancer = Address::generate(&env);
+        let mint = StellarAssetClient::new(&env, &s.token.address);
+        mint.mint(&client, &2_200);
+        mint.mint(&freelancer, &200);
+        let terms = DisputeTerms {
+            client: client.clone(),
+            freelancer: freelancer.clone(),
+            token: s.token.address.clone(),
+            amount: 1_000,
+        };
+
+        let ruled = String::from_str(&env, "escrow-900");
+        court.initiate_dispute(&ruled, &client);
+        court.deposit_funds(&ruled, &client, &terms);
+        court.post_bond(&ruled, &client);
+        assert_eq!(
+            court.try_post_bond(&ruled, &client),
+            Err(Ok(DisputeError::BondPosted.into()))
+        );
+        court.post_bond(&ruled, &freelancer);
+        assert_eq!(court.get_bond(&ruled, &freelancer), 100);
+        pass_evidence_window(&env);
+        assert_eq!(
+            court.try_resolve_bond_default(&ruled),
+            Err(Ok(DisputeError::BondPosted.into()))
+        );
+        court.close_evidence(&ruled, &s.arbiter);
+        assert_eq!(
+            court.try_post_bond(&ruled, &freelancer),
+            Err(Ok(DisputeError::EvidenceClosed.into()))
+        );
+        court.adjudicate(&ruled, &30, &s.arbiter);
+        assert_eq!(court.claimable_balance(&s.arbiter, &s.token.address), 100);
+        assert_eq!(court.claimable_balance(&freelancer, &s.token.address), 300);
+        assert_eq!(court.claimable_balance(&client, &s.token.address), 800);
+        assert_eq!(court.get_bond(&ruled, &freelancer), 0);
+
+        // Settling before adjudication returns both bonds.
+        let settled = String::from_str(&env, "escrow-901");
+        court.initiate_dispute(&settled, &client);
+        court.deposit_funds(&settled, &client, &terms);
+        court.post_bond(&settled, &client);
+        court.post_bond(&settled, &freelancer);
+        court.propose_settlement(&settled, &30, &client);
+        court.accept_settlement(&settled, &freelancer);
+        assert_eq!(court.claimable_balance(&s.arbiter, &s.token.address), 100);
+        assert_eq!(claimed(&s, &freelancer), 300 + 300 + 100);
+        assert_eq!(claimed(&s, &client), 800 + 700 + 100);
+    }
+
+    #[test]
+    fn test_withheld_bond_loses_by_default() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let client = Address::generate(&env);
+        let freelancer = Address::generate(&env);
+        let mint = StellarAssetClient::new(&env, &s.token.address);
+        mint.mint(&client, &1_200);
+        mint.mint(&freelancer, &100);
+        let terms = DisputeTerms {
+            client: client.clone(),
+            freelancer: freelancer.clone(),
+            token: s.token.address.clone(),
+            amount: 1_000,
+        };
+        let dispute_id = String::from_str(&env, "escrow-910");
+        court.initiate_dispute(&dispute_id, &freelancer);
+        court.deposit_funds(&dispute_id, &client, &terms);
+        assert_eq!(
+            court.try_post_bond(&dispute_id, &client),
+            Err(Ok(DisputeError::NoBondDue.into()))
+        );
+
+        court.set_bond_rate(&s.admin, &1_000);
+        court.post_bond(&dispute_id, &freelancer);
+        // The client holds out: evidence keeps the default judgment away,
+        // but the missing bond does not stall the dispute.
+        court.submit_evidence(&dispute_id, &String::from_str(&env, "QmClient"), &client);
+        assert_eq!(
+            court.try_resolve_bond_default(&dispute_id),
+            Err(Ok(DisputeError::EvidenceOpen.into()))
+        );
+        pass_evidence_window(&env);
+        assert_eq!(
+            court.try_post_bond(&dispute_id, &client),
+            Err(Ok(DisputeError::EvidenceClosed.into()))
+        );
+        assert_eq!(
+            court.try_close_evidence(&dispute_id, &s.arbiter),
+            Err(Ok(DisputeError::BondMissing.into()))
+        );
+
+        court.resolve_bond_default(&dispute_id);
+        let dispute = court.get_dispute(&dispute_id);
+        assert_eq!(dispute.split_ratio, Some(100));
+        assert!(dispute.settled);
+        assert_eq!(claimed(&s, &freelancer), 1_000 + 100);
+        assert_eq!(claimed(&s, &client), 200);
+    }
+
+    #[test]
+    fn test_filing_fee_refunded_by_share_won() {
+        let env = Env::default();
+        let s = setup(&env);
+        let court = &s.court;
+        let treasury = Address::generate(&env);
+        court.set_role(&KeyRole::Treasury, &treasury);
+        court.set_filing_fee(&s.admin, &100);
+        assert_eq!(
+            court.try_set_filing_fee(&s.admin, &-1),
+            Err(Ok(DisputeError::InvalidAmount.into()))
+        );
+
+        // The client files, deposits and wins 30% of the dispute.
+        let terms = funded_ruling(&env, &s, "escrow-800", 70);
+        let dispute_id = String::from_str(&env, "escrow-800");
+        assert_eq!(court.get_dispute(&dispute_id).filing_fee, 0);
+        assert_eq!(claimed(&s, &terms.client), 330);
+        assert_eq!(s.token.balance(&treasury), 70);
+
+        // A disputer who cannot pay the fee cannot open a dispute.
+        let broke = Address::generate(&env);
+        assert!(court
+            .try_initiate_dispute(&String::from_str(&env, "escrow-801"), &broke)
+            .is_err());
+    }
 }
@@ -0,0 +1,43 @@
+//! Shared error codes, and the rule for how entrypoints report failures.
+//!
+//! Every contract defines its own `#[contracterror]` enum, numbered from 100
+//! so it never overlaps [`CommonError`]. Entrypoints raise those errors with
+//! `panic_with_error!`, except the ones whose failure is a routine outcome a
+//! caller is expected to check for and retry later: a ruling that cannot be
+//! recorded yet (`DisputeResolutionCourt::adjudicate`), a sponsorship outside
+//! its limits (`GaslessHandler::sponsor_transaction`), and a milestone payout
+//! that is not due yet (`Escrow::release`, `release_partial`,
+//! `claim_expired_milestone`). Those return `Result` with the contract's
+//! error type. Either way the caller sees the same contract error code; a
+//! new entrypoint returns `Result` only if it fits the same rule.
+use soroban_sdk::contracterror;
+
+/// Error codes raised by the shared helper modules (`admin`, `pausable`, ...).
+///
+/// These occupy the low end of the code space so contract-specific errors
+/// can be added alongside them without clashing.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CommonError {
+    /// The contract already has an admin
+    AlreadyInitialized = 1,
+    /// The contract has not been initialized with an admin yet
+    NotInitialized = 2,
+    /// The caller is not the pending admin of an ownership transfer
+    NotPendingAdmin = 3,
+    /// The caller is not the contract's admin
+    NotAdmin = 4,
+    /// The contract is paused and rejects state-changing calls
+    ContractPaused = 5,
+    /// The asset is not in the supported asset registry
+    UnsupportedAsset = 6,
+    /// Too many entries were passed to a batch call
+    BatchTooLarge = 7,
+    /// Stored entries must be migrated before the contract can be used
+    MigrationPending = 8,
+    /// The caller does not hold the role the entrypoint requires
+    MissingRole = 9,
+    /// The caller is not the role admin
+    NotRoleAdmin = 10,
+}
@@ -0,0 +1,3532 @@
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, BytesN,
+    Env, IntoVal, String, Symbol, Val, Vec,
+};
+
+use crate::admin::{self, KeyRole, Roles};
+use crate::assets::{self, AssetInfo};
+use crate::badge::{self, BadgeKind, Delivery};
+use crate::claimable;
+use crate::gasless::GaslessHandlerClient;
+use crate::kyc;
+use crate::rbac::{self, Role};
+use crate::rebalancer::RouterClient;
+use crate::reputation::{self, Outcome};
+use crate::{events, pausable, treasury, ttl};
+
+/// Denominator for basis-point rates.
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Default ledgers a dispute may stay open before anyone can force a refund
+/// to the client (~180 days at 5s per ledger).
+pub const DEFAULT_STALE_DISPUTE_TIMEOUT: u32 = 180 * 17_280;
+
+/// Maximum number of escrows accepted by a single summary query.
+pub const MAX_SUMMARY_BATCH: u32 = 50;
+
+/// Number of escrow ids held by each page of a user's escrow index.
+pub const USER_INDEX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of recipients in a payout split.
+pub const MAX_SPLIT_RECIPIENTS: u32 = 10;
+
+/// Fixed-point scale of payout conversion rates (7 decimals, like Stellar
+/// assets).
+pub const RATE_SCALE: i128 = 10_000_000;
+
+/// Default escrow total below which completions earn no reputation or badge
+/// (100 units of a 7-decimal asset).
+pub const DEFAULT_MIN_REPORTED_TOTAL: i128 = 100 * RATE_SCALE;
+
+/// Lifecycle of an escrow as a whole.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum EscrowStatus {
+    /// Created, waiting for the client to deposit funds
+    Pending = 0,
+    /// Funds are held by the contract
+    Funded = 1,
+    /// Every milestone has been paid out
+    Completed = 2,
+    /// A party has raised a dispute; releases are blocked until the arbiter rules
+    Disputed = 3,
+    /// The arbiter has ruled and the remaining funds were distributed
+    Resolved = 4,
+    /// A stalled dispute timed out and the remaining funds went back to the client
+    Refunded = 5,
+    /// Both parties agreed to cancel and the remaining funds were split, or
+    /// the client withdrew the escrow before funding it
+    Cancelled = 6,
+    /// The remaining funds of a frozen escrow were clawed back by compliance
+    ClawedBack = 7,
+}
+
+/// Progress of a single milestone, derived from its confirmations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum MilestoneStatus {
+    /// Neither party has confirmed the milestone yet
+    Pending = 0,
+    /// The freelancer has submitted the work
+    Submitted = 1,
+    /// The client has approved the work
+    Approved = 2,
+    /// The milestone amount has been paid out
+    Released = 3,
+}
+
+/// A single deliverable and the amount paid out when it is released.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Milestone {
+    pub amount: i128,
+    pub description: String,
+    /// Set when the client approves the milestone
+    pub client_confirmed: bool,
+    /// Set when the freelancer submits the milestone as complete
+    pub freelancer_confirmed: bool,
+    /// Set once the whole amount has been paid out
+    pub released: bool,
+    /// Paid out so far through partial releases; `amount - released_amount`
+    /// is still locked
+    pub released_amount: i128,
+    /// Last ledger of the client's review, set on submission while a review
+    /// period is configured. Past it, `claim_expired_milestone` pays out.
+    pub review_deadline: Option<u32>,
+    /// Ledger the work is due by, once both parties have agreed on one
+    pub deadline: Option<u32>,
+}
+
+/// A freelancer's request to push back a milestone's deadline. Lapses if
+/// the client has not approved it by the current deadline.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DeadlineExtension {
+    pub new_deadline: u32,
+    /// Deadline in force when the request was made
+    pub expires: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowData {
+    pub client: Address,
+    pub freelancer: Address,
+    pub arbiter: Address,
+    pub token: Address,
+    pub status: EscrowStatus,
+    /// When set, a milestone is only releasable once both the client and the
+    /// freelancer have confirmed it. Otherwise the client's approval suffices.
+    pub dual_approval: bool,
+    pub milestones: Vec<Milestone>,
+    /// Arbiter compensation deposited on top of the milestone total. Whatever
+    /// is not paid to the arbiter is refunded to the client when the escrow closes.
+    pub arbiter_reserve: i128,
+    /// Ledger on which the current dispute was raised
+    pub disputed_ledger: Option<u32>,
+    /// Ledger on which the escrow was created
+    pub created_ledger: u32,
+}
+
+/// Compact view of an escrow for dashboards.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowSummary {
+    pub escrow_id: u64,
+    pub client: Address,
+    pub freelancer: Address,
+    pub token: Address,
+    pub status: EscrowStatus,
+    /// Sum of every milestone amount
+    pub total: i128,
+    /// Sum of the milestones already paid out
+    pub released: i128,
+    pub milestone_count: u32,
+}
+
+/// How arbiter compensation is funded and paid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ArbiterIncentive {
+    /// Reserve carved out at creation, in basis points of the milestone total
+    pub reserve_bps: u32,
+    /// Arbiter fee on ruling, in basis points of the disputed balance,
+    /// capped at the escrow's reserve
+    pub fee_bps: u32,
+}
+
+/// A consensual settlement recorded in place of an arbiter ruling.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Settlement {
+    pub split_ratio: u32,
+    /// Hash of the signed settlement agreement, kept for off-chain verification
+    pub agreement_hash: BytesN<32>,
+    pub ledger: u32,
+}
+
+/// A party's offer to cancel an escrow, awaiting the other party's consent.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CancellationProposal {
+    pub proposer: Address,
+    /// Percentage of the locked funds refunded to the client; the freelancer
+    /// keeps the rest
+    pub refund_ratio: u32,
+}
+
+/// Asset the freelancer's payouts are converted into through the DEX router.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PayoutConversion {
+    /// Asset delivered to the freelancer
+    pub asset: Address,
+    /// Least units of `asset` accepted per `RATE_SCALE` units of the escrow
+    /// token; a swap filling below this rate reverts the release
+    pub min_rate: i128,
+}
+
+/// Escrows whose milestone total exceeds `threshold` need both parties
+/// verified with the KYC `registry`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct KycRequirement {
+    pub registry: Address,
+    pub threshold: i128,
+}
+
+/// Sponsored transactions granted to the winner of an arbiter ruling.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct WinnerSponsorship {
+    /// `GaslessHandler` that allows this contract as a granter
+    pub gasless: Address,
+    pub transactions: u32,
+}
+
+/// Fee discount granted once a client has completed enough escrows.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct LoyaltyTier {
+    /// Completed escrows required to reach the tier
+    pub min_completed: u32,
+    /// Discount off the fee rate, in basis points of that rate
+    pub discount_bps: u32,
+}
+
+/// Errors raised by the escrow contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum EscrowError {
+    /// No escrow with this id
+    EscrowNotFound = 100,
+    /// The escrow has no milestones
+    NoMilestones = 101,
+    /// A milestone amount is not positive
+    InvalidMilestoneAmount = 102,
+    /// The token is not a supported asset
+    UnsupportedToken = 103,
+    /// The client has reached the active escrow limit
+    TooManyActiveEscrows = 104,
+    /// A rate exceeds 100%
+    InvalidRate = 105,
+    /// The escrow is already funded
+    AlreadyFunded = 106,
+    /// The escrow is not funded
+    NotFunded = 107,
+    /// No milestone at this index
+    MilestoneNotFound = 108,
+    /// The milestone was already paid out
+    AlreadyReleased = 109,
+    /// The client has not approved the milestone
+    NotApproved = 110,
+    /// The freelancer has not confirmed the milestone
+    NotConfirmed = 111,
+    /// The caller is not a party to the escrow
+    NotParty = 112,
+    /// The escrow is not disputed
+    NotDisputed = 113,
+    /// The split ratio is outside 0-100
+    InvalidSplitRatio = 114,
+    /// The earned amount exceeds the held balance
+    EarnedExceedsBalance = 115,
+    /// The dispute has not timed out yet
+    DisputeNotStale = 116,
+    /// Too many escrows were requested at once
+    BatchTooLarge = 117,
+    /// The milestone is not submitted or no review period applied to it
+    NoReviewDeadline = 118,
+    /// The client's review period has not ended yet
+    ReviewNotExpired = 119,
+    /// A partial release is not positive or exceeds the locked remainder
+    InvalidReleaseAmount = 120,
+    /// A payout split has too many recipients or a zero share
+    InvalidPayoutSplit = 121,
+    /// There is no cancellation proposal to accept
+    NoCancellationProposal = 122,
+    /// The proposer cannot accept their own cancellation proposal
+    OwnCancellationProposal = 123,
+    /// The milestone has no agreed deadline
+    NoDeadline = 124,
+    /// A new deadline is not later than the current one
+    InvalidDeadline = 125,
+    /// The milestone's deadline has already passed
+    DeadlinePassed = 126,
+    /// There is no pending extension request for the milestone
+    NoExtensionRequest = 127,
+    /// A payout conversion targets the escrow token or has no positive rate
+    InvalidConversion = 128,
+    /// No DEX router is set to convert payouts through
+    RouterNotSet = 129,
+    /// The router delivered less than the conversion's minimum rate allows
+    SlippageExceeded = 130,
+    /// The escrow is frozen; no funds can leave it
+    EscrowFrozen = 131,
+    /// The escrow is not frozen
+    NotFrozen = 132,
+    /// The escrow is above the KYC threshold and a party is not verified
+    KycRequired = 133,
+    /// The client and freelancer are the same address
+    SelfEscrow = 134,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    EscrowCount,
+    Escrow(u64),
+    ArbiterIncentive,
+    /// Cap on open escrows per client; unlimited when unset
+    MaxActiveEscrows,
+    /// Number of escrows a client has open (not yet completed or resolved)
+    ActiveEscrows(Address),
+    Settlement(u64),
+    /// Contract notified after every milestone release
+    ReleaseHook,
+    /// Platform fee on releases, in basis points
+    FeeBps,
+    /// Fee rate overriding `FeeBps` for escrows in a given token
+    TokenFeeBps(Address),
+    LoyaltyTiers,
+    /// Number of escrows a client has seen through to completion
+    CompletedEscrows(Address),
+    /// Ledgers after which a stalled dispute can be force-refunded
+    StaleDisputeTimeout,
+    /// Last ledger before a disputed escrow can be force-refunded, fixed
+    /// when the dispute is raised
+    StaleRefundLedger(u64),
+    WinnerSponsorship,
+    /// Ledgers a client has to review a submitted milestone; unset disables
+    /// auto-release
+    ReviewPeriod,
+    /// Recipients and shares an escrow's freelancer payouts are split across
+    PayoutSplit(u64),
+    /// Reputation contract escrow and dispute outcomes are reported to
+    Reputation,
+    CancellationProposal(u64),
+    /// Pending deadline extension for a milestone, by escrow id and index
+    DeadlineExtension(u64, u32),
+    /// One page of the escrows a user is the client or freelancer of,
+    /// oldest first, by user and page number
+    UserEscrows(Address, u32),
+    /// Number of escrows a user is the client or freelancer of
+    UserEscrowCount(Address),
+    /// DEX router payout conversions are swapped through
+    Router,
+    /// Asset an escrow's freelancer payouts are converted into
+    PayoutConversion(u64),
+    /// Ledger a frozen escrow was frozen on
+    Frozen(u64),
+    /// KYC registry and the escrow total above which it is consulted
+    KycRequirement,
+    /// Badge contract freelancers are minted completion badges from
+    Badges,
+    /// Escrow total below which completions earn no reputation or badge
+    MinReportedTotal,
+}
+
+/// Escrow contract holding client funds against a list of milestones.
+///
+/// Unlike `MilestoneEscrow`, which is a single escrow per deployment, this
+/// contract manages many escrows keyed by a sequential id.
+#[contract]
+pub struct Escrow;
+
+#[contractimpl]
+impl Escrow {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Assigns a key role. Platform fees go to the treasury contract, or to
+    /// the fee collector while no treasury is assigned.
+    pub fn set_role(env: Env, role: KeyRole, address: Address) {
+        admin::set_role(&env, role, &address);
+    }
+
+    /// Returns the admin and every key role in a single read.
+    pub fn get_roles(env: Env) -> Roles {
+        admin::get_roles(&env)
+    }
+
+    /// Grants `role` to `account`. `caller` must be the role admin.
+    pub fn grant_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::grant_role(&env, &caller, role, &account);
+    }
+
+    /// Revokes `role` from `account`. `caller` must be the role admin.
+    pub fn revoke_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::revoke_role(&env, &caller, role, &account);
+    }
+
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        rbac::has_role(&env, role, &account)
+    }
+
+    /// Returns the account that grants and revokes roles.
+    pub fn role_admin(env: Env) -> Address {
+        rbac::role_admin(&env)
+    }
+
+    /// Hands role administration to `new_admin`, e.g. the multisig
+    /// governance contract. `caller` must be the current role admin.
+    pub fn transfer_role_admin(env: Env, caller: Address, new_admin: Address) {
+        rbac::transfer_role_admin(&env, &caller, &new_admin);
+    }
+
+    /// Extends the TTL of each persistent entry in `keys` that is running
+    /// low, so idle escrows are not archived. Meant for keepers; anyone may
+    /// call it. Returns how many of the entries exist.
+    pub fn bump_entries(env: Env, keys: Vec<DataKey>) -> u32 {
+        ttl::bump_all(&env, &keys)
+    }
+
+    /// Halts funding, releases, refunds and dispute payouts. Admin or
+    /// guardian only.
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause. Admin or guardian only.
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Sets the platform fee deducted from every release and from the
+    /// freelancer's share of every dispute payout.
+    ///
+    /// Fees are only charged while a treasury or fee collector role is
+    /// assigned.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or the rate exceeds 100%
+    pub fn set_fee(env: Env, admin: Address, fee_bps: u32) {
+        admin::require_admin_caller(&env, &admin);
+        Self::check_rate(&env, fee_bps);
+        env.storage().instance().set(&DataKey::FeeBps, &fee_bps);
+    }
+
+    /// Overrides the platform fee for escrows denominated in `token`.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or the rate exceeds 100%
+    pub fn set_token_fee(env: Env, admin: Address, token: Address, fee_bps: u32) {
+        admin::require_admin_caller(&env, &admin);
+        Self::check_rate(&env, fee_bps);
+        env.storage()
+            .instance()
+            .set(&DataKey::TokenFeeBps(token), &fee_bps);
+    }
+
+    /// Replaces the loyalty tiers. A client gets the largest discount among
+    /// the tiers whose `min_completed` they have reached.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or a discount exceeds 100%
+    pub fn set_loyalty_tiers(env: Env, admin: Address, tiers: Vec<LoyaltyTier>) {
+        admin::require_admin_caller(&env, &admin);
+        for tier in tiers.iter() {
+            Self::check_rate(&env, tier.discount_bps);
+        }
+        env.storage().instance().set(&DataKey::LoyaltyTiers, &tiers);
+    }
+
+    /// Returns how many escrows `client` has completed.
+    pub fn completed_escrows(env: Env, client: Address) -> u32 {
+        ttl::get(&env, &DataKey::CompletedEscrows(client)).unwrap_or(0)
+    }
+
+    /// Returns the fee rate, in basis points, that the escrow's next release
+    /// would pay, after the client's loyalty discount.
+    pub fn effective_fee_rate(env: Env, escrow_id: u64) -> u32 {
+        let escrow = Self::load(&env, escrow_id);
+        Self::fee_rate(&env, &escrow)
+    }
+
+    /// Configures the arbiter reserve for escrows created from now on.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or either rate exceeds 100%
+    pub fn set_arbiter_incentive(env: Env, admin: Address, reserve_bps: u32, fee_bps: u32) {
+        admin::require_admin_caller(&env, &admin);
+        if reserve_bps as i128 > BPS_DENOMINATOR || fee_bps as i128 > BPS_DENOMINATOR {
+            panic_with_error!(env, EscrowError::InvalidRate);
+        }
+        env.storage().instance().set(
+            &DataKey::ArbiterIncentive,
+            &ArbiterIncentive {
+                reserve_bps,
+                fee_bps,
+            },
+        );
+    }
+
+    pub fn get_arbiter_incentive(env: Env) -> ArbiterIncentive {
+        Self::arbiter_incentive(&env)
+    }
+
+    /// Caps how many open escrows a single client may have at once.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_max_active_escrows(env: Env, admin: Address, max: u32) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxActiveEscrows, &max);
+    }
+
+    /// Returns the number of open escrows `client` currently has.
+    pub fn active_escrows(env: Env, client: Address) -> u32 {
+        ttl::get(&env, &DataKey::ActiveEscrows(client)).unwrap_or(0)
+    }
+
+    /// Registers (or re-points) a supported asset.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn register_asset(env: Env, admin: Address, asset_code: String, asset: AssetInfo) {
+        assets::register_asset(&env, &admin, asset_code, asset);
+    }
+
+    /// Returns every supported `(asset_code, asset)`, ordered by code.
+    pub fn supported_assets(env: Env) -> Vec<(String, AssetInfo)> {
+        assets::supported_assets(&env)
+    }
+
+    /// Registers a contract to be notified after every milestone release.
+    ///
+    /// The hook must expose
+    /// `on_release(escrow_id: u64, milestone_index: u32, freelancer: Address, amount: i128)`.
+    /// Hook failures are ignored so they can never block a payout.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_release_hook(env: Env, admin: Address, hook: Address) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage().instance().set(&DataKey::ReleaseHook, &hook);
+    }
+
+    /// Returns the registered release hook, if any.
+    pub fn release_hook(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ReleaseHook)
+    }
+
+    /// Registers the reputation contract that completed escrows, lost
+    /// rulings and missed deadlines are reported to. This contract must be
+    /// one of its reporters; failed reports are ignored.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_reputation(env: Env, admin: Address, reputation: Address) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::Reputation, &reputation);
+    }
+
+    /// Returns the registered reputation contract, if any.
+    pub fn reputation(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Reputation)
+    }
+
+    /// Registers the badge contract freelancers are minted a badge from when
+    /// an escrow completes or a ruling goes their way. This contract must be
+    /// one of its issuers; failed mints are ignored.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_badges(env: Env, admin: Address, badges: Address) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage().instance().set(&DataKey::Badges, &badges);
+    }
+
+    /// Returns the registered badge contract, if any.
+    pub fn badges(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Badges)
+    }
+
+    /// Sets the escrow total below which completing an escrow reports no
+    /// reputation and mints no badge, so dust escrows cannot be cycled to
+    /// farm either.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_min_reported_total(env: Env, admin: Address, amount: i128) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::MinReportedTotal, &amount);
+    }
+
+    /// Returns the escrow total below which completions are not reported,
+    /// `DEFAULT_MIN_REPORTED_TOTAL` unless set.
+    pub fn min_reported_total(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinReportedTotal)
+            .unwrap_or(DEFAULT_MIN_REPORTED_TOTAL)
+    }
+
+    /// Sets the DEX router payout conversions are swapped through, the same
+    /// router the liquidity rebalancer uses.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_router(env: Env, admin: Address, router: Address) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage().instance().set(&DataKey::Router, &router);
+    }
+
+    /// Returns the DEX router, if one is set.
+    pub fn router(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Router)
+    }
+
+    /// Withdraws everything credited to `recipient` in `asset` by releases,
+    /// refunds and dispute payouts, and returns the amount.
+    pub fn claim(env: Env, recipient: Address, asset: Address) -> i128 {
+        claimable::claim(&env, &recipient, &asset)
+    }
+
+    /// Returns what `user` may claim in `asset`.
+    pub fn claimable_balance(env: Env, user: Address, asset: Address) -> i128 {
+        claimable::balance(&env, &user, &asset)
+    }
+
+    /// Requires both parties of escrows whose milestone total exceeds
+    /// `requirement.threshold` to be verified with `requirement.registry`
+    /// when the escrow is created and funded. Escrows at or below the
+    /// threshold stay permissionless. Passing `None` lifts the requirement.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_kyc_requirement(env: Env, admin: Address, requirement: Option<KycRequirement>) {
+        admin::require_admin_caller(&env, &admin);
+        match requirement {
+            Some(requirement) => env
+                .storage()
+                .instance()
+                .set(&DataKey::KycRequirement, &requirement),
+            None => env.storage().instance().remove(&DataKey::KycRequirement),
+        }
+    }
+
+    /// Returns the KYC requirement, if one is set.
+    pub fn kyc_requirement(env: Env) -> Option<KycRequirement> {
+        env.storage().instance().get(&DataKey::KycRequirement)
+    }
+
+    /// Creates a new escrow and returns its id.
+    ///
+    /// If an arbiter incentive is configured, an arbiter reserve of
+    /// `reserve_bps` of the milestone total is added to the funding amount.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `client` - Address paying for the work
+    /// * `freelancer` - Address receiving milestone payouts
+    /// * `arbiter` - Address allowed to intervene in disputes
+    /// * `token` - Token contract the escrow is denominated in; must be a
+    ///   supported asset once any are registered
+    /// * `milestones` - `(amount, description)` of each milestone, in order
+    /// * `dual_approval` - Require both parties to confirm each milestone
+    ///
+    /// # Panics
+    /// If the client is also the freelancer, there are no milestones, any
+    /// amount is not positive, the token is not a supported asset, or the
+    /// client already has the maximum number of open escrows
+    pub fn create_escrow(
+        env: Env,
+        client: Address,
+        freelancer: Address,
+        arbiter: Address,
+        token: Address,
+        milestones: Vec<(i128, String)>,
+        dual_approval: bool,
+    ) -> u64 {
+        client.require_auth();
+
+        if client == freelancer {
+            panic_with_error!(env, EscrowError::SelfEscrow);
+        }
+
+        if !assets::is_empty(&env) && !assets::is_supported_token(&env, &token) {
+            panic_with_error!(env, EscrowError::UnsupportedToken);
+        }
+
+        let active = Self::active_escrows(env.clone(), client.clone());
+        if let Some(max) = env
+            .storage()
+            .instance()
+            .get::<DataKey, u32>(&DataKey::MaxActiveEscrows)
+        {
+            if active >= max {
+                panic_with_error!(env, EscrowError::TooManyActiveEscrows);
+            }
+        }
+
+        if milestones.is_empty() {
+            panic_with_error!(env, EscrowError::NoMilestones);
+        }
+
+        let specs = milestones;
+        let mut milestones = Vec::new(&env);
+        let mut total: i128 = 0;
+        for (amount, description) in specs.iter() {
+            if amount <= 0 {
+                panic_with_error!(env, EscrowError::InvalidMilestoneAmount);
+            }
+            total += amount;
+            milestones.push_back(Milestone {
+                amount,
+                description,
+                client_confirmed: false,
+                freelancer_confirmed: false,
+                released: false,
+                released_amount: 0,
+                review_deadline: None,
+                deadline: None,
+            });
+        }
+
+        Self::require_kyc(&env, &client, &freelancer, total);
+
+        let escrow_id = env
+            .storage()
+            .instance()
+            .get::<DataKey, u64>(&DataKey::EscrowCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::EscrowCount, &escrow_id);
+        ttl::set(&env, &DataKey::ActiveEscrows(client.clone()), &(active + 1));
+        Self::index_escrow(&env, &client, escrow_id);
+        Self::index_escrow(&env, &freelancer, escrow_id);
+
+        Self::save(
+            &env,
+            escrow_id,
+            &EscrowData {
+                client,
+                freelancer,
+                arbiter,
+                token,
+                status: EscrowStatus::Pending,
+                dual_approval,
+                milestones,
+                arbiter_reserve: total * Self::arbiter_incentive(&env).reserve_bps as i128
+                    / BPS_DENOMINATOR,
+                disputed_ledger: None,
+                created_ledger: env.ledger().sequence(),
+            },
+        );
+
+        escrow_id
+    }
+
+    /// Deposits the full milestone total plus the arbiter reserve from the
+    /// client into the contract.
+    ///
+    /// # Panics
+    /// If the escrow is not awaiting funding
+    pub fn fund(env: Env, escrow_id: u64) {
+        pausable::require_not_paused(&env);
+        let mut escrow = Self::load(&env, escrow_id);
+        escrow.client.require_auth();
+
+        if escrow.status != EscrowStatus::Pending {
+            panic_with_error!(env, EscrowError::AlreadyFunded);
+        }
+
+        let milestone_total: i128 = escrow.milestones.iter().map(|m| m.amount).sum();
+        Self::require_kyc(&env, &escrow.client, &escrow.freelancer, milestone_total);
+        let total = milestone_total + escrow.arbiter_reserve;
+        token::Client::new(&env, &escrow.token).transfer(
+            &escrow.client,
+            &env.current_contract_address(),
+            &total,
+        );
+
+        escrow.status = EscrowStatus::Funded;
+        Self::save(&env, escrow_id, &escrow);
+
+        events::emit_escrow_funded(&env, escrow_id, total);
+    }
+
+    /// Freelancer confirms that a milestone's work is complete. Starts the
+    /// client's review period, if one is configured.
+    pub fn submit_milestone(env: Env, escrow_id: u64, milestone_index: u32) {
+        let mut escrow = Self::load(&env, escrow_id);
+        escrow.freelancer.require_auth();
+
+        let mut milestone = Self::open_milestone(&escrow, milestone_index);
+        milestone.freelancer_confirmed = true;
+        let period = Self::review_period(env.clone());
+        if period > 0 {
+            milestone.review_deadline = Some(env.ledger().sequence().saturating_add(period));
+        }
+        escrow.milestones.set(milestone_index, milestone);
+        Self::save(&env, escrow_id, &escrow);
+    }
+
+    /// Sets the ledger a milestone is due by. Both the client and the
+    /// freelancer must authorize the call; afterwards the deadline only moves
+    /// through an approved extension.
+    ///
+    /// # Panics
+    /// If the milestone already has a deadline, is released, or `deadline`
+    /// has already passed
+    pub fn set_milestone_deadline(env: Env, escrow_id: u64, milestone_index: u32, deadline: u32) {
+        let mut escrow = Self::load(&env, escrow_id);
+        escrow.client.require_auth();
+        escrow.freelancer.require_auth();
+
+        let mut milestone = Self::open_milestone(&escrow, milestone_index);
+        if milestone.deadline.is_some() || deadline <= env.ledger().sequence() {
+            panic_with_error!(env, EscrowError::InvalidDeadline);
+        }
+        milestone.deadline = Some(deadline);
+        escrow.milestones.set(milestone_index, milestone);
+        Self::save(&env, escrow_id, &escrow);
+    }
+
+    /// Freelancer asks to move a milestone's deadline to `new_deadline`,
+    /// replacing any earlier request. The request lapses unless the client
+    /// approves it by the current deadline.
+    ///
+    /// # Panics
+    /// If the milestone has no deadline, it has passed, or `new_deadline` is
+    /// not later than it
+    pub fn request_deadline_extension(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        new_deadline: u32,
+    ) {
+        let escrow = Self::load(&env, escrow_id);
+        escrow.freelancer.require_auth();
+
+        let deadline = Self::open_milestone(&escrow, milestone_index)
+            .deadline
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::NoDeadline));
+        if env.ledger().sequence() > deadline {
+            panic_with_error!(env, EscrowError::DeadlinePassed);
+        }
+        if new_deadline <= deadline {
+            panic_with_error!(env, EscrowError::InvalidDeadline);
+        }
+        ttl::set(
+            &env,
+            &DataKey::DeadlineExtension(escrow_id, milestone_index),
+            &DeadlineExtension {
+                new_deadline,
+                expires: deadline,
+            },
+        );
+        events::emit_deadline_extension_requested(&env, escrow_id, milestone_index, new_deadline);
+    }
+
+    /// Client approves the pending extension request, moving the milestone's
+    /// deadline.
+    ///
+    /// # Panics
+    /// If there is no pending request or it lapsed at the old deadline
+    pub fn approve_extension(env: Env, escrow_id: u64, milestone_index: u32) {
+        let mut escrow = Self::load(&env, escrow_id);
+        escrow.client.require_auth();
+
+        let key = DataKey::DeadlineExtension(escrow_id, milestone_index);
+        let request = Self::get_extension_request(env.clone(), escrow_id, milestone_index)
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::NoExtensionRequest));
+        env.storage().persistent().remove(&key);
+
+        let mut milestone = Self::open_milestone(&escrow, milestone_index);
+        milestone.deadline = Some(request.new_deadline);
+        escrow.milestones.set(milestone_index, milestone);
+        Self::save(&env, escrow_id, &escrow);
+        events::emit_deadline_extended(&env, escrow_id, milestone_index, request.new_deadline);
+    }
+
+    /// Returns the pending extension request for a milestone, or `None` if
+    /// there is none or it has lapsed.
+    pub fn get_extension_request(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Option<DeadlineExtension> {
+        let request: DeadlineExtension = ttl::get(
+            &env,
+            &DataKey::DeadlineExtension(escrow_id, milestone_index),
+        )?;
+        if env.ledger().sequence() > request.expires {
+            return None;
+        }
+        Some(request)
+    }
+
+    /// Splits the freelancer's future payouts across `split`, each recipient
+    /// receiving a portion proportional to its shares. An empty `split` pays
+    /// the freelancer alone again. Requires the freelancer's auth.
+    ///
+    /// # Panics
+    /// If `split` has more than `MAX_SPLIT_RECIPIENTS` entries or a zero share
+    pub fn set_payout_split(env: Env, escrow_id: u64, split: Vec<(Address, u32)>) {
+        let escrow = Self::load(&env, escrow_id);
+        escrow.freelancer.require_auth();
+
+        let key = DataKey::PayoutSplit(escrow_id);
+        if split.is_empty() {
+            env.storage().persistent().remove(&key);
+            return;
+        }
+        if split.len() > MAX_SPLIT_RECIPIENTS || split.iter().any(|(_, shares)| shares == 0) {
+            panic_with_error!(env, EscrowError::InvalidPayoutSplit);
+        }
+        ttl::set(&env, &key, &split);
+    }
+
+    /// Returns the escrow's payout split, if the freelancer has set one.
+    pub fn payout_split(env: Env, escrow_id: u64) -> Option<Vec<(Address, u32)>> {
+        ttl::get(&env, &DataKey::PayoutSplit(escrow_id))
+    }
+
+    /// Converts the freelancer's future payouts into `conversion.asset`
+    /// through the DEX router, or pays them in the escrow token again when
+    /// `conversion` is `None`. Requires the freelancer's auth.
+    ///
+    /// # Panics
+    /// If the conversion targets the escrow token, has no positive rate or
+    /// names an unsupported asset, or no router is set
+    pub fn set_payout_conversion(env: Env, escrow_id: u64, conversion: Option<PayoutConversion>) {
+        let escrow = Self::load(&env, escrow_id);
+        escrow.freelancer.require_auth();
+
+        let key = DataKey::PayoutConversion(escrow_id);
+        let Some(conversion) = conversion else {
+            env.storage().persistent().remove(&key);
+            return;
+        };
+        if conversion.asset == escrow.token || conversion.min_rate <= 0 {
+            panic_with_error!(env, EscrowError::InvalidConversion);
+        }
+        if !assets::is_empty(&env) && !assets::is_supported_token(&env, &conversion.asset) {
+            panic_with_error!(env, EscrowError::UnsupportedToken);
+        }
+        if Self::router(env.clone()).is_none() {
+            panic_with_error!(env, EscrowError::RouterNotSet);
+        }
+        ttl::set(&env, &key, &conversion);
+    }
+
+    /// Returns the escrow's payout conversion, if the freelancer has set one.
+    pub fn payout_conversion(env: Env, escrow_id: u64) -> Option<PayoutConversion> {
+        ttl::get(&env, &DataKey::PayoutConversion(escrow_id))
+    }
+
+    /// Client confirms that a milestone has been delivered to their satisfaction.
+    pub fn approve_milestone(env: Env, escrow_id: u64, milestone_index: u32) {
+        let mut escrow = Self::load(&env, escrow_id);
+        escrow.client.require_auth();
+
+        let mut milestone = Self::open_milestone(&escrow, milestone_index);
+        milestone.client_confirmed = true;
+        escrow.milestones.set(milestone_index, milestone);
+        Self::save(&env, escrow_id, &escrow);
+    }
+
+    /// Returns where a milestone stands in its lifecycle.
+    ///
+    /// # Panics
+    /// If the escrow or milestone does not exist
+    pub fn milestone_status(env: Env, escrow_id: u64, milestone_index: u32) -> MilestoneStatus {
+        let milestone = Self::load(&env, escrow_id)
+            .milestones
+            .get(milestone_index)
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::MilestoneNotFound));
+        if milestone.released {
+            MilestoneStatus::Released
+        } else if milestone.client_confirmed {
+            MilestoneStatus::Approved
+        } else if milestone.freelancer_confirmed {
+            MilestoneStatus::Submitted
+        } else {
+            MilestoneStatus::Pending
+        }
+    }
+
+    /// Pays a confirmed milestone out to the freelancer.
+    ///
+    /// Anyone may trigger the payout once the required confirmations are
+    /// recorded; the recipient is always the escrow's freelancer.
+    ///
+    /// # Errors
+    /// If the escrow is not funded or the milestone lacks the required confirmations
+    pub fn release(env: Env, escrow_id: u64, milestone_index: u32) -> Result<(), EscrowError> {
+        pausable::require_not_paused(&env);
+        let escrow = Self::load(&env, escrow_id);
+        let milestone = Self::releasable_milestone(&escrow, milestone_index)?;
+        let remaining = milestone.amount - milestone.released_amount;
+        Self::pay_milestone(
+            &env,
+            escrow_id,
+            escrow,
+            milestone_index,
+            milestone,
+            remaining,
+        );
+        Ok(())
+    }
+
+    /// Pays `amount` of a milestone out to the freelancer and keeps the rest
+    /// locked. Unless that empties the milestone, it goes back for rework:
+    /// both confirmations are cleared and the freelancer must submit again.
+    /// Requires the client's auth.
+    ///
+    /// # Errors
+    /// If the escrow is not funded, the milestone is missing or released,
+    /// or `amount` is not positive or exceeds what is still locked
+    pub fn release_partial(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        amount: i128,
+    ) -> Result<(), EscrowError> {
+        pausable::require_not_paused(&env);
+        let escrow = Self::load(&env, escrow_id);
+        escrow.client.require_auth();
+        if escrow.status != EscrowStatus::Funded {
+            return Err(EscrowError::NotFunded);
+        }
+        let mut milestone = escrow
+            .milestones
+            .get(milestone_index)
+            .ok_or(EscrowError::MilestoneNotFound)?;
+        if milestone.released {
+            return Err(EscrowError::AlreadyReleased);
+        }
+        if amount <= 0 || amount > milestone.amount - milestone.released_amount {
+            return Err(EscrowError::InvalidReleaseAmount);
+        }
+
+        milestone.client_confirmed = false;
+        milestone.freelancer_confirmed = false;
+        milestone.review_deadline = None;
+        Self::pay_milestone(&env, escrow_id, escrow, milestone_index, milestone, amount);
+        Ok(())
+    }
+
+    /// Pays out a submitted milestone the client neither approved nor
+    /// disputed before its review deadline. Anyone may call this; the
+    /// recipient is always the escrow's freelancer.
+    ///
+    /// # Errors
+    /// If the escrow is not funded, the milestone is released or has no
+    /// review deadline, or the deadline has not passed
+    pub fn claim_expired_milestone(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Result<(), EscrowError> {
+        pausable::require_not_paused(&env);
+        let escrow = Self::load(&env, escrow_id);
+        if escrow.status != EscrowStatus::Funded {
+            return Err(EscrowError::NotFunded);
+        }
+        let mut milestone = escrow
+            .milestones
+            .get(milestone_index)
+            .ok_or(EscrowError::MilestoneNotFound)?;
+        if milestone.released {
+            return Err(EscrowError::AlreadyReleased);
+        }
+        let deadline = milestone
+            .review_deadline
+            .ok_or(EscrowError::NoReviewDeadline)?;
+        if env.ledger().sequence() <= deadline {
+            return Err(EscrowError::ReviewNotExpired);
+        }
+
+        // Silence past the deadline counts as the client's approval.
+        milestone.client_confirmed = true;
+        Self::report(&env, &escrow.client, Outcome::DeadlineMissed);
+        let remaining = milestone.amount - milestone.released_amount;
+        Self::pay_milestone(
+            &env,
+            escrow_id,
+            escrow,
+            milestone_index,
+            milestone,
+            remaining,
+        );
+        Ok(())
+    }
+
+    /// Sets how many ledgers a client has to review a submitted milestone
+    /// before the freelancer can claim it. Zero disables auto-release for
+    /// milestones submitted afterwards.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_review_period(env: Env, admin: Address, ledgers: u32) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::ReviewPeriod, &ledgers);
+    }
+
+    /// Returns the review period in ledgers (zero when disabled).
+    pub fn review_period(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ReviewPeriod)
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if `release` would currently succeed for the milestone.
+    ///
+    /// Lets UIs disable the release action instead of submitting a doomed
+    /// transaction. Unknown escrows and milestones report `false`.
+    pub fn can_release(env: Env, escrow_id: u64, milestone_index: u32) -> bool {
+        match ttl::get::<DataKey, EscrowData>(&env, &DataKey::Escrow(escrow_id)) {
+            Some(escrow) => Self::releasable_milestone(&escrow, milestone_index).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Freezes a funded escrow pending the arbiter's ruling.
+    ///
+    /// # Panics
+    /// If `caller` is not the client or freelancer, or the escrow is not funded
+    pub fn raise_dispute(env: Env, escrow_id: u64, caller: Address) {
+        caller.require_auth();
+
+        let mut escrow = Self::load(&env, escrow_id);
+        if caller != escrow.client && caller != escrow.freelancer {
+            panic_with_error!(env, EscrowError::NotParty);
+        }
+        if escrow.status != EscrowStatus::Funded {
+            panic_with_error!(env, EscrowError::NotFunded);
+        }
+
+        escrow.status = EscrowStatus::Disputed;
+        escrow.disputed_ledger = Some(env.ledger().sequence());
+        Self::save(&env, escrow_id, &escrow);
+        ttl::set(
+            &env,
+            &DataKey::StaleRefundLedger(escrow_id),
+            &env.ledger()
+                .sequence()
+                .saturating_add(Self::stale_dispute_timeout(env.clone())),
+        );
+
+        events::emit_escrow_disputed(&env, escrow_id, &caller);
+    }
+
+    /// Sets how long a dispute may stay unresolved before `force_refund_stale`
+    /// becomes available. Disputes already raised keep the timeout that was
+    /// in force when they were raised.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_stale_dispute_timeout(env: Env, admin: Address, ledgers: u32) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::StaleDisputeTimeout, &ledgers);
+    }
+
+    /// Returns the stale-dispute timeout in ledgers.
+    pub fn stale_dispute_timeout(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::StaleDisputeTimeout)
+            .unwrap_or(DEFAULT_STALE_DISPUTE_TIMEOUT)
+    }
+
+    /// Failsafe for disputes nobody resolves: once the stale-dispute timeout
+    /// has passed since the dispute was raised, anyone may return the
+    /// unreleased balance and the arbiter reserve to the client.
+    ///
+    /// # Panics
+    /// If the escrow is not disputed or the timeout has not elapsed
+    pub fn force_refund_stale(env: Env, escrow_id: u64) {
+        pausable::require_not_paused(&env);
+        let mut escrow = Self::load(&env, escrow_id);
+        if escrow.status != EscrowStatus::Disputed {
+            panic_with_error!(env, EscrowError::NotDisputed);
+        }
+        let stale_after =
+            ttl::get(&env, &DataKey::StaleRefundLedger(escrow_id)).unwrap_or_else(|| {
+                // Raised before the timeout was fixed at dispute time.
+                escrow
+                    .disputed_ledger
+                    .unwrap_or(0)
+                    .saturating_add(Self::stale_dispute_timeout(env.clone()))
+            });
+        if env.ledger().sequence() <= stale_after {
+            panic_with_error!(env, EscrowError::DisputeNotStale);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::StaleRefundLedger(escrow_id));
+
+        Self::require_not_frozen(&env, escrow_id);
+        let refund = Self::unreleased(&escrow) + escrow.arbiter_reserve;
+        Self::pay(&env, &escrow, &escrow.client, refund);
+
+        for i in 0..escrow.milestones.len() {
+            let mut milestone = escrow.milestones.get(i).unwrap();
+            milestone.released = true;
+            escrow.milestones.set(i, milestone);
+        }
+        escrow.status = EscrowStatus::Refunded;
+        Self::close(&env, &escrow);
+        Self::save(&env, escrow_id, &escrow);
+        // The arbiter let the dispute go stale without ruling.
+        Self::report(&env, &escrow.arbiter, Outcome::DeadlineMissed);
+
+        events::emit_escrow_refunded(&env, escrow_id, refund);
+    }
+
+    /// Freezes an escrow for fraud or chargeback review, blocking releases,
+    /// refunds, cancellations and dispute payouts until it is unfrozen.
+    /// Admin only; the admin should be the multisig governance contract so
+    /// freezes pass its approval quorum.
+    ///
+    /// # Panics
+    /// If the escrow does not exist or is already frozen
+    pub fn freeze_escrow(env: Env, escrow_id: u64) {
+        let admin = admin::require_admin(&env);
+        Self::load(&env, escrow_id);
+        let key = DataKey::Frozen(escrow_id);
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, EscrowError::EscrowFrozen);
+        }
+        ttl::set(&env, &key, &env.ledger().sequence());
+        events::emit_escrow_frozen(&env, escrow_id, &admin);
+    }
+
+    /// Lifts a freeze. Admin only.
+    ///
+    /// # Panics
+    /// If the escrow is not frozen
+    pub fn unfreeze_escrow(env: Env, escrow_id: u64) {
+        let admin = admin::require_admin(&env);
+        Self::frozen_ledger(&env, escrow_id);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Frozen(escrow_id));
+        events::emit_escrow_unfrozen(&env, escrow_id, &admin);
+    }
+
+    /// Returns `true` while the escrow is frozen.
+    pub fn is_frozen(env: Env, escrow_id: u64) -> bool {
+        env.storage().persistent().has(&DataKey::Frozen(escrow_id))
+    }
+
+    /// Sends everything a frozen escrow still holds, including the arbiter
+    /// reserve, to `to` and closes it as `ClawedBack`. Restricted to holders
+    /// of the compliance role; always emits `escrow_clawed_back` with the
+    /// officer, recipient, asset and amount for the audit trail.
+    ///
+    /// # Panics
+    /// If `caller` lacks the compliance role, or the escrow is not frozen or
+    /// holds no funds
+    pub fn clawback(env: Env, escrow_id: u64, caller: Address, to: Address) {
+        rbac::require_role(&env, Role::Compliance, &caller);
+        let mut escrow = Self::load(&env, escrow_id);
+        let frozen_ledger = Self::frozen_ledger(&env, escrow_id);
+        if !matches!(escrow.status, EscrowStatus::Funded | EscrowStatus::Disputed) {
+            panic_with_error!(env, EscrowError::NotFunded);
+        }
+
+        let amount = Self::unreleased(&escrow) + escrow.arbiter_reserve;
+        Self::pay(&env, &escrow, &to, amount);
+        for i in 0..escrow.milestones.len() {
+            let mut milestone = escrow.milestones.get(i).unwrap();
+            milestone.released = true;
+            escrow.milestones.set(i, milestone);
+        }
+        escrow.status = EscrowStatus::ClawedBack;
+        Self::close(&env, &escrow);
+        Self::save(&env, escrow_id, &escrow);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Frozen(escrow_id));
+
+        events::emit_escrow_clawed_back(
+            &env,
+            escrow_id,
+            &caller,
+            &to,
+            &escrow.token,
+            amount,
+            frozen_ledger,
+        );
+    }
+
+    /// Sponsors the claim transactions of whichever party wins an arbiter
+    /// ruling, via the given gasless handler. `transactions` of zero
+    /// disables sponsorship.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin
+    pub fn set_winner_sponsorship(env: Env, admin: Address, gasless: Address, transactions: u32) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage().instance().set(
+            &DataKey::WinnerSponsorship,
+            &WinnerSponsorship {
+                gasless,
+                transactions,
+            },
+        );
+    }
+
+    pub fn get_winner_sponsorship(env: Env) -> Option<WinnerSponsorship> {
+        env.storage().instance().get(&DataKey::WinnerSponsorship)
+    }
+
+    /// Arbiter ruling on a disputed escrow.
+    ///
+    /// The arbiter's fee is paid from the escrow's reserve, never from the
+    /// disputed balance. The unreleased milestone balance is then split
+    /// `split_ratio`% to the freelancer and the rest to the client, and any
+    /// unused reserve is refunded to the client.
+    ///
+    /// # Panics
+    /// If the escrow is not disputed or `split_ratio` > 100
+    pub fn resolve_dispute(env: Env, escrow_id: u64, split_ratio: u32) {
+        pausable::require_not_paused(&env);
+        let mut escrow = Self::load(&env, escrow_id);
+        escrow.arbiter.require_auth();
+
+        if escrow.status != EscrowStatus::Disputed {
+            panic_with_error!(env, EscrowError::NotDisputed);
+        }
+
+        let arbiter_fee = Self::arbiter_fee(&env, &escrow);
+        let freelancer_amount = Self::split_amount(&escrow, split_ratio);
+        let disputed = Self::unreleased(&escrow);
+        Self::distribute(
+            &env,
+            escrow_id,
+            &mut escrow,
+            split_ratio,
+            freelancer_amount,
+            arbiter_fee,
+        );
+        Self::sponsor_winner(&env, &escrow, freelancer_amount, disputed);
+        Self::report_loser(&env, &escrow, freelancer_amount, disputed);
+        Self::badge_winner(&env, escrow_id, &escrow, freelancer_amount, disputed);
+    }
+
+    /// Arbiter ruling that only `earned` of a disputed installment-funded
+    /// escrow was earned.
+    ///
+    /// The freelancer is paid `earned` out of the held, unreleased balance;
+    /// the unearned remainder is clawed back to the client together with the
+    /// unused reserve. The arbiter fee is charged as in `resolve_dispute`.
+    ///
+    /// # Panics
+    /// If the escrow is not disputed, or `earned` is negative or exceeds the
+    /// held balance
+    pub fn resolve_with_clawback(env: Env, escrow_id: u64, earned: i128) {
+        pausable::require_not_paused(&env);
+        let mut escrow = Self::load(&env, escrow_id);
+        escrow.arbiter.require_auth();
+
+        if escrow.status != EscrowStatus::Disputed {
+            panic_with_error!(env, EscrowError::NotDisputed);
+        }
+        let held = Self::unreleased(&escrow);
+        if earned < 0 || earned > held {
+            panic_with_error!(env, EscrowError::EarnedExceedsBalance);
+        }
+
+        let arbiter_fee = Self::arbiter_fee(&env, &escrow);
+        // Reported split, rounded down to a whole percentage.
+        let split_ratio = (earned * 100 / held) as u32;
+        Self::distribute(
+            &env,
+            escrow_id,
+            &mut escrow,
+            split_ratio,
+            earned,
+            arbiter_fee,
+        );
+        Self::sponsor_winner(&env, &escrow, earned, held);
+        Self::report_loser(&env, &escrow, earned, held);
+        Self::badge_winner(&env, escrow_id, &escrow, earned, held);
+    }
+
+    /// Settles a disputed escrow on terms agreed by both parties, without an
+    /// arbiter ruling.
+    ///
+    /// Both the client and the freelancer must authorize the call. The hash of
+    /// their signed agreement is stored with the settlement so the terms can
+    /// be verified off-chain. No arbiter fee is charged; the full reserve is
+    /// refunded to the client.
+    ///
+    /// # Panics
+    /// If the escrow is not disputed or `split_ratio` > 100
+    pub fn settle_dispute(env: Env, escrow_id: u64, split_ratio: u32, agreement_hash: BytesN<32>) {
+        pausable::require_not_paused(&env);
+        let mut escrow = Self::load(&env, escrow_id);
+        escrow.client.require_auth();
+        escrow.freelancer.require_auth();
+
+        if escrow.status != EscrowStatus::Disputed {
+            panic_with_error!(env, EscrowError::NotDisputed);
+        }
+
+        let freelancer_amount = Self::split_amount(&escrow, split_ratio);
+        Self::distribute(
+            &env,
+            escrow_id,
+            &mut escrow,
+            split_ratio,
+            freelancer_amount,
+            0,
+        );
+
+        ttl::set(
+            &env,
+            &DataKey::Settlement(escrow_id),
+            &Settlement {
+                split_ratio,
+                agreement_hash: agreement_hash.clone(),
+                ledger: env.ledger().sequence(),
+            },
+        );
+        events::emit_escrow_settled(&env, escrow_id, split_ratio, &agreement_hash);
+    }
+
+    /// Returns the recorded settlement for `escrow_id`, if the parties settled.
+    pub fn get_settlement(env: Env, escrow_id: u64) -> Option<Settlement> {
+        ttl::get(&env, &DataKey::Settlement(escrow_id))
+    }
+
+    /// Proposes cancelling a funded escrow, refunding `refund_ratio`% of the
+    /// locked funds to the client and paying the rest to the freelancer.
+    /// Replaces any earlier proposal. `caller` must be a party.
+    ///
+    /// # Panics
+    /// If `caller` is not a party, the escrow is not funded or
+    /// `refund_ratio` > 100
+    pub fn propose_cancellation(env: Env, escrow_id: u64, caller: Address, refund_ratio: u32) {
+        caller.require_auth();
+        let escrow = Self::load(&env, escrow_id);
+        if caller != escrow.client && caller != escrow.freelancer {
+            panic_with_error!(env, EscrowError::NotParty);
+        }
+        if escrow.status != EscrowStatus::Funded {
+            panic_with_error!(env, EscrowError::NotFunded);
+        }
+        if refund_ratio > 100 {
+            panic_with_error!(env, EscrowError::InvalidSplitRatio);
+        }
+        ttl::set(
+            &env,
+            &DataKey::CancellationProposal(escrow_id),
+            &CancellationProposal {
+                proposer: caller,
+                refund_ratio,
+            },
+        );
+    }
+
+    /// Accepts the other party's cancellation proposal: the locked funds are
+    /// split as proposed, the arbiter reserve goes back to the client and the
+    /// escrow is cancelled. `caller` must be the party that did not propose.
+    ///
+    /// # Panics
+    /// If there is no proposal, `caller` is not the other party or the
+    /// escrow is no longer funded
+    pub fn accept_cancellation(env: Env, escrow_id: u64, caller: Address) {
+        pausable::require_not_paused(&env);
+        caller.require_auth();
+        let mut escrow = Self::load(&env, escrow_id);
+        let key = DataKey::CancellationProposal(escrow_id);
+        let proposal: CancellationProposal = ttl::get(&env, &key)
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::NoCancellationProposal));
+        if caller == proposal.proposer {
+            panic_with_error!(env, EscrowError::OwnCancellationProposal);
+        }
+        if caller != escrow.client && caller != escrow.freelancer {
+            panic_with_error!(env, EscrowError::NotParty);
+        }
+        if escrow.status != EscrowStatus::Funded {
+            panic_with_error!(env, EscrowError::NotFunded);
+        }
+        Self::require_not_frozen(&env, escrow_id);
+        env.storage().persistent().remove(&key);
+
+        let locked = Self::unreleased(&escrow);
+        let client_amount = locked * proposal.refund_ratio as i128 / 100;
+        let freelancer_amount = locked - client_amount;
+        let fee = Self::charge_fee(&env, &escrow, freelancer_amount);
+        Self::pay_freelancer(&env, escrow_id, &escrow, freelancer_amount - fee);
+        Self::pay(
+            &env,
+            &escrow,
+            &escrow.client,
+            client_amount + escrow.arbiter_reserve,
+        );
+
+        for i in 0..escrow.milestones.len() {
+            let mut milestone = escrow.milestones.get(i).unwrap();
+            milestone.released = true;
+            escrow.milestones.set(i, milestone);
+        }
+        escrow.status = EscrowStatus::Cancelled;
+        Self::close(&env, &escrow);
+        Self::save(&env, escrow_id, &escrow);
+
+        events::emit_escrow_cancelled(
+            &env,
+            escrow_id,
+            proposal.refund_ratio,
+            freelancer_amount,
+            client_amount,
+        );
+    }
+
+    /// Returns the pending cancellation proposal for `escrow_id`, if any.
+    pub fn get_cancellation_proposal(env: Env, escrow_id: u64) -> Option<CancellationProposal> {
+        ttl::get(&env, &DataKey::CancellationProposal(escrow_id))
+    }
+
+    /// Client withdraws an escrow that was never funded, freeing its
+    /// active-escrow slot. Nothing is held, so nothing is paid out.
+    ///
+    /// # Panics
+    /// If the escrow is no longer awaiting funding
+    pub fn cancel_unfunded(env: Env, escrow_id: u64) {
+        let mut escrow = Self::load(&env, escrow_id);
+        escrow.client.require_auth();
+        if escrow.status != EscrowStatus::Pending {
+            panic_with_error!(env, EscrowError::AlreadyFunded);
+        }
+
+        escrow.status = EscrowStatus::Cancelled;
+        Self::close(&env, &escrow);
+        Self::save(&env, escrow_id, &escrow);
+
+        events::emit_escrow_cancelled(&env, escrow_id, 100, 0, 0);
+    }
+
+    /// Returns a summary of each escrow in `ids`, in order, or `None` for
+    /// ids that do not exist.
+    ///
+    /// # Panics
+    /// If more than `MAX_SUMMARY_BATCH` ids are requested
+    pub fn escrow_summaries(env: Env, ids: Vec<u64>) -> Vec<Option<EscrowSummary>> {
+        if ids.len() > MAX_SUMMARY_BATCH {
+            panic_with_error!(env, EscrowError::BatchTooLarge);
+        }
+
+        let mut summaries = Vec::new(&env);
+        for id in ids.iter() {
+            let summary = ttl::get::<DataKey, EscrowData>(&env, &DataKey::Escrow(id))
+                .map(|escrow| Self::summarize(id, escrow));
+            summaries.push_back(summary);
+        }
+        summaries
+    }
+
+    /// Returns up to `limit` summaries of the escrows `user` is the client or
+    /// freelancer of, oldest first, skipping the first `start`.
+    ///
+    /// # Panics
+    /// If `limit` exceeds `MAX_SUMMARY_BATCH`
+    pub fn list_escrows_by_user(
+        env: Env,
+        user: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<EscrowSummary> {
+        if limit > MAX_SUMMARY_BATCH {
+            panic_with_error!(env, EscrowError::BatchTooLarge);
+        }
+        let end =
+            Self::count_escrows_by_user(env.clone(), user.clone()).min(start.saturating_add(limit));
+        let mut summaries = Vec::new(&env);
+        let mut page = Vec::new(&env);
+        for i in start..end {
+            if i == start || i % USER_INDEX_PAGE_SIZE == 0 {
+                page = Self::user_escrows(&env, &user, i / USER_INDEX_PAGE_SIZE);
+            }
+            let id = page.get_unchecked(i % USER_INDEX_PAGE_SIZE);
+            summaries.push_back(Self::summarize(id, Self::load(&env, id)));
+        }
+        summaries
+    }
+
+    /// Returns how many escrows `user` is the client or freelancer of.
+    pub fn count_escrows_by_user(env: Env, user: Address) -> u32 {
+        ttl::get(&env, &DataKey::UserEscrowCount(user)).unwrap_or(0)
+    }
+
+    /// Returns how many ledgers have passed since the escrow was created.
+    pub fn escrow_age(env: Env, escrow_id: u64) -> u32 {
+        env.ledger().sequence() - Self::load(&env, escrow_id).created_ledger
+    }
+
+    pub fn get_escrow(env: Env, escrow_id: u64) -> EscrowData {
+        Self::load(&env, escrow_id)
+    }
+
+    /// Arbiter's fee on a ruling: `fee_bps` of the disputed balance, capped
+    /// at the escrow's reserve.
+    fn arbiter_fee(env: &Env, escrow: &EscrowData) -> i128 {
+        (Self::unreleased(escrow) * Self::arbiter_incentive(env).fee_bps as i128 / BPS_DENOMINATOR)
+            .min(escrow.arbiter_reserve)
+    }
+
+    /// Freelancer's share of the disputed balance for a percentage split.
+    fn split_amount(escrow: &EscrowData, split_ratio: u32) -> i128 {
+        if split_ratio > 100 {
+            panic_with_error!(escrow.milestones.env(), EscrowError::InvalidSplitRatio);
+        }
+        Self::unreleased(escrow) * split_ratio as i128 / 100
+    }
+
+    /// Pays out the unreleased balance of a disputed escrow and closes it.
+    ///
+    /// `freelancer_amount` of the disputed balance goes to the freelancer,
+    /// net of the platform fee, and the rest to the client. `arbiter_fee`
+    /// comes out of the reserve; the rest of the reserve is refunded to the
+    /// client.
+    fn distribute(
+        env: &Env,
+        escrow_id: u64,
+        escrow: &mut EscrowData,
+        split_ratio: u32,
+        freelancer_amount: i128,
+        arbiter_fee: i128,
+    ) {
+        Self::require_not_frozen(env, escrow_id);
+        let disputed = Self::unreleased(escrow);
+        let client_amount = disputed - freelancer_amount + escrow.arbiter_reserve - arbiter_fee;
+
+        Self::pay(env, escrow, &escrow.arbiter, arbiter_fee);
+        let platform_fee = Self::charge_fee(env, escrow, freelancer_amount);
+        Self::pay_freelancer(env, escrow_id, escrow, freelancer_amount - platform_fee);
+        Self::pay(env, escrow, &escrow.client, client_amount);
+
+        let mut milestones = Vec::new(env);
+        for mut milestone in escrow.milestones.iter() {
+            milestone.released = true;
+            milestones.push_back(milestone);
+        }
+        escrow.milestones = milestones;
+        escrow.status = EscrowStatus::Resolved;
+        Self::close(env, escrow);
+        Self::save(env, escrow_id, escrow);
+
+        events::emit_escrow_dispute_resolved(
+            env,
+            escrow_id,
+            split_ratio,
+            freelancer_amount,
+            client_amount,
+            arbiter_fee,
+        );
+    }
+
+    /// Grants sponsored transactions to the party awarded the larger share
+    /// of the disputed balance. Even splits have no winner. Grant failures
+    /// (e.g. an exhausted budget) never block the ruling.
+    fn sponsor_winner(env: &Env, escrow: &EscrowData, freelancer_amount: i128, disputed: i128) {
+        let sponsorship = match Self::get_winner_sponsorship(env.clone()) {
+            Some(sponsorship) if sponsorship.transactions > 0 => sponsorship,
+            _ => return,
+        };
+        let (winner, _) = match Self::ruling_winner(escrow, freelancer_amount, disputed) {
+            Some(parties) => parties,
+            None => return,
+        };
+        let _ = GaslessHandlerClient::new(env, &sponsorship.gasless).try_grant_sponsorship(
+            &env.current_contract_address(),
+            winner,
+            &sponsorship.transactions,
+        );
+    }
+
+    /// Reports the party awarded the smaller share of a ruling as having
+    /// lost the dispute.
+    fn report_loser(env: &Env, escrow: &EscrowData, freelancer_amount: i128, disputed: i128) {
+        if let Some((_, loser)) = Self::ruling_winner(escrow, freelancer_amount, disputed) {
+            Self::report(env, loser, Outcome::DisputeLost);
+        }
+    }
+
+    /// Mints the freelancer a dispute badge when the ruling awards them the
+    /// larger share.
+    fn badge_winner(
+        env: &Env,
+        escrow_id: u64,
+        escrow: &EscrowData,
+        freelancer_amount: i128,
+        disputed: i128,
+    ) {
+        if let Some((winner, _)) = Self::ruling_winner(escrow, freelancer_amount, disputed) {
+            if *winner == escrow.freelancer {
+                Self::mint_badge(
+                    env,
+                    escrow_id,
+                    escrow,
+                    BadgeKind::DisputeWon,
+                    freelancer_amount,
+                );
+            }
+        }
+    }
+
+    /// Mints the freelancer a badge for `amount` of work on the escrow, if a
+    /// badge contract is registered and `amount` is not below the minimum
+    /// reported total. The project hash commits to the milestone amounts and
+    /// descriptions agreed at creation.
+    fn mint_badge(env: &Env, escrow_id: u64, escrow: &EscrowData, kind: BadgeKind, amount: i128) {
+        let badges = match Self::badges(env.clone()) {
+            Some(badges) if amount >= Self::min_reported_total(env.clone()) => badges,
+            _ => return,
+        };
+        let mut scope: Vec<(i128, String)> = Vec::new(env);
+        for milestone in escrow.milestones.iter() {
+            scope.push_back((milestone.amount, milestone.description));
+        }
+        let delivery = Delivery {
+            escrow_id,
+            project_hash: env.crypto().sha256(&scope.to_xdr(env)).to_bytes(),
+            counterparty: escrow.client.clone(),
+            amount,
+        };
+        badge::mint(env, &badges, &escrow.freelancer, kind, &delivery);
+    }
+
+    fn summarize(escrow_id: u64, escrow: EscrowData) -> EscrowSummary {
+        let total: i128 = escrow.milestones.iter().map(|m| m.amount).sum();
+        EscrowSummary {
+            escrow_id,
+            released: total - Self::unreleased(&escrow),
+            total,
+            milestone_count: escrow.milestones.len(),
+            client: escrow.client,
+            freelancer: escrow.freelancer,
+            token: escrow.token,
+            status: escrow.status,
+        }
+    }
+
+    fn user_escrows(env: &Env, user: &Address, page: u32) -> Vec<u64> {
+        ttl::get(env, &DataKey::UserEscrows(user.clone(), page)).unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Appends an escrow to the last page of `user`'s index, starting a new
+    /// page once it is full.
+    fn index_escrow(env: &Env, user: &Address, escrow_id: u64) {
+        let count = Self::count_escrows_by_user(env.clone(), user.clone());
+        let page = count / USER_INDEX_PAGE_SIZE;
+        let mut ids = Self::user_escrows(env, user, page);
+        ids.push_back(escrow_id);
+        ttl::set(env, &DataKey::UserEscrows(user.clone(), page), &ids);
+        ttl::set(env, &DataKey::UserEscrowCount(user.clone()), &(count + 1));
+    }
+
+    /// Returns the `(winner, loser)` of a ruling awarding `freelancer_amount`
+    /// of `disputed` to the freelancer. Even splits have no winner.
+    fn ruling_winner(
+        escrow: &EscrowData,
+        freelancer_amount: i128,
+        disputed: i128,
+    ) -> Option<(&Address, &Address)> {
+        match (freelancer_amount * 2).cmp(&disputed) {
+            core::cmp::Ordering::Greater => Some((&escrow.freelancer, &escrow.client)),
+            core::cmp::Ordering::Less => Some((&escrow.client, &escrow.freelancer)),
+            core::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Reports `outcome` for `subject` to the reputation contract, if one is
+    /// registered.
+    fn report(env: &Env, subject: &Address, outcome: Outcome) {
+        if let Some(reputation) = Self::reputation(env.clone()) {
+            reputation::report(env, &reputation, subject, outcome);
+        }
+    }
+
+    /// Calls the release hook, if one is registered, discarding any failure.
+    fn notify_release_hook(
+        env: &Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        escrow: &EscrowData,
+        amount: i128,
+    ) {
+        let hook = match Self::release_hook(env.clone()) {
+            Some(hook) => hook,
+            None => return,
+        };
+        let args: Vec<Val> = (
+            escrow_id,
+            milestone_index,
+            escrow.freelancer.clone(),
+            amount,
+        )
+            .into_val(env);
+        let _ = env.try_invoke_contract::<(), soroban_sdk::Error>(
+            &hook,
+            &Symbol::new(env, "on_release"),
+            args,
+        );
+    }
+
+    /// Fee rate for `escrow`: the token override or the base rate, reduced
+    /// by the client's best loyalty discount.
+    fn fee_rate(env: &Env, escrow: &EscrowData) -> u32 {
+        let storage = env.storage().instance();
+        let rate: u32 = storage
+            .get(&DataKey::TokenFeeBps(escrow.token.clone()))
+            .or_else(|| storage.get(&DataKey::FeeBps))
+            .unwrap_or(0);
+
+        let completed = Self::completed_escrows(env.clone(), escrow.client.clone());
+        let discount = storage
+            .get::<DataKey, Vec<LoyaltyTier>>(&DataKey::LoyaltyTiers)
+            .map(|tiers| {
+                tiers
+                    .iter()
+                    .filter(|tier| completed >= tier.min_completed)
+                    .map(|tier| tier.discount_bps)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .unwrap_or(0);
+
+        (rate as i128 * (BPS_DENOMINATOR - discount as i128) / BPS_DENOMINATOR) as u32
+    }
+
+    fn check_rate(env: &Env, bps: u32) {
+        if bps as i128 > BPS_DENOMINATOR {
+            panic_with_error!(env, EscrowError::InvalidRate);
+        }
+    }
+
+    fn arbiter_incentive(env: &Env) -> ArbiterIncentive {
+        env.storage()
+            .instance()
+            .get(&DataKey::ArbiterIncentive)
+            .unwrap_or(ArbiterIncentive {
+                reserve_bps: 0,
+                fee_bps: 0,
+            })
+    }
+
+    /// Sum of the milestones that have not been paid out yet.
+    fn unreleased(escrow: &EscrowData) -> i128 {
+        escrow
+            .milestones
+            .iter()
+            .filter(|m| !m.released)
+            .map(|m| m.amount - m.released_amount)
+            .sum()
+    }
+
+    /// Credits `amount` of the escrow's token to `to`'s claimable balance,
+    /// skipping zero amounts.
+    fn pay(env: &Env, escrow: &EscrowData, to: &Address, amount: i128) {
+        claimable::credit(env, to, &escrow.token, amount);
+    }
+
+    /// Credits `amount` to the freelancer or, if a payout split is set, across
+    /// its recipients by share. Rounding dust goes to the first recipient.
+    /// With a payout conversion set, `amount` is converted first and the
+    /// proceeds are paid out instead.
+    fn pay_freelancer(env: &Env, escrow_id: u64, escrow: &EscrowData, amount: i128) {
+        let (token, amount) = match Self::payout_conversion(env.clone(), escrow_id) {
+            Some(conversion) if amount > 0 => {
+                let received = Self::convert(env, escrow_id, escrow, &conversion, amount);
+                (conversion.asset, received)
+            }
+            _ => (escrow.token.clone(), amount),
+        };
+        let split: Vec<(Address, u32)> = match ttl::get(env, &DataKey::PayoutSplit(escrow_id)) {
+            Some(split) => split,
+            None => return claimable::credit(env, &escrow.freelancer, &token, amount),
+        };
+        let total: i128 = split.iter().map(|(_, shares)| shares as i128).sum();
+        let mut paid = 0;
+        for (recipient, shares) in split.iter().skip(1) {
+            let portion = amount * shares as i128 / total;
+            claimable::credit(env, &recipient, &token, portion);
+            paid += portion;
+        }
+        let (first, _) = split.get_unchecked(0);
+        claimable::credit(env, &first, &token, amount - paid);
+    }
+
+    /// Sells `amount` of the escrow token for `conversion.asset` through the
+    /// router and returns the amount received.
+    ///
+    /// # Panics
+    /// If no router is set or the router delivers less than
+    /// `conversion.min_rate` allows, reverting the release
+    fn convert(
+        env: &Env,
+        escrow_id: u64,
+        escrow: &EscrowData,
+        conversion: &PayoutConversion,
+        amount: i128,
+    ) -> i128 {
+        let router = Self::router(env.clone())
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::RouterNotSet));
+        let this = env.current_contract_address();
+        let min_out = amount * conversion.min_rate / RATE_SCALE;
+        let asset_client = token::Client::new(env, &conversion.asset);
+        let before = asset_client.balance(&this);
+
+        token::Client::new(env, &escrow.token).approve(
+            &this,
+            &router,
+            &amount,
+            &env.ledger().sequence(),
+        );
+        RouterClient::new(env, &router).swap_exact_tokens_for_tokens(
+            &amount,
+            &min_out,
+            &Vec::from_array(env, [escrow.token.clone(), conversion.asset.clone()]),
+            &this,
+            &env.ledger().timestamp(),
+        );
+
+        // Trust balances, not the router's reported amounts.
+        let received = asset_client.balance(&this) - before;
+        if received < min_out {
+            panic_with_error!(env, EscrowError::SlippageExceeded);
+        }
+        events::emit_payout_converted(env, escrow_id, &conversion.asset, amount, received);
+        received
+    }
+
+    /// Panics with `KycRequired` if `total` is above the KYC threshold and
+    /// either party is not verified.
+    fn require_kyc(env: &Env, client: &Address, freelancer: &Address, total: i128) {
+        let Some(requirement) = Self::kyc_requirement(env.clone()) else {
+            return;
+        };
+        if total > requirement.threshold
+            && !(kyc::is_verified(env, &requirement.registry, client)
+                && kyc::is_verified(env, &requirement.registry, freelancer))
+        {
+            panic_with_error!(env, EscrowError::KycRequired);
+        }
+    }
+
+    fn require_not_frozen(env: &Env, escrow_id: u64) {
+        if env.storage().persistent().has(&DataKey::Frozen(escrow_id)) {
+            panic_with_error!(env, EscrowError::EscrowFrozen);
+        }
+    }
+
+    /// Returns the ledger a frozen escrow was frozen on.
+    ///
+    /// # Panics
+    /// With `NotFrozen` if the escrow is not frozen
+    fn frozen_ledger(env: &Env, escrow_id: u64) -> u32 {
+        ttl::get(env, &DataKey::Frozen(escrow_id))
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::NotFrozen))
+    }
+
+    /// Releases the client's active-escrow slot once an escrow reaches a final state.
+    fn close(env: &Env, escrow: &EscrowData) {
+        let key = DataKey::ActiveEscrows(escrow.client.clone());
+        let active: u32 = ttl::get(env, &key).unwrap_or(0);
+        ttl::set(env, &key, &active.saturating_sub(1));
+    }
+
+    /// Takes the platform fee on `amount` owed to the freelancer and returns
+    /// it. The fee is deposited with the treasury contract if one is
+    /// assigned and otherwise sent to the fee collector; with neither role
+    /// set nothing is charged.
+    fn charge_fee(env: &Env, escrow: &EscrowData, amount: i128) -> i128 {
+        let fee = amount * Self::fee_rate(env, escrow) as i128 / BPS_DENOMINATOR;
+        if let Some(treasury) = admin::get_role(env, KeyRole::Treasury) {
+            if fee > 0 {
+                treasury::pay_fee(env, &treasury, &escrow.token, fee);
+            }
+        } else if let Some(collector) = admin::get_role(env, KeyRole::FeeCollector) {
+            Self::pay(env, escrow, &collector, fee);
+        } else {
+            return 0;
+        }
+        fee
+    }
+
+    /// Pays `amount` of `milestone` out to the freelancer, net of the
+    /// platform fee. The milestone counts as released once nothing is left
+    /// locked, and the escrow completes once every milestone is released.
+    fn pay_milestone(
+        env: &Env,
+        escrow_id: u64,
+        mut escrow: EscrowData,
+        milestone_index: u32,
+        mut milestone: Milestone,
+        amount: i128,
+    ) {
+        Self::require_not_frozen(env, escrow_id);
+        let fee = Self::charge_fee(env, &escrow, amount);
+        Self::pay_freelancer(env, escrow_id, &escrow, amount - fee);
+
+        milestone.released_amount += amount;
+        milestone.released = milestone.released_amount == milestone.amount;
+        escrow.milestones.set(milestone_index, milestone.clone());
+        if escrow.milestones.iter().all(|m| m.released) {
+            // Nothing was disputed, so the whole reserve goes back to the client.
+            Self::pay(env, &escrow, &escrow.client, escrow.arbiter_reserve);
+            escrow.status = EscrowStatus::Completed;
+            Self::close(env, &escrow);
+            let completed = Self::completed_escrows(env.clone(), escrow.client.clone()) + 1;
+            ttl::set(
+                env,
+                &DataKey::CompletedEscrows(escrow.client.clone()),
+                &completed,
+            );
+            let total = escrow.milestones.iter().map(|m| m.amount).sum();
+            if total >= Self::min_reported_total(env.clone()) {
+                Self::report(env, &escrow.client, Outcome::EscrowCompleted);
+                Self::report(env, &escrow.freelancer, Outcome::EscrowCompleted);
+            }
+            Self::mint_badge(env, escrow_id, &escrow, BadgeKind::Completed, total);
+        }
+        Self::save(env, escrow_id, &escrow);
+
+        events::emit_milestone_released(env, escrow_id, milestone_index, amount);
+        Self::notify_release_hook(env, escrow_id, milestone_index, &escrow, amount);
+    }
+
+    fn load(env: &Env, escrow_id: u64) -> EscrowData {
+        ttl::get(env, &DataKey::Escrow(escrow_id))
+            .unwrap_or_else(|| panic_with_error!(env, EscrowError::EscrowNotFound))
+    }
+
+    fn save(env: &Env, escrow_id: u64, escrow: &EscrowData) {
+        ttl::set(env, &DataKey::Escrow(escrow_id), escrow);
+    }
+
+    /// Returns the milestone at `index` if it can be paid out right now, or
+    /// the reason it cannot. Shared by `release` and `can_release` so the two
+    /// never disagree.
+    fn releasable_milestone(escrow: &EscrowData, index: u32) -> Result<Milestone, EscrowError> {
+        if escrow.status != EscrowStatus::Funded {
+            return Err(EscrowError::NotFunded);
+        }
+        let milestone = escrow
+            .milestones
+            .get(index)
+            .ok_or(EscrowError::MilestoneNotFound)?;
+        if milestone.released {
+            return Err(EscrowError::AlreadyReleased);
+        }
+        if !milestone.client_confirmed {
+            return Err(EscrowError::NotApproved);
+        }
+        if escrow.dual_approval && !milestone.freelancer_confirmed {
+            return Err(EscrowError::NotConfirmed);
+        }
+        Ok(milestone)
+    }
+
+    /// Returns the milestone at `index`, panicking if it is missing or already paid.
+    fn open_milestone(escrow: &EscrowData, index: u32) -> Milestone {
+        let milestone = escrow.milestones.get(index).unwrap_or_else(|| {
+            panic_with_error!(escrow.milestones.env(), EscrowError::MilestoneNotFound)
+        });
+        if milestone.released {
+            panic_with_error!(escrow.milestones.env(), EscrowError::AlreadyReleased);
+        }
+        milestone
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::{EscrowClawedBackEvent, PayoutClaimedEvent, EVENT_VERSION};
+    use crate::gasless::{GaslessHandler, SponsoredOperation};
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+        token::StellarAssetClient,
+        vec, Address, Env, IntoVal, String,
+    };
+
+    struct Setup<'a> {
+        escrow: EscrowClient<'a>,
+        token: token::Client<'a>,
+        client: Address,
+        freelancer: Address,
+        arbiter: Address,
+    }
+
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, Escrow);
+        let token_id = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+
+        let client = Address::generate(env);
+        StellarAssetClient::new(env, &token_id).mint(&client, &10_000);
+
+        Setup {
+            escrow: EscrowClient::new(env, &contract_id),
+            token: token::Client::new(env, &token_id),
+            client,
+            freelancer: Address::generate(env),
+            arbiter: Address::generate(env),
+        }
+    }
+
+    /// Claims what the escrow has credited to `who` and returns their token
+    /// balance.
+    fn claimed(s: &Setup, who: &Address) -> i128 {
+        s.escrow.claim(who, &s.token.address);
+        s.token.balance(who)
+    }
+
+    fn create(env: &Env, s: &Setup, dual_approval: bool) -> u64 {
+        let id = s.escrow.create_escrow(
+            &s.client,
+            &s.freelancer,
+            &s.arbiter,
+            &s.token.address,
+            &vec![
+                env,
+                (400, String::from_str(env, "Design")),
+                (600, String::from_str(env, "Build")),
+            ],
+            &dual_approval,
+        );
+        s.escrow.fund(&id);
+        id
+    }
+
+    #[test]
+    fn test_escrow_ttl_is_bumped_on_access() {
+        use soroban_sdk::testutils::storage::Persistent as _;
+
+        let env = Env::default();
+        // Keep the contracts themselves alive across the jumps below, but
+        // give new entries the network's short default TTL.
+        env.ledger().with_mut(|l| {
+            l.min_persistent_entry_ttl = 4 * ttl::BUMP_TO;
+            l.max_entry_ttl = 5 * ttl::BUMP_TO;
+        });
+        let s = setup(&env);
+        env.ledger()
+            .with_mut(|l| l.min_persistent_entry_ttl = 4_096);
+        let id = create(&env, &s, false);
+        let key = DataKey::Escrow(id);
+        let entry_ttl = || {
+            env.as_contract(&s.escrow.address, || {
+                env.storage().persistent().get_ttl(&key)
+            })
+        };
+        assert_eq!(entry_ttl(), ttl::BUMP_TO);
+
+        // Reading a long-idle escrow extends it again.
+        env.ledger()
+            .with_mut(|l| l.sequence_number += ttl::BUMP_TO - ttl::BUMP_THRESHOLD + 1);
+        assert!(entry_ttl() < ttl::BUMP_THRESHOLD);
+        s.escrow.get_escrow(&id);
+        assert_eq!(entry_ttl(), ttl::BUMP_TO);
+
+        // A keeper can do the same without touching the escrow.
+        env.ledger()
+            .with_mut(|l| l.sequence_number += ttl::BUMP_TO - ttl::BUMP_THRESHOLD + 1);
+        assert_eq!(
+            s.escrow
+                .bump_entries(&vec![&env, key.clone(), DataKey::Escrow(id + 1)]),
+            1
+        );
+        assert_eq!(entry_ttl(), ttl::BUMP_TO);
+    }
+
+    #[test]
+    fn test_client_approval_releases_by_default() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, false);
+        assert_eq!(s.token.balance(&s.escrow.address), 1_000);
+
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+
+        assert_eq!(claimed(&s, &s.freelancer), 400);
+        assert_eq!(s.escrow.get_escrow(&id).status, EscrowStatus::Funded);
+    }
+
+    #[test]
+    fn test_pause_blocks_payouts() {
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        let guardian = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        s.escrow.grant_role(&admin, &Role::Guardian, &guardian);
+        let id = create(&env, &s, false);
+        s.escrow.approve_milestone(&id, &0);
+
+        assert!(s.escrow.try_pause(&s.freelancer).is_err());
+        s.escrow.pause(&guardian);
+        assert!(s.escrow.is_paused());
+        assert!(s.escrow.try_release(&id, &0).is_err());
+        assert!(s.escrow.try_resolve_dispute(&id, &50).is_err());
+        assert_eq!(s.token.balance(&s.freelancer), 0);
+
+        s.escrow.unpause(&admin);
+        s.escrow.release(&id, &0);
+        assert_eq!(claimed(&s, &s.freelancer), 400);
+    }
+
+    #[test]
+    fn test_freeze_blocks_payouts_until_clawback() {
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        let officer = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        s.escrow.grant_role(&admin, &Role::Compliance, &officer);
+        let id = create(&env, &s, false);
+        s.escrow.approve_milestone(&id, &0);
+
+        assert_eq!(
+            s.escrow.try_unfreeze_escrow(&id),
+            Err(Ok(EscrowError::NotFrozen.into()))
+        );
+        s.escrow.freeze_escrow(&id);
+        assert!(s.escrow.is_frozen(&id));
+        assert!(s.escrow.try_release(&id, &0).is_err());
+        s.escrow.propose_cancellation(&id, &s.freelancer, &50);
+        assert_eq!(
+            s.escrow.try_accept_cancellation(&id, &s.client),
+            Err(Ok(EscrowError::EscrowFrozen.into()))
+        );
+
+        s.escrow.unfreeze_escrow(&id);
+        s.escrow.release(&id, &0);
+        assert_eq!(claimed(&s, &s.freelancer), 400);
+
+        let recovery = Address::generate(&env);
+        assert_eq!(
+            s.escrow.try_clawback(&id, &officer, &recovery),
+            Err(Ok(EscrowError::NotFrozen.into()))
+        );
+        s.escrow.freeze_escrow(&id);
+        assert!(s.escrow.try_clawback(&id, &s.client, &recovery).is_err());
+        s.escrow.clawback(&id, &officer, &recovery);
+        let all = env.events().all();
+        assert_eq!(
+            all.slice(all.len() - 1..),
+            vec![
+                &env,
+                (
+                    s.escrow.address.clone(),
+                    (Symbol::new(&env, "escrow_clawed_back"), id).into_val(&env),
+                    EscrowClawedBackEvent {
+                        officer,
+                        to: recovery.clone(),
+                        token: s.token.address.clone(),
+                        amount: 600,
+                        frozen_ledger: env.ledger().sequence(),
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+        assert_eq!(claimed(&s, &recovery), 600);
+        assert_eq!(s.token.balance(&s.escrow.address), 0);
+        assert_eq!(s.escrow.get_escrow(&id).status, EscrowStatus::ClawedBack);
+        assert!(!s.escrow.is_frozen(&id));
+        assert_eq!(s.escrow.active_escrows(&s.client), 0);
+    }
+
+    #[test]
+    fn test_partial_release_keeps_remainder_locked() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, false);
+        s.escrow.submit_milestone(&id, &0);
+
+        assert_eq!(
+            s.escrow.try_release_partial(&id, &0, &401),
+            Err(Ok(EscrowError::InvalidReleaseAmount))
+        );
+        s.escrow.release_partial(&id, &0, &240);
+        assert_eq!(claimed(&s, &s.freelancer), 240);
+        let milestone = s.escrow.get_escrow(&id).milestones.get(0).unwrap();
+        assert_eq!(milestone.released_amount, 240);
+        assert!(!milestone.released);
+        // The milestone went back for rework.
+        assert_eq!(s.escrow.milestone_status(&id, &0), MilestoneStatus::Pending);
+        assert_eq!(
+            s.escrow.try_release(&id, &0),
+            Err(Ok(EscrowError::NotApproved))
+        );
+
+        // Approving the rework releases only what is still locked.
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        assert_eq!(claimed(&s, &s.freelancer), 400);
+        assert_eq!(
+            s.escrow.milestone_status(&id, &0),
+            MilestoneStatus::Released
+        );
+        assert_eq!(
+            s.escrow
+                .escrow_summaries(&vec![&env, id])
+                .get(0)
+                .unwrap()
+                .unwrap()
+                .released,
+            400
+        );
+
+        // Partially releasing the whole remainder completes the milestone.
+        s.escrow.release_partial(&id, &1, &600);
+        assert_eq!(s.escrow.get_escrow(&id).status, EscrowStatus::Completed);
+        assert_eq!(claimed(&s, &s.freelancer), 1_000);
+        assert_eq!(s.token.balance(&s.escrow.address), 0);
+    }
+
+    #[test]
+    fn test_deadline_extension_needs_client_approval() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, false);
+        let now = env.ledger().sequence();
+        let deadline = |s: &Setup| s.escrow.get_escrow(&id).milestones.get(0).unwrap().deadline;
+
+        assert_eq!(
+            s.escrow
+                .try_request_deadline_extension(&id, &0, &(now + 300)),
+            Err(Ok(EscrowError::NoDeadline.into()))
+        );
+        s.escrow.set_milestone_deadline(&id, &0, &(now + 100));
+        assert_eq!(
+            s.escrow.try_set_milestone_deadline(&id, &0, &(now + 200)),
+            Err(Ok(EscrowError::InvalidDeadline.into()))
+        );
+        assert_eq!(
+            s.escrow
+                .try_request_deadline_extension(&id, &0, &(now + 50)),
+            Err(Ok(EscrowError::InvalidDeadline.into()))
+        );
+
+        s.escrow.request_deadline_extension(&id, &0, &(now + 300));
+        assert_eq!(deadline(&s), Some(now + 100));
+        s.escrow.approve_extension(&id, &0);
+        assert_eq!(deadline(&s), Some(now + 300));
+        assert_eq!(s.escrow.get_extension_request(&id, &0), None);
+        assert_eq!(
+            s.escrow.try_approve_extension(&id, &0),
+            Err(Ok(EscrowError::NoExtensionRequest.into()))
+        );
+
+        // A request lapses once the deadline it would move has passed.
+        s.escrow.request_deadline_extension(&id, &0, &(now + 500));
+        env.ledger().with_mut(|l| l.sequence_number = now + 301);
+        assert_eq!(s.escrow.get_extension_request(&id, &0), None);
+        assert!(s.escrow.try_approve_extension(&id, &0).is_err());
+        assert_eq!(deadline(&s), Some(now + 300));
+        assert_eq!(
+            s.escrow
+                .try_request_deadline_extension(&id, &0, &(now + 600)),
+            Err(Ok(EscrowError::DeadlinePassed.into()))
+        );
+    }
+
+    #[test]
+    fn test_release_splits_payout_across_team() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, false);
+        let designer = Address::generate(&env);
+        let developer = Address::generate(&env);
+
+        assert_eq!(
+            s.escrow
+                .try_set_payout_split(&id, &vec![&env, (designer.clone(), 0)]),
+            Err(Ok(EscrowError::InvalidPayoutSplit.into()))
+        );
+        s.escrow.set_payout_split(
+            &id,
+            &vec![
+                &env,
+                (s.freelancer.clone(), 1),
+                (designer.clone(), 1),
+                (developer.clone(), 2),
+            ],
+        );
+
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        assert_eq!(claimed(&s, &s.freelancer), 100);
+        assert_eq!(claimed(&s, &designer), 100);
+        assert_eq!(claimed(&s, &developer), 200);
+
+        // Clearing the split pays the freelancer alone again.
+        s.escrow.set_payout_split(&id, &Vec::new(&env));
+        assert_eq!(s.escrow.payout_split(&id), None);
+        s.escrow.approve_milestone(&id, &1);
+        s.escrow.release(&id, &1);
+        assert_eq!(claimed(&s, &s.freelancer), 700);
+        assert_eq!(claimed(&s, &designer), 100);
+    }
+
+    #[test]
+    fn test_payout_split_dust_goes_to_first_recipient() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, false);
+        let others = [Address::generate(&env), Address::generate(&env)];
+        s.escrow.set_payout_split(
+            &id,
+            &vec![
+                &env,
+                (s.freelancer.clone(), 1),
+                (others[0].clone(), 1),
+                (others[1].clone(), 1),
+            ],
+        );
+
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        assert_eq!(claimed(&s, &others[0]), 133);
+        assert_eq!(claimed(&s, &others[1]), 133);
+        assert_eq!(claimed(&s, &s.freelancer), 134);
+    }
+
+    #[test]
+    fn test_dual_approval_requires_both_parties() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, true);
+
+        // Client alone cannot release.
+        s.escrow.approve_milestone(&id, &0);
+        assert_eq!(
+            s.escrow.try_release(&id, &0),
+            Err(Ok(EscrowError::NotConfirmed))
+        );
+
+        // Freelancer alone cannot release either.
+        s.escrow.submit_milestone(&id, &1);
+        assert_eq!(
+            s.escrow.try_release(&id, &1),
+            Err(Ok(EscrowError::NotApproved))
+        );
+
+        s.escrow.submit_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        s.escrow.approve_milestone(&id, &1);
+        s.escrow.release(&id, &1);
+
+        assert_eq!(claimed(&s, &s.freelancer), 1_000);
+        assert_eq!(s.escrow.get_escrow(&id).status, EscrowStatus::Completed);
+        assert!(s.escrow.try_release(&id, &1).is_err());
+    }
+
+    fn setup_with_incentive(env: &Env, reserve_bps: u32, fee_bps: u32) -> Setup<'_> {
+        let s = setup(env);
+        let admin = Address::generate(env);
+        s.escrow.initialize(&admin);
+        s.escrow
+            .set_arbiter_incentive(&admin, &reserve_bps, &fee_bps);
+        s
+    }
+
+    #[test]
+    fn test_arbiter_paid_from_reserve_on_ruling() {
+        let env = Env::default();
+        // 5% reserve, arbiter earns 3% of the disputed balance.
+        let s = setup_with_incentive(&env, 500, 300);
+        let id = create(&env, &s, false);
+        assert_eq!(s.escrow.get_escrow(&id).arbiter_reserve, 50);
+        assert_eq!(s.token.balance(&s.escrow.address), 1_050);
+
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        s.escrow.raise_dispute(&id, &s.freelancer);
+        assert!(s.escrow.try_release(&id, &1).is_err());
+
+        s.escrow.resolve_dispute(&id, &50);
+
+        // 600 disputed: arbiter takes 18 from the reserve, the disputed
+        // balance is split evenly and the unused 32 of reserve goes back.
+        assert_eq!(claimed(&s, &s.arbiter), 18);
+        assert_eq!(claimed(&s, &s.freelancer), 400 + 300);
+        assert_eq!(claimed(&s, &s.client), 10_000 - 1_050 + 300 + 32);
+        assert_eq!(s.token.balance(&s.escrow.address), 0);
+        assert_eq!(s.escrow.get_escrow(&id).status, EscrowStatus::Resolved);
+    }
+
+    #[test]
+    fn test_platform_fees_accumulate_in_treasury() {
+        use crate::treasury::{Treasury, TreasuryClient};
+
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        let treasury = TreasuryClient::new(&env, &env.register_contract(None, Treasury));
+        treasury.initialize(&admin, &Address::generate(&env));
+        let collector = Address::generate(&env);
+        s.escrow.set_role(&KeyRole::FeeCollector, &collector);
+        s.escrow.set_role(&KeyRole::Treasury, &treasury.address);
+        s.escrow.set_fee(&admin, &500);
+
+        let id = create(&env, &s, false);
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        s.escrow.raise_dispute(&id, &s.freelancer);
+        s.escrow.resolve_dispute(&id, &50);
+
+        // 5% of the 400 release and of the freelancer's 300 share.
+        assert_eq!(claimed(&s, &s.freelancer), 380 + 285);
+        assert_eq!(claimed(&s, &s.client), 10_000 - 1_000 + 300);
+        assert_eq!(treasury.fees_collected(&s.token.address), 35);
+        assert_eq!(treasury.balance(&s.token.address), 35);
+        // The treasury takes precedence over the fee collector.
+        assert_eq!(claimed(&s, &collector), 0);
+    }
+
+    #[test]
+    fn test_outcomes_reported_to_reputation() {
+        use crate::reputation::{Reputation, ReputationClient};
+
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        let reputation = ReputationClient::new(&env, &env.register_contract(None, Reputation));
+        reputation.initialize(&admin);
+        s.escrow.set_reputation(&admin, &reputation.address);
+        assert_eq!(s.escrow.min_reported_total(), DEFAULT_MIN_REPORTED_TOTAL);
+        s.escrow.set_min_reported_total(&admin, &1_000);
+
+        // Until the escrow is a registered reporter its reports are dropped,
+        // without blocking the release.
+        let first = create(&env, &s, false);
+        s.escrow.approve_milestone(&first, &0);
+        s.escrow.release(&first, &0);
+        assert_eq!(reputation.score(&s.client), 0);
+
+        reputation.add_reporter(&admin, &s.escrow.address);
+        s.escrow.approve_milestone(&first, &1);
+        s.escrow.release(&first, &1);
+        assert_eq!(reputation.score(&s.client), 10);
+        assert_eq!(reputation.score(&s.freelancer), 10);
+
+        let second = create(&env, &s, false);
+        s.escrow.raise_dispute(&second, &s.client);
+        s.escrow.resolve_dispute(&second, &20);
+        let record = reputation.get_reputation(&s.freelancer);
+        assert_eq!(record.disputes_lost, 1);
+        assert_eq!(record.score, 0);
+        assert_eq!(reputation.score(&s.client), 10);
+
+        // Completions cannot be farmed by escrowing to oneself or by cycling
+        // escrows below the minimum total.
+        let milestones = vec![&env, (999, String::from_str(&env, "Dust"))];
+        let to_self = s.escrow.try_create_escrow(
+            &s.client,
+            &s.client,
+            &s.arbiter,
+            &s.token.address,
+            &milestones,
+            &false,
+        );
+        assert_eq!(to_self, Err(Ok(EscrowError::SelfEscrow.into())));
+        let dust = s.escrow.create_escrow(
+            &s.client,
+            &s.freelancer,
+            &s.arbiter,
+            &s.token.address,
+            &milestones,
+            &false,
+        );
+        s.escrow.fund(&dust);
+        s.escrow.approve_milestone(&dust, &0);
+        s.escrow.release(&dust, &0);
+        assert_eq!(s.escrow.get_escrow(&dust).status, EscrowStatus::Completed);
+        assert_eq!(reputation.score(&s.client), 10);
+        assert_eq!(reputation.get_reputation(&s.client).escrows_completed, 1);
+    }
+
+    #[test]
+    fn test_freelancer_badged_on_completion_and_won_rulings() {
+        use crate::badge::{AmountBracket, CompletionBadges, CompletionBadgesClient};
+
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        let badges =
+            CompletionBadgesClient::new(&env, &env.register_contract(None, CompletionBadges));
+        badges.initialize(&admin);
+        badges.add_issuer(&admin, &s.escrow.address);
+        s.escrow.set_badges(&admin, &badges.address);
+        // The won ruling below awards the freelancer 800.
+        s.escrow.set_min_reported_total(&admin, &800);
+
+        let completed = create(&env, &s, false);
+        s.escrow.approve_milestone(&completed, &0);
+        s.escrow.release(&completed, &0);
+        assert_eq!(badges.badge_count(&s.freelancer), 0);
+        s.escrow.approve_milestone(&completed, &1);
+        s.escrow.release(&completed, &1);
+
+        let lost = create(&env, &s, false);
+        s.escrow.raise_dispute(&lost, &s.client);
+        s.escrow.resolve_dispute(&lost, &20);
+        let won = create(&env, &s, false);
+        s.escrow.raise_dispute(&won, &s.client);
+        s.escrow.resolve_dispute(&won, &80);
+
+        let minted = badges.badges(&s.freelancer);
+        assert_eq!(minted.len(), 2);
+        let badge = minted.get(0).unwrap();
+        assert_eq!(
+            (badge.escrow_id, badge.kind, badge.bracket),
+            (completed, BadgeKind::Completed, AmountBracket::Small)
+        );
+        assert_eq!(badge.counterparty, s.client);
+        assert_eq!(badge.issuer, s.escrow.address);
+        assert_eq!(minted.get(1).unwrap().escrow_id, won);
+        assert_eq!(minted.get(1).unwrap().kind, BadgeKind::DisputeWon);
+        // Escrows with the same milestones commit to the same scope.
+        assert_eq!(minted.get(1).unwrap().project_hash, badge.project_hash);
+        assert_eq!(badges.badge_count(&s.client), 0);
+    }
+
+    #[test]
+    fn test_kyc_required_above_threshold() {
+        use crate::kyc::{KycRegistry, KycRegistryClient};
+
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        let registry = KycRegistryClient::new(&env, &env.register_contract(None, KycRegistry));
+        registry.initialize(&admin);
+        let attestor = Address::generate(&env);
+        registry.add_attestor(&admin, &attestor);
+        s.escrow.set_kyc_requirement(
+            &admin,
+            &Some(KycRequirement {
+                registry: registry.address.clone(),
+                threshold: 500,
+            }),
+        );
+
+        // Small escrows stay permissionless.
+        let small = s.escrow.create_escrow(
+            &s.client,
+            &s.freelancer,
+            &s.arbiter,
+            &s.token.address,
+            &vec![&env, (500, String::from_str(&env, "Audit"))],
+            &false,
+        );
+        s.escrow.fund(&small);
+
+        let create_large = || {
+            s.escrow.try_create_escrow(
+                &s.client,
+                &s.freelancer,
+                &s.arbiter,
+                &s.token.address,
+                &vec![&env, (501, String::from_str(&env, "Audit"))],
+                &false,
+            )
+        };
+        registry.attest(&attestor, &s.client);
+        assert_eq!(create_large(), Err(Ok(EscrowError::KycRequired.into())));
+        registry.attest(&attestor, &s.freelancer);
+        let large = create_large().unwrap().unwrap();
+
+        // Verification is checked again at funding.
+        registry.revoke(&attestor, &s.freelancer);
+        assert_eq!(
+            s.escrow.try_fund(&large),
+            Err(Ok(EscrowError::KycRequired.into()))
+        );
+        registry.attest(&attestor, &s.freelancer);
+        s.escrow.fund(&large);
+        assert_eq!(s.token.balance(&s.escrow.address), 1_001);
+
+        s.escrow.set_kyc_requirement(&admin, &None);
+        assert_eq!(s.escrow.kyc_requirement(), None);
+    }
+
+    #[test]
+    fn test_arbiter_fee_capped_at_reserve() {
+        let env = Env::default();
+        let s = setup_with_incentive(&env, 100, 1_000);
+        let id = create(&env, &s, false);
+        s.escrow.raise_dispute(&id, &s.client);
+        s.escrow.resolve_dispute(&id, &0);
+
+        assert_eq!(claimed(&s, &s.arbiter), 10);
+        assert_eq!(claimed(&s, &s.client), 10_000 - 10);
+    }
+
+    #[test]
+    fn test_reserve_refunded_when_undisputed() {
+        let env = Env::default();
+        let s = setup_with_incentive(&env, 500, 300);
+        let id = create(&env, &s, false);
+
+        for index in 0..2 {
+            s.escrow.approve_milestone(&id, &index);
+            s.escrow.release(&id, &index);
+        }
+
+        assert_eq!(claimed(&s, &s.freelancer), 1_000);
+        assert_eq!(claimed(&s, &s.client), 10_000 - 1_000);
+        assert_eq!(claimed(&s, &s.arbiter), 0);
+    }
+
+    #[test]
+    fn test_max_active_escrows_per_client() {
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        s.escrow.set_max_active_escrows(&admin, &2);
+
+        let first = create(&env, &s, false);
+        create(&env, &s, false);
+        assert_eq!(s.escrow.active_escrows(&s.client), 2);
+        assert!(s
+            .escrow
+            .try_create_escrow(
+                &s.client,
+                &s.freelancer,
+                &s.arbiter,
+                &s.token.address,
+                &vec![&env, (100, String::from_str(&env, "Audit"))],
+                &false,
+            )
+            .is_err());
+
+        // Other clients are unaffected.
+        let other = Address::generate(&env);
+        s.escrow.create_escrow(
+            &other,
+            &s.freelancer,
+            &s.arbiter,
+            &s.token.address,
+            &vec![&env, (100, String::from_str(&env, "Audit"))],
+            &false,
+        );
+
+        for index in 0..2 {
+            s.escrow.approve_milestone(&first, &index);
+            s.escrow.release(&first, &index);
+        }
+        assert_eq!(s.escrow.active_escrows(&s.client), 1);
+        create(&env, &s, false);
+        assert_eq!(s.escrow.active_escrows(&s.client), 2);
+
+        // An escrow left unfunded only holds its slot until the client
+        // withdraws it.
+        s.escrow.set_max_active_escrows(&admin, &3);
+        let unfunded = s.escrow.create_escrow(
+            &s.client,
+            &s.freelancer,
+            &s.arbiter,
+            &s.token.address,
+            &vec![&env, (100, String::from_str(&env, "Audit"))],
+            &false,
+        );
+        assert_eq!(s.escrow.active_escrows(&s.client), 3);
+        s.escrow.cancel_unfunded(&unfunded);
+        assert_eq!(env.auths()[0].0, s.client);
+        assert_eq!(s.escrow.active_escrows(&s.client), 2);
+        assert_eq!(
+            s.escrow.get_escrow(&unfunded).status,
+            EscrowStatus::Cancelled
+        );
+        assert_eq!(
+            s.escrow.try_cancel_unfunded(&unfunded),
+            Err(Ok(EscrowError::AlreadyFunded.into()))
+        );
+        assert_eq!(
+            s.escrow.try_cancel_unfunded(&first),
+            Err(Ok(EscrowError::AlreadyFunded.into()))
+        );
+    }
+
+    #[test]
+    fn test_settlement_records_agreement_hash() {
+        let env = Env::default();
+        let s = setup_with_incentive(&env, 500, 300);
+        let id = create(&env, &s, false);
+        s.escrow.raise_dispute(&id, &s.client);
+        assert_eq!(s.escrow.get_settlement(&id), None);
+
+        let agreement_hash = BytesN::from_array(&env, &[7; 32]);
+        s.escrow.settle_dispute(&id, &60, &agreement_hash);
+
+        // Both parties had to sign off on the settlement.
+        let auths = env.auths();
+        assert!(auths.iter().any(|(signer, _)| *signer == s.client));
+        assert!(auths.iter().any(|(signer, _)| *signer == s.freelancer));
+
+        let settlement = s.escrow.get_settlement(&id).unwrap();
+        assert_eq!(settlement.agreement_hash, agreement_hash);
+        assert_eq!(settlement.split_ratio, 60);
+
+        // No arbiter fee on a settlement: the whole reserve goes back.
+        assert_eq!(claimed(&s, &s.arbiter), 0);
+        assert_eq!(claimed(&s, &s.freelancer), 600);
+        assert_eq!(claimed(&s, &s.client), 10_000 - 600);
+        assert_eq!(s.escrow.get_escrow(&id).status, EscrowStatus::Resolved);
+    }
+
+    #[test]
+    fn test_mutual_cancellation_splits_locked_funds() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, false);
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+
+        assert_eq!(
+            s.escrow.try_accept_cancellation(&id, &s.client),
+            Err(Ok(EscrowError::NoCancellationProposal.into()))
+        );
+        assert!(s
+            .escrow
+            .try_propose_cancellation(&id, &s.freelancer, &101)
+            .is_err());
+        s.escrow.propose_cancellation(&id, &s.freelancer, &70);
+        assert_eq!(
+            s.escrow.try_accept_cancellation(&id, &s.freelancer),
+            Err(Ok(EscrowError::OwnCancellationProposal.into()))
+        );
+        assert_eq!(
+            s.escrow
+                .try_accept_cancellation(&id, &Address::generate(&env)),
+            Err(Ok(EscrowError::NotParty.into()))
+        );
+
+        // 70% of the 600 still locked goes back to the client.
+        s.escrow.accept_cancellation(&id, &s.client);
+        assert_eq!(claimed(&s, &s.freelancer), 400 + 180);
+        assert_eq!(claimed(&s, &s.client), 10_000 - 1_000 + 420);
+        assert_eq!(s.token.balance(&s.escrow.address), 0);
+        assert_eq!(s.escrow.get_escrow(&id).status, EscrowStatus::Cancelled);
+        assert_eq!(s.escrow.get_cancellation_proposal(&id), None);
+        assert!(s.escrow.try_release(&id, &1).is_err());
+        assert_eq!(s.escrow.active_escrows(&s.client), 0);
+    }
+
+    #[test]
+    fn test_settlement_requires_both_signatures() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, false);
+        s.escrow.raise_dispute(&id, &s.client);
+        let agreement_hash = BytesN::from_array(&env, &[7; 32]);
+
+        // Only the client signs.
+        let result = s
+            .escrow
+            .mock_auths(&[MockAuth {
+                address: &s.client,
+                invoke: &MockAuthInvoke {
+                    contract: &s.escrow.address,
+                    fn_name: "settle_dispute",
+                    args: (id, 60u32, agreement_hash.clone()).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_settle_dispute(&id, &60, &agreement_hash);
+        assert!(result.is_err());
+        assert_eq!(s.escrow.get_settlement(&id), None);
+    }
+
+    #[test]
+    fn test_can_release() {
+        let env = Env::default();
+        let s = setup(&env);
+        let ready = create(&env, &s, false);
+        let disputed = create(&env, &s, false);
+
+        assert!(!s.escrow.can_release(&ready, &0));
+        s.escrow.approve_milestone(&ready, &0);
+        assert!(s.escrow.can_release(&ready, &0));
+        assert!(!s.escrow.can_release(&ready, &1));
+
+        s.escrow.approve_milestone(&disputed, &0);
+        s.escrow.raise_dispute(&disputed, &s.freelancer);
+        assert!(!s.escrow.can_release(&disputed, &0));
+
+        s.escrow.release(&ready, &0);
+        assert!(!s.escrow.can_release(&ready, &0));
+        assert!(!s.escrow.can_release(&ready, &9));
+        assert!(!s.escrow.can_release(&99, &0));
+    }
+
+    mod recording_hook {
+        use soroban_sdk::{contract, contractimpl, Address, Env, Symbol};
+
+        #[contract]
+        pub struct RecordingHook;
+
+        #[contractimpl]
+        impl RecordingHook {
+            pub fn on_release(
+                env: Env,
+                escrow_id: u64,
+                milestone_index: u32,
+                _freelancer: Address,
+                amount: i128,
+            ) {
+                env.storage().instance().set(
+                    &Symbol::new(&env, "last"),
+                    &(escrow_id, milestone_index, amount),
+                );
+            }
+
+            pub fn last_release(env: Env) -> Option<(u64, u32, i128)> {
+                env.storage().instance().get(&Symbol::new(&env, "last"))
+            }
+        }
+    }
+
+    mod failing_hook {
+        use soroban_sdk::{contract, contractimpl, Address, Env};
+
+        #[contract]
+        pub struct FailingHook;
+
+        #[contractimpl]
+        impl FailingHook {
+            pub fn on_release(
+                _env: Env,
+                _escrow_id: u64,
+                _milestone_index: u32,
+                _freelancer: Address,
+                _amount: i128,
+            ) {
+                panic!("hook failure");
+            }
+        }
+    }
+
+    mod mock_router {
+        use soroban_sdk::{contract, contractimpl, token, Address, Env, Vec};
+
+        /// Fills exact-input swaps at a settable rate, in basis points.
+        #[contract]
+        pub struct MockRouter;
+
+        #[contractimpl]
+        impl MockRouter {
+            pub fn set_fill_bps(env: Env, fill_bps: i128) {
+                env.storage().instance().set(&0u32, &fill_bps);
+            }
+
+            pub fn swap_exact_tokens_for_tokens(
+                env: Env,
+                amount_in: i128,
+                _amount_out_min: i128,
+                path: Vec<Address>,
+                to: Address,
+                _deadline: u64,
+            ) -> Vec<i128> {
+                to.require_auth();
+                let fill_bps: i128 = env.storage().instance().get(&0u32).unwrap_or(10_000);
+                let delivered = amount_in * fill_bps / 10_000;
+                let this = env.current_contract_address();
+                token::Client::new(&env, &path.first().unwrap())
+                    .transfer_from(&this, &to, &this, &amount_in);
+                token::Client::new(&env, &path.last().unwrap()).transfer(&this, &to, &delivered);
+                Vec::from_array(&env, [amount_in, delivered])
+            }
+        }
+    }
+
+    #[test]
+    fn test_payouts_convert_through_router() {
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        let id = create(&env, &s, false);
+
+        let usdc = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let router_id = env.register_contract(None, mock_router::MockRouter);
+        let router = mock_router::MockRouterClient::new(&env, &router_id);
+        StellarAssetClient::new(&env, &usdc).mint(&router_id, &10_000);
+        let conversion = PayoutConversion {
+            asset: usdc.clone(),
+            min_rate: RATE_SCALE * 85 / 100,
+        };
+        assert_eq!(
+            s.escrow
+                .try_set_payout_conversion(&id, &Some(conversion.clone())),
+            Err(Ok(EscrowError::RouterNotSet.into()))
+        );
+        s.escrow.set_router(&admin, &router_id);
+        assert_eq!(
+            s.escrow.try_set_payout_conversion(
+                &id,
+                &Some(PayoutConversion {
+                    asset: s.token.address.clone(),
+                    min_rate: RATE_SCALE,
+                })
+            ),
+            Err(Ok(EscrowError::InvalidConversion.into()))
+        );
+        s.escrow
+            .set_payout_conversion(&id, &Some(conversion.clone()));
+        assert_eq!(s.escrow.payout_conversion(&id), Some(conversion));
+
+        // The swap needs no auth beyond the escrow contract's own.
+        s.escrow.approve_milestone(&id, &0);
+        router.set_fill_bps(&9_000);
+        env.set_auths(&[]);
+        s.escrow.release(&id, &0);
+        assert_eq!(s.escrow.claimable_balance(&s.freelancer, &usdc), 360);
+        assert_eq!(
+            s.escrow.claimable_balance(&s.freelancer, &s.token.address),
+            0
+        );
+        assert_eq!(s.token.balance(&router_id), 400);
+
+        // A fill below the minimum rate reverts the release.
+        env.mock_all_auths();
+        s.escrow.approve_milestone(&id, &1);
+        router.set_fill_bps(&8_000);
+        assert_eq!(
+            s.escrow.try_release(&id, &1),
+            Err(Ok(EscrowError::SlippageExceeded))
+        );
+        assert_eq!(s.token.balance(&s.escrow.address), 600);
+
+        s.escrow.set_payout_conversion(&id, &None);
+        s.escrow.release(&id, &1);
+        assert_eq!(claimed(&s, &s.freelancer), 600);
+    }
+
+    #[test]
+    fn test_release_invokes_hook() {
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        let hook_id = env.register_contract(None, recording_hook::RecordingHook);
+        s.escrow.set_release_hook(&admin, &hook_id);
+        assert_eq!(s.escrow.release_hook(), Some(hook_id.clone()));
+
+        let id = create(&env, &s, false);
+        s.escrow.approve_milestone(&id, &1);
+        s.escrow.release(&id, &1);
+
+        let hook = recording_hook::RecordingHookClient::new(&env, &hook_id);
+        assert_eq!(hook.last_release(), Some((id, 1, 600)));
+    }
+
+    #[test]
+    fn test_failing_hook_does_not_block_release() {
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        let hook_id = env.register_contract(None, failing_hook::FailingHook);
+        s.escrow.set_release_hook(&admin, &hook_id);
+
+        let id = create(&env, &s, false);
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        assert_eq!(claimed(&s, &s.freelancer), 400);
+        assert!(s.escrow.get_escrow(&id).milestones.get(0).unwrap().released);
+    }
+
+    #[test]
+    fn test_loyal_client_gets_reduced_fee_rate() {
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        let collector = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        s.escrow.set_role(&KeyRole::FeeCollector, &collector);
+        s.escrow.set_fee(&admin, &200);
+        s.escrow.set_loyalty_tiers(
+            &admin,
+            &vec![
+                &env,
+                LoyaltyTier {
+                    min_completed: 1,
+                    discount_bps: 5_000,
+                },
+                LoyaltyTier {
+                    min_completed: 5,
+                    discount_bps: 9_000,
+                },
+            ],
+        );
+
+        let first = create(&env, &s, false);
+        assert_eq!(s.escrow.effective_fee_rate(&first), 200);
+        s.escrow.approve_milestone(&first, &0);
+        s.escrow.approve_milestone(&first, &1);
+        s.escrow.release(&first, &0);
+        s.escrow.release(&first, &1);
+        assert_eq!(claimed(&s, &collector), 20);
+        assert_eq!(claimed(&s, &s.freelancer), 980);
+        assert_eq!(s.escrow.completed_escrows(&s.client), 1);
+
+        // One completed escrow unlocks the first tier's 50% discount.
+        let second = create(&env, &s, false);
+        assert_eq!(s.escrow.effective_fee_rate(&second), 100);
+
+        // A token override replaces the base rate before the discount applies.
+        s.escrow.set_token_fee(&admin, &s.token.address, &400);
+        assert_eq!(s.escrow.effective_fee_rate(&second), 200);
+    }
+
+    #[test]
+    fn test_stale_dispute_refund_after_timeout() {
+        let env = Env::default();
+        let s = setup_with_incentive(&env, 500, 300);
+        s.escrow
+            .set_stale_dispute_timeout(&s.escrow.admin(), &1_000);
+
+        let id = create(&env, &s, false);
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        s.escrow.raise_dispute(&id, &s.client);
+        // Lowering the timeout does not shorten a dispute already raised.
+        s.escrow.set_stale_dispute_timeout(&s.escrow.admin(), &0);
+
+        env.ledger().with_mut(|l| l.sequence_number += 1_000);
+        assert_eq!(
+            s.escrow.try_force_refund_stale(&id),
+            Err(Ok(EscrowError::DisputeNotStale.into()))
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        s.escrow.force_refund_stale(&id);
+        // Unreleased 600 plus the untouched 50 reserve.
+        assert_eq!(claimed(&s, &s.client), 10_000 - 1_050 + 650);
+        assert_eq!(claimed(&s, &s.freelancer), 400);
+        assert_eq!(s.token.balance(&s.escrow.address), 0);
+        assert_eq!(s.escrow.get_escrow(&id).status, EscrowStatus::Refunded);
+        assert_eq!(s.escrow.active_escrows(&s.client), 0);
+        assert!(s.escrow.try_force_refund_stale(&id).is_err());
+    }
+
+    #[test]
+    fn test_silent_client_milestone_auto_releases() {
+        let env = Env::default();
+        let s = setup_with_incentive(&env, 0, 0);
+        s.escrow.set_review_period(&s.escrow.admin(), &100);
+        let id = create(&env, &s, true);
+
+        // Nothing to claim before the freelancer submits.
+        assert_eq!(
+            s.escrow.try_claim_expired_milestone(&id, &0),
+            Err(Ok(EscrowError::NoReviewDeadline))
+        );
+        s.escrow.submit_milestone(&id, &0);
+        s.escrow.submit_milestone(&id, &1);
+        let deadline = env.ledger().sequence() + 100;
+        assert_eq!(
+            s.escrow
+                .get_escrow(&id)
+                .milestones
+                .get(0)
+                .unwrap()
+                .review_deadline,
+            Some(deadline)
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number = deadline);
+        assert_eq!(
+            s.escrow.try_claim_expired_milestone(&id, &0),
+            Err(Ok(EscrowError::ReviewNotExpired))
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        s.escrow.claim_expired_milestone(&id, &0);
+        assert_eq!(claimed(&s, &s.freelancer), 400);
+        assert_eq!(
+            s.escrow.try_claim_expired_milestone(&id, &0),
+            Err(Ok(EscrowError::AlreadyReleased))
+        );
+
+        // A dispute raised in time stops the auto-release.
+        s.escrow.raise_dispute(&id, &s.client);
+        assert_eq!(
+            s.escrow.try_claim_expired_milestone(&id, &1),
+            Err(Ok(EscrowError::NotFunded))
+        );
+        assert_eq!(claimed(&s, &s.freelancer), 400);
+    }
+
+    #[test]
+    fn test_escrow_summaries_batch() {
+        let env = Env::default();
+        let s = setup(&env);
+        let first = create(&env, &s, false);
+        let second = create(&env, &s, false);
+        s.escrow.approve_milestone(&second, &0);
+        s.escrow.release(&second, &0);
+
+        let summaries = s.escrow.escrow_summaries(&vec![&env, first, 99, second]);
+        assert_eq!(summaries.len(), 3);
+        let summary = summaries.get(0).unwrap().unwrap();
+        assert_eq!(summary.status, EscrowStatus::Funded);
+        assert_eq!(summary.total, 1_000);
+        assert_eq!(summary.released, 0);
+        assert_eq!(summary.milestone_count, 2);
+        assert_eq!(summaries.get(1).unwrap(), None);
+        let summary = summaries.get(2).unwrap().unwrap();
+        assert_eq!(summary.released, 400);
+        assert_eq!(summary.client, s.client);
+
+        let mut ids = Vec::new(&env);
+        for id in 0..=MAX_SUMMARY_BATCH as u64 {
+            ids.push_back(id);
+        }
+        assert!(s.escrow.try_escrow_summaries(&ids).is_err());
+    }
+
+    #[test]
+    fn test_list_escrows_by_user() {
+        let env = Env::default();
+        let s = setup(&env);
+        let first = create(&env, &s, false);
+        let second = create(&env, &s, false);
+
+        assert_eq!(s.escrow.count_escrows_by_user(&s.freelancer), 2);
+        let all = s.escrow.list_escrows_by_user(&s.client, &0, &10);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all.get(0).unwrap().escrow_id, first);
+        assert_eq!(all.get(0).unwrap().total, 1_000);
+        assert_eq!(all.get(1).unwrap().escrow_id, second);
+        let page = s.escrow.list_escrows_by_user(&s.freelancer, &1, &1);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page.get(0).unwrap().escrow_id, second);
+        assert!(s
+            .escrow
+            .list_escrows_by_user(&Address::generate(&env), &0, &10)
+            .is_empty());
+        assert_eq!(
+            s.escrow
+                .try_list_escrows_by_user(&s.client, &0, &(MAX_SUMMARY_BATCH + 1)),
+            Err(Ok(EscrowError::BatchTooLarge.into()))
+        );
+    }
+
+    #[test]
+    fn test_partial_ruling_claws_back_unearned_installments() {
+        let env = Env::default();
+        let s = setup_with_incentive(&env, 500, 300);
+        let id = create(&env, &s, false);
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        s.escrow.raise_dispute(&id, &s.client);
+
+        // 600 still held; the freelancer earned only 250 of it.
+        assert!(s.escrow.try_resolve_with_clawback(&id, &601).is_err());
+        assert!(s.escrow.try_resolve_with_clawback(&id, &-1).is_err());
+        s.escrow.resolve_with_clawback(&id, &250);
+
+        // Arbiter fee is 3% of 600; 350 is clawed back plus the unused reserve.
+        assert_eq!(claimed(&s, &s.arbiter), 18);
+        assert_eq!(claimed(&s, &s.freelancer), 400 + 250);
+        assert_eq!(claimed(&s, &s.client), 10_000 - 1_050 + 350 + 32);
+        assert_eq!(s.token.balance(&s.escrow.address), 0);
+        assert_eq!(s.escrow.get_escrow(&id).status, EscrowStatus::Resolved);
+    }
+
+    #[test]
+    fn test_dispute_winner_gets_sponsored_withdrawal() {
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+
+        let gasless_id = env.register_contract(None, GaslessHandler);
+        let gasless = GaslessHandlerClient::new(&env, &gasless_id);
+        gasless.initialize(&admin);
+        gasless.fund_bonus_budget(&admin, &10);
+        gasless.fund_sponsorship_pool(&admin, &10_000);
+        gasless.grant_role(&admin, &Role::Operator, &s.escrow.address);
+        s.escrow.set_winner_sponsorship(&admin, &gasless_id, &3);
+
+        let id = create(&env, &s, false);
+        s.escrow.raise_dispute(&id, &s.freelancer);
+        s.escrow.resolve_dispute(&id, &70);
+
+        assert_eq!(gasless.bonus_quota(&s.freelancer), 3);
+        assert_eq!(gasless.bonus_quota(&s.client), 0);
+        assert_eq!(gasless.bonus_budget(), 7);
+
+        // The winner's withdrawal is sponsored even with the daily quota spent.
+        let withdraw = Symbol::new(&env, "withdraw");
+        gasless.allow_operation(&admin, &s.escrow.address, &withdraw, &0);
+        let op = SponsoredOperation {
+            contract: s.escrow.address.clone(),
+            function: withdraw,
+            args_hash: BytesN::from_array(&env, &[0; 32]),
+            amount: 0,
+            max_fee: 100,
+        };
+        for _ in 0..=crate::gasless::DAILY_SPONSORSHIP_LIMIT {
+            gasless.sponsor_transaction(&s.freelancer, &op, &gasless.nonce(&s.freelancer));
+        }
+        assert_eq!(gasless.bonus_quota(&s.freelancer), 2);
+    }
+
+    #[test]
+    fn test_escrow_age_tracks_ledgers() {
+        let env = Env::default();
+        let s = setup(&env);
+        env.ledger().with_mut(|l| l.sequence_number = 1_000);
+        let id = create(&env, &s, false);
+        assert_eq!(s.escrow.get_escrow(&id).created_ledger, 1_000);
+        assert_eq!(s.escrow.escrow_age(&id), 0);
+
+        env.ledger().with_mut(|l| l.sequence_number += 250);
+        assert_eq!(s.escrow.escrow_age(&id), 250);
+        env.ledger().with_mut(|l| l.sequence_number += 50);
+        assert_eq!(s.escrow.escrow_age(&id), 300);
+        assert!(s.escrow.try_escrow_age(&99).is_err());
+    }
+
+    #[test]
+    fn test_milestone_lifecycle_and_custody() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, true);
+        assert_eq!(s.token.balance(&s.escrow.address), 1_000);
+        let milestone = s.escrow.get_escrow(&id).milestones.get(0).unwrap();
+        assert_eq!(milestone.description, String::from_str(&env, "Design"));
+        assert_eq!(s.escrow.milestone_status(&id, &0), MilestoneStatus::Pending);
+
+        s.escrow.submit_milestone(&id, &0);
+        assert_eq!(
+            s.escrow.milestone_status(&id, &0),
+            MilestoneStatus::Submitted
+        );
+        s.escrow.approve_milestone(&id, &0);
+        assert_eq!(
+            s.escrow.milestone_status(&id, &0),
+            MilestoneStatus::Approved
+        );
+        s.escrow.release(&id, &0);
+        assert_eq!(
+            s.escrow.milestone_status(&id, &0),
+            MilestoneStatus::Released
+        );
+        // Released funds stay in custody until the freelancer claims them.
+        assert_eq!(s.token.balance(&s.escrow.address), 1_000);
+        assert_eq!(s.escrow.milestone_status(&id, &1), MilestoneStatus::Pending);
+        assert!(s.escrow.try_milestone_status(&id, &2).is_err());
+    }
+
+    #[test]
+    fn test_released_funds_are_claimed_by_the_recipient() {
+        use soroban_sdk::testutils::IssuerFlags;
+
+        let env = Env::default();
+        let mut s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        // An asset whose issuer can revoke a holder's trustline.
+        let sac = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+        let asset = StellarAssetClient::new(&env, &sac.address());
+        asset.mint(&s.client, &10_000);
+        s.token = token::Client::new(&env, &sac.address());
+        let id = create(&env, &s, false);
+
+        // A recipient that cannot hold the asset does not block the release;
+        // the payout waits in its claimable balance instead.
+        asset.set_authorized(&s.freelancer, &false);
+        s.escrow.approve_milestone(&id, &0);
+        s.escrow.release(&id, &0);
+        assert_eq!(
+            s.escrow.claimable_balance(&s.freelancer, &s.token.address),
+            400
+        );
+        assert!(s.escrow.try_claim(&s.freelancer, &s.token.address).is_err());
+        assert_eq!(s.token.balance(&s.escrow.address), 1_000);
+        asset.set_authorized(&s.freelancer, &true);
+
+        // Claims are held while the contract is paused.
+        s.escrow.pause(&admin);
+        assert_eq!(
+            s.escrow.try_claim(&s.freelancer, &s.token.address),
+            Err(Ok(crate::errors::CommonError::ContractPaused.into()))
+        );
+        s.escrow.unpause(&admin);
+
+        assert_eq!(s.escrow.claim(&s.freelancer, &s.token.address), 400);
+        let all = env.events().all();
+        assert_eq!(
+            all.slice(all.len() - 1..),
+            vec![
+                &env,
+                (
+                    s.escrow.address.clone(),
+                    (Symbol::new(&env, "payout_claimed"), s.freelancer.clone()).into_val(&env),
+                    PayoutClaimedEvent {
+                        token: s.token.address.clone(),
+                        amount: 400,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+        assert_eq!(s.token.balance(&s.freelancer), 400);
+        assert_eq!(s.token.balance(&s.escrow.address), 600);
+        assert_eq!(s.escrow.claim(&s.freelancer, &s.token.address), 0);
+    }
+
+    #[test]
+    fn test_confirmations_require_party_auth() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, false);
+
+        // A freelancer signature cannot approve on the client's behalf.
+        assert!(s
+            .escrow
+            .mock_auths(&[MockAuth {
+                address: &s.freelancer,
+                invoke: &MockAuthInvoke {
+                    contract: &s.escrow.address,
+                    fn_name: "approve_milestone",
+                    args: (id, 0u32).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_approve_milestone(&id, &0)
+            .is_err());
+        assert!(s
+            .escrow
+            .mock_auths(&[MockAuth {
+                address: &s.client,
+                invoke: &MockAuthInvoke {
+                    contract: &s.escrow.address,
+                    fn_name: "submit_milestone",
+                    args: (id, 0u32).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_submit_milestone(&id, &0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_create_escrow_requires_supported_token() {
+        let env = Env::default();
+        let s = setup(&env);
+        let admin = Address::generate(&env);
+        s.escrow.initialize(&admin);
+        let usdc = String::from_str(&env, "USDC");
+        let other = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let milestones = vec![&env, (100, String::from_str(&env, "Audit"))];
+
+        // Until an asset is registered any token is accepted.
+        s.escrow.create_escrow(
+            &s.client,
+            &s.freelancer,
+            &s.arbiter,
+            &other,
+            &milestones,
+            &false,
+        );
+
+        let info = AssetInfo {
+            issuer: Address::generate(&env),
+            token: s.token.address.clone(),
+        };
+        s.escrow.register_asset(&admin, &usdc, &info);
+        assert_eq!(s.escrow.supported_assets(), vec![&env, (usdc, info)]);
+        assert!(s
+            .escrow
+            .try_create_escrow(
+                &s.client,
+                &s.freelancer,
+                &s.arbiter,
+                &other,
+                &milestones,
+                &false
+            )
+            .is_err());
+        create(&env, &s, false);
+    }
+}
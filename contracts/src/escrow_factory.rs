@@ -0,0 +1,317 @@
+//! Deploys one escrow contract per project.
+//!
+//! Each project gets its own instance of the uploaded escrow wasm, deployed
+//! from this contract with a salt of `sha256((client, freelancer,
+//! project_id))`, so anyone can compute a project's escrow address off-chain
+//! before it exists. The factory initializes every instance with its own
+//! admin and indexes the deployed addresses by client and by freelancer.
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, xdr::ToXdr, Address,
+    BytesN, Env, String, Vec,
+};
+
+use crate::admin;
+use crate::errors::CommonError;
+use crate::escrow::EscrowClient;
+use crate::{events, pausable, ttl};
+
+/// Number of escrow addresses held by each page of a user's escrow index.
+pub const INDEX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of escrows returned by a single `escrows_of` call.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Errors raised by the escrow factory.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FactoryError {
+    /// An escrow was already deployed for the project
+    ProjectExists = 100,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Hash of the uploaded escrow wasm new projects are deployed from
+    EscrowWasm,
+    /// Escrow deployed for a project, by salt
+    Project(BytesN<32>),
+    /// One page of the escrows a user is the client or freelancer of,
+    /// oldest first, by user and page number
+    UserEscrows(Address, u32),
+    /// Number of escrows a user is the client or freelancer of
+    UserEscrowCount(Address),
+}
+
+#[contract]
+pub struct EscrowFactory;
+
+#[contractimpl]
+impl EscrowFactory {
+    /// Initializes the factory with its admin and the escrow wasm to deploy.
+    /// Can only be called once.
+    pub fn initialize(env: Env, admin: Address, escrow_wasm_hash: BytesN<32>) {
+        admin::set_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::EscrowWasm, &escrow_wasm_hash);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Pauses deployments. Admin or guardian only.
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes deployments after a pause. Admin or guardian only.
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the factory is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Points future deployments at a newly uploaded escrow wasm. Escrows
+    /// already deployed keep their code. Admin only.
+    pub fn set_escrow_wasm(env: Env, admin: Address, escrow_wasm_hash: BytesN<32>) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::EscrowWasm, &escrow_wasm_hash);
+    }
+
+    /// Returns the hash of the escrow wasm new projects are deployed from.
+    ///
+    /// # Panics
+    /// If the factory is not initialized
+    pub fn escrow_wasm(env: Env) -> BytesN<32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::EscrowWasm)
+            .unwrap_or_else(|| panic_with_error!(env, CommonError::NotInitialized))
+    }
+
+    /// Deploys and initializes the escrow for a project. Requires the
+    /// client's auth.
+    ///
+    /// # Returns
+    /// The new escrow's address, equal to `escrow_address` for the same
+    /// arguments
+    ///
+    /// # Panics
+    /// With `ProjectExists` if the project already has an escrow
+    pub fn deploy_escrow(
+        env: Env,
+        client: Address,
+        freelancer: Address,
+        project_id: String,
+    ) -> Address {
+        pausable::require_not_paused(&env);
+        client.require_auth();
+
+        let salt = Self::salt(&env, &client, &freelancer, &project_id);
+        let key = DataKey::Project(salt.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, FactoryError::ProjectExists);
+        }
+
+        let escrow = env
+            .deployer()
+            .with_current_contract(salt)
+            .deploy(Self::escrow_wasm(env.clone()));
+        EscrowClient::new(&env, &escrow).initialize(&admin::get_admin(&env));
+
+        ttl::set(&env, &key, &escrow);
+        Self::index(&env, &client, &escrow);
+        if freelancer != client {
+            Self::index(&env, &freelancer, &escrow);
+        }
+        events::emit_escrow_deployed(&env, &escrow, &client, &freelancer, &project_id);
+        escrow
+    }
+
+    /// Returns the address a project's escrow has or will have once deployed.
+    pub fn escrow_address(
+        env: Env,
+        client: Address,
+        freelancer: Address,
+        project_id: String,
+    ) -> Address {
+        let salt = Self::salt(&env, &client, &freelancer, &project_id);
+        env.deployer()
+            .with_current_contract(salt)
+            .deployed_address()
+    }
+
+    /// Returns the project's escrow, if it has been deployed.
+    pub fn get_escrow(
+        env: Env,
+        client: Address,
+        freelancer: Address,
+        project_id: String,
+    ) -> Option<Address> {
+        let salt = Self::salt(&env, &client, &freelancer, &project_id);
+        ttl::get(&env, &DataKey::Project(salt))
+    }
+
+    /// Returns up to `limit` escrows `user` is the client or freelancer of,
+    /// oldest first, skipping the first `start`.
+    ///
+    /// # Panics
+    /// If `limit` is above `MAX_PAGE_SIZE`
+    pub fn escrows_of(env: Env, user: Address, start: u32, limit: u32) -> Vec<Address> {
+        if limit > MAX_PAGE_SIZE {
+            panic_with_error!(env, CommonError::BatchTooLarge);
+        }
+        let end = Self::escrow_count_of(env.clone(), user.clone()).min(start.saturating_add(limit));
+        let mut escrows = Vec::new(&env);
+        let mut page = Vec::new(&env);
+        for i in start..end {
+            if i == start || i % INDEX_PAGE_SIZE == 0 {
+                page = Self::page(&env, &user, i / INDEX_PAGE_SIZE);
+            }
+            escrows.push_back(page.get_unchecked(i % INDEX_PAGE_SIZE));
+        }
+        escrows
+    }
+
+    /// Returns how many escrows `user` is the client or freelancer of.
+    pub fn escrow_count_of(env: Env, user: Address) -> u32 {
+        ttl::get(&env, &DataKey::UserEscrowCount(user)).unwrap_or(0)
+    }
+
+    fn salt(env: &Env, client: &Address, freelancer: &Address, project_id: &String) -> BytesN<32> {
+        let preimage = (client.clone(), freelancer.clone(), project_id.clone()).to_xdr(env);
+        env.crypto().sha256(&preimage).into()
+    }
+
+    fn page(env: &Env, user: &Address, page: u32) -> Vec<Address> {
+        ttl::get(env, &DataKey::UserEscrows(user.clone(), page)).unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Appends an escrow to the last page of `user`'s index, starting a new
+    /// page once it is full.
+    fn index(env: &Env, user: &Address, escrow: &Address) {
+        let count = Self::escrow_count_of(env.clone(), user.clone());
+        let page = count / INDEX_PAGE_SIZE;
+        let mut escrows = Self::page(env, user, page);
+        escrows.push_back(escrow.clone());
+        ttl::set(env, &DataKey::UserEscrows(user.clone(), page), &escrows);
+        ttl::set(env, &DataKey::UserEscrowCount(user.clone()), &(count + 1));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, Bytes};
+
+    /// Smallest wasm the host accepts as a contract: an `initialize` export
+    /// that returns `()`, plus the environment meta for protocol 21.
+    const STUB_ESCROW_WASM: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x06, 0x01, 0x60, 0x01, 0x7e, 0x01, 0x7e, // type: (i64) -> i64
+        0x03, 0x02, 0x01, 0x00, // function 0 has type 0
+        0x07, 0x0e, 0x01, 0x0a, b'i', b'n', b'i', b't', b'i', b'a', b'l', b'i', b'z', b'e', 0x00,
+        0x00, // export "initialize"
+        0x0a, 0x06, 0x01, 0x04, 0x00, 0x42, 0x02, 0x0b, // body: i64.const 2 (Void)
+        0x00, 0x1e, 0x11, b'c', b'o', b'n', b't', b'r', b'a', b'c', b't', b'e', b'n', b'v', b'm',
+        b'e', b't', b'a', b'v', b'0', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x15, 0x00, 0x00,
+        0x00, 0x00, // interface version 21.0
+    ];
+
+    fn setup(env: &Env) -> EscrowFactoryClient<'_> {
+        env.mock_all_auths();
+        let wasm_hash = env
+            .deployer()
+            .upload_contract_wasm(Bytes::from_slice(env, STUB_ESCROW_WASM));
+        let factory = EscrowFactoryClient::new(env, &env.register_contract(None, EscrowFactory));
+        factory.initialize(&Address::generate(env), &wasm_hash);
+        factory
+    }
+
+    #[test]
+    fn test_deploys_to_predicted_address() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let client = Address::generate(&env);
+        let freelancer = Address::generate(&env);
+        let project = String::from_str(&env, "website-redesign");
+
+        let predicted = factory.escrow_address(&client, &freelancer, &project);
+        assert_eq!(factory.get_escrow(&client, &freelancer, &project), None);
+
+        let escrow = factory.deploy_escrow(&client, &freelancer, &project);
+        assert_eq!(escrow, predicted);
+        assert_eq!(
+            factory.get_escrow(&client, &freelancer, &project),
+            Some(escrow.clone())
+        );
+        assert_eq!(
+            factory.try_deploy_escrow(&client, &freelancer, &project),
+            Err(Ok(FactoryError::ProjectExists.into()))
+        );
+
+        // Any change to the triple yields a different address.
+        let other = String::from_str(&env, "mobile-app");
+        assert_ne!(factory.escrow_address(&client, &freelancer, &other), escrow);
+        assert_ne!(
+            factory.escrow_address(&freelancer, &client, &project),
+            escrow
+        );
+    }
+
+    #[test]
+    fn test_indexes_escrows_per_user() {
+        let env = Env::default();
+        let factory = setup(&env);
+        let client = Address::generate(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+
+        let first = factory.deploy_escrow(&client, &alice, &String::from_str(&env, "logo"));
+        let second = factory.deploy_escrow(&client, &bob, &String::from_str(&env, "site"));
+
+        assert_eq!(
+            factory.escrows_of(&client, &0, &10),
+            Vec::from_array(&env, [first.clone(), second.clone()])
+        );
+        assert_eq!(factory.escrow_count_of(&client), 2);
+        assert_eq!(
+            factory.escrows_of(&client, &1, &10),
+            Vec::from_array(&env, [second.clone()])
+        );
+        assert_eq!(
+            factory.escrows_of(&alice, &0, &10),
+            Vec::from_array(&env, [first])
+        );
+        assert_eq!(
+            factory.escrows_of(&bob, &0, &10),
+            Vec::from_array(&env, [second])
+        );
+        assert!(factory
+            .escrows_of(&Address::generate(&env), &0, &10)
+            .is_empty());
+        assert_eq!(
+            factory.try_escrows_of(&client, &0, &(MAX_PAGE_SIZE + 1)),
+            Err(Ok(CommonError::BatchTooLarge.into()))
+        );
+    }
+}
@@ -0,0 +1,1450 @@
+//! Typed events published by the LancePay contracts.
+//!
+//! Every event is published with a fixed `Symbol` as its first topic and the
+//! id of the object it concerns (escrow, dispute, user, ...) as its second
+//! topic. The data is always one of the `#[contracttype]` structs below, so
+//! off-chain indexers can subscribe by symbol and decode a stable payload.
+//! Contracts must publish through these helpers rather than calling
+//! `env.events().publish` directly.
+//!
+//! Every payload also carries the ledger `timestamp` it was published at and
+//! the schema `version` it was encoded with. Adding, removing or retyping a
+//! field of any payload bumps [`EVENT_VERSION`], so indexers can tell which
+//! layout to decode.
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, String, Symbol, Val, Vec};
+
+use crate::badge::BadgeKind;
+use crate::batch_payout::Batch;
+use crate::gasless::SponsoredOperation;
+use crate::rbac::Role;
+use crate::reputation::Outcome;
+use crate::upgrade_utils::FeatureFlag;
+
+/// Schema version stamped on every event payload.
+pub const EVENT_VERSION: u32 = 2;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputeStartedEvent {
+    pub disputer: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EvidenceSubmittedEvent {
+    pub submitter: Address,
+    pub evidence_hash: String,
+    pub round: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputeResolvedEvent {
+    pub split_ratio: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PayoutCreditedEvent {
+    pub token: Address,
+    pub amount: i128,
+    /// Claimable balance after the credit
+    pub balance: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BatchPaidEvent {
+    pub payer: Address,
+    pub token: Address,
+    pub total: i128,
+    pub paid: u32,
+    pub credited: u32,
+    pub rejected: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PayoutClaimedEvent {
+    pub token: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BondPostedEvent {
+    pub party: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BondForfeitedEvent {
+    /// Party the ruling went against
+    pub party: Address,
+    pub arbiter: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DefaultJudgmentEvent {
+    /// Disputer the dispute was resolved for
+    pub claimant: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputePayoutEvent {
+    pub freelancer_amount: i128,
+    pub client_amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputeAppealedEvent {
+    pub appellant: Address,
+    pub panel: Vec<Address>,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputeEscalatedEvent {
+    pub deadline: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ArbiterAssignedEvent {
+    pub arbiter: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowDeployedEvent {
+    pub client: Address,
+    pub freelancer: Address,
+    pub project_id: String,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowFundedEvent {
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MilestoneReleasedEvent {
+    pub milestone_index: u32,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowDisputedEvent {
+    pub raised_by: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowDisputeResolvedEvent {
+    pub split_ratio: u32,
+    pub freelancer_amount: i128,
+    pub client_amount: i128,
+    pub arbiter_fee: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowSettledEvent {
+    pub split_ratio: u32,
+    pub agreement_hash: BytesN<32>,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MilestoneDeadlineEvent {
+    pub milestone_index: u32,
+    pub deadline: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowCancelledEvent {
+    pub refund_ratio: u32,
+    pub freelancer_amount: i128,
+    pub client_amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowFreezeEvent {
+    pub admin: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowClawedBackEvent {
+    /// Compliance officer who performed the clawback
+    pub officer: Address,
+    pub to: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Ledger the escrow was frozen on
+    pub frozen_ledger: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EscrowRefundedEvent {
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PayoutConvertedEvent {
+    pub asset: Address,
+    pub amount_in: i128,
+    pub amount_out: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RecurringCollectedEvent {
+    pub amount: i128,
+    /// Payments collected so far, including this one
+    pub collected: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AgreementCancelledEvent {
+    pub refund: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct StreamWithdrawnEvent {
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct StreamCancelledEvent {
+    pub cancelled_by: Address,
+    /// Vested balance paid to the freelancer on cancellation
+    pub payout: i128,
+    /// Unvested balance returned to the client
+    pub refund: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InvoiceCreatedEvent {
+    pub freelancer: Address,
+    pub payer: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InvoiceSettledEvent {
+    pub payer: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InvoiceCancelledEvent {
+    pub freelancer: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FeeDepositedEvent {
+    pub from: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TreasuryWithdrawalEvent {
+    pub to: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ReputationChangedEvent {
+    pub reporter: Address,
+    pub outcome: Outcome,
+    pub score: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BadgeMintedEvent {
+    pub issuer: Address,
+    pub escrow_id: u64,
+    pub kind: BadgeKind,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+/// Published as both `kyc_attested` and `kyc_revoked`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct KycChangedEvent {
+    pub attestor: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SponsorshipApprovedEvent {
+    pub operation: SponsoredOperation,
+    pub nonce: u64,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InvoicePaidEvent {
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TxProposedEvent {
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TxExecutedEvent {
+    pub tx_hash: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ProposalCancelledEvent {
+    pub cancelled_by: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ProposalQueuedEvent {
+    /// First ledger the proposal may execute on
+    pub executable_ledger: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ProposalVetoedEvent {
+    pub vetoed_by: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ProposalExpiredEvent {
+    pub expiry_ledger: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PathPaymentEvent {
+    pub source_amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct KeeperBountyPaidEvent {
+    pub keeper: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RebalanceExecutedEvent {
+    /// XLM received
+    pub amount: i128,
+    /// USDC spent
+    pub source_amount: i128,
+    /// Most USDC the swap may spend, allowing for slippage
+    pub max_source_amount: i128,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TrustlineConfiguredEvent {
+    pub asset_code: String,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FeatureFlagSetEvent {
+    pub min_protocol: u32,
+    pub admin_override: Option<bool>,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ContractUpgradedEvent {
+    pub wasm_hash: BytesN<32>,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PauseEvent {
+    pub ledger: u32,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RoleChangedEvent {
+    pub role: Role,
+    pub sender: Address,
+    pub timestamp: u64,
+    pub version: u32,
+}
+
+fn publish<S, D>(env: &Env, name: &str, subject: S, data: D)
+where
+    S: IntoVal<Env, Val>,
+    D: IntoVal<Env, Val>,
+{
+    env.events()
+        .publish((Symbol::new(env, name), subject), data);
+}
+
+pub fn emit_dispute_started(env: &Env, escrow_id: &String, disputer: &Address) {
+    publish(
+        env,
+        "dispute_started",
+        escrow_id.clone(),
+        DisputeStartedEvent {
+            disputer: disputer.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_evidence_submitted(
+    env: &Env,
+    dispute_id: &String,
+    submitter: &Address,
+    evidence_hash: &String,
+    round: u32,
+) {
+    publish(
+        env,
+        "evidence_submitted",
+        dispute_id.clone(),
+        EvidenceSubmittedEvent {
+            submitter: submitter.clone(),
+            evidence_hash: evidence_hash.clone(),
+            round,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_dispute_resolved(env: &Env, dispute_id: &String, split_ratio: u32) {
+    publish(
+        env,
+        "dispute_resolved",
+        dispute_id.clone(),
+        DisputeResolvedEvent {
+            split_ratio,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_payout_credited(
+    env: &Env,
+    recipient: &Address,
+    token: &Address,
+    amount: i128,
+    balance: i128,
+) {
+    publish(
+        env,
+        "payout_credited",
+        recipient.clone(),
+        PayoutCreditedEvent {
+            token: token.clone(),
+            amount,
+            balance,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_payout_claimed(env: &Env, recipient: &Address, token: &Address, amount: i128) {
+    publish(
+        env,
+        "payout_claimed",
+        recipient.clone(),
+        PayoutClaimedEvent {
+            token: token.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_batch_paid(env: &Env, batch_id: u64, batch: &Batch) {
+    publish(
+        env,
+        "batch_paid",
+        batch_id,
+        BatchPaidEvent {
+            payer: batch.payer.clone(),
+            token: batch.token.clone(),
+            total: batch.total,
+            paid: batch.paid,
+            credited: batch.credited,
+            rejected: batch.rejected,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_default_judgment(env: &Env, dispute_id: &String, claimant: &Address) {
+    publish(
+        env,
+        "default_judgment",
+        dispute_id.clone(),
+        DefaultJudgmentEvent {
+            claimant: claimant.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_bond_posted(env: &Env, dispute_id: &String, party: &Address, amount: i128) {
+    publish(
+        env,
+        "bond_posted",
+        dispute_id.clone(),
+        BondPostedEvent {
+            party: party.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_bond_forfeited(
+    env: &Env,
+    dispute_id: &String,
+    party: &Address,
+    arbiter: &Address,
+    amount: i128,
+) {
+    publish(
+        env,
+        "bond_forfeited",
+        dispute_id.clone(),
+        BondForfeitedEvent {
+            party: party.clone(),
+            arbiter: arbiter.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_dispute_payout(
+    env: &Env,
+    dispute_id: &String,
+    freelancer_amount: i128,
+    client_amount: i128,
+) {
+    publish(
+        env,
+        "dispute_payout",
+        dispute_id.clone(),
+        DisputePayoutEvent {
+            freelancer_amount,
+            client_amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_dispute_appealed(
+    env: &Env,
+    dispute_id: &String,
+    appellant: &Address,
+    panel: &Vec<Address>,
+) {
+    publish(
+        env,
+        "dispute_appealed",
+        dispute_id.clone(),
+        DisputeAppealedEvent {
+            appellant: appellant.clone(),
+            panel: panel.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_dispute_escalated(env: &Env, dispute_id: &String, deadline: u32) {
+    publish(
+        env,
+        "dispute_escalated",
+        dispute_id.clone(),
+        DisputeEscalatedEvent {
+            deadline,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_arbiter_assigned(env: &Env, dispute_id: &String, arbiter: &Address) {
+    publish(
+        env,
+        "arbiter_assigned",
+        dispute_id.clone(),
+        ArbiterAssignedEvent {
+            arbiter: arbiter.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_deployed(
+    env: &Env,
+    escrow: &Address,
+    client: &Address,
+    freelancer: &Address,
+    project_id: &String,
+) {
+    publish(
+        env,
+        "escrow_deployed",
+        escrow.clone(),
+        EscrowDeployedEvent {
+            client: client.clone(),
+            freelancer: freelancer.clone(),
+            project_id: project_id.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_funded(env: &Env, escrow_id: u64, amount: i128) {
+    publish(
+        env,
+        "escrow_funded",
+        escrow_id,
+        EscrowFundedEvent {
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_milestone_released(env: &Env, escrow_id: u64, milestone_index: u32, amount: i128) {
+    publish(
+        env,
+        "milestone_released",
+        escrow_id,
+        MilestoneReleasedEvent {
+            milestone_index,
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_deadline_extension_requested(
+    env: &Env,
+    escrow_id: u64,
+    milestone_index: u32,
+    deadline: u32,
+) {
+    publish(
+        env,
+        "deadline_extension_requested",
+        escrow_id,
+        MilestoneDeadlineEvent {
+            milestone_index,
+            deadline,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_deadline_extended(env: &Env, escrow_id: u64, milestone_index: u32, deadline: u32) {
+    publish(
+        env,
+        "deadline_extended",
+        escrow_id,
+        MilestoneDeadlineEvent {
+            milestone_index,
+            deadline,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_disputed(env: &Env, escrow_id: u64, raised_by: &Address) {
+    publish(
+        env,
+        "escrow_disputed",
+        escrow_id,
+        EscrowDisputedEvent {
+            raised_by: raised_by.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_dispute_resolved(
+    env: &Env,
+    escrow_id: u64,
+    split_ratio: u32,
+    freelancer_amount: i128,
+    client_amount: i128,
+    arbiter_fee: i128,
+) {
+    publish(
+        env,
+        "escrow_dispute_resolved",
+        escrow_id,
+        EscrowDisputeResolvedEvent {
+            split_ratio,
+            freelancer_amount,
+            client_amount,
+            arbiter_fee,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_settled(
+    env: &Env,
+    escrow_id: u64,
+    split_ratio: u32,
+    agreement_hash: &BytesN<32>,
+) {
+    publish(
+        env,
+        "escrow_settled",
+        escrow_id,
+        EscrowSettledEvent {
+            split_ratio,
+            agreement_hash: agreement_hash.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_cancelled(
+    env: &Env,
+    escrow_id: u64,
+    refund_ratio: u32,
+    freelancer_amount: i128,
+    client_amount: i128,
+) {
+    publish(
+        env,
+        "escrow_cancelled",
+        escrow_id,
+        EscrowCancelledEvent {
+            refund_ratio,
+            freelancer_amount,
+            client_amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_frozen(env: &Env, escrow_id: u64, admin: &Address) {
+    publish(
+        env,
+        "escrow_frozen",
+        escrow_id,
+        EscrowFreezeEvent {
+            admin: admin.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_unfrozen(env: &Env, escrow_id: u64, admin: &Address) {
+    publish(
+        env,
+        "escrow_unfrozen",
+        escrow_id,
+        EscrowFreezeEvent {
+            admin: admin.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_clawed_back(
+    env: &Env,
+    escrow_id: u64,
+    officer: &Address,
+    to: &Address,
+    token: &Address,
+    amount: i128,
+    frozen_ledger: u32,
+) {
+    publish(
+        env,
+        "escrow_clawed_back",
+        escrow_id,
+        EscrowClawedBackEvent {
+            officer: officer.clone(),
+            to: to.clone(),
+            token: token.clone(),
+            amount,
+            frozen_ledger,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_escrow_refunded(env: &Env, escrow_id: u64, amount: i128) {
+    publish(
+        env,
+        "escrow_refunded",
+        escrow_id,
+        EscrowRefundedEvent {
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_payout_converted(
+    env: &Env,
+    escrow_id: u64,
+    asset: &Address,
+    amount_in: i128,
+    amount_out: i128,
+) {
+    publish(
+        env,
+        "payout_converted",
+        escrow_id,
+        PayoutConvertedEvent {
+            asset: asset.clone(),
+            amount_in,
+            amount_out,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_recurring_collected(env: &Env, agreement_id: u64, amount: i128, collected: u32) {
+    publish(
+        env,
+        "recurring_collected",
+        agreement_id,
+        RecurringCollectedEvent {
+            amount,
+            collected,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_agreement_cancelled(env: &Env, agreement_id: u64, refund: i128) {
+    publish(
+        env,
+        "agreement_cancelled",
+        agreement_id,
+        AgreementCancelledEvent {
+            refund,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_stream_withdrawn(env: &Env, stream_id: u64, amount: i128) {
+    publish(
+        env,
+        "stream_withdrawn",
+        stream_id,
+        StreamWithdrawnEvent {
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_stream_cancelled(
+    env: &Env,
+    stream_id: u64,
+    cancelled_by: &Address,
+    payout: i128,
+    refund: i128,
+) {
+    publish(
+        env,
+        "stream_cancelled",
+        stream_id,
+        StreamCancelledEvent {
+            cancelled_by: cancelled_by.clone(),
+            payout,
+            refund,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_invoice_created(
+    env: &Env,
+    invoice_id: u64,
+    freelancer: &Address,
+    payer: &Address,
+    amount: i128,
+) {
+    publish(
+        env,
+        "invoice_created",
+        invoice_id,
+        InvoiceCreatedEvent {
+            freelancer: freelancer.clone(),
+            payer: payer.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_invoice_settled(env: &Env, invoice_id: u64, payer: &Address, amount: i128) {
+    publish(
+        env,
+        "invoice_settled",
+        invoice_id,
+        InvoiceSettledEvent {
+            payer: payer.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_invoice_cancelled(env: &Env, invoice_id: u64, freelancer: &Address) {
+    publish(
+        env,
+        "invoice_cancelled",
+        invoice_id,
+        InvoiceCancelledEvent {
+            freelancer: freelancer.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_fee_deposited(env: &Env, token: &Address, from: &Address, amount: i128) {
+    publish(
+        env,
+        "fee_deposited",
+        token.clone(),
+        FeeDepositedEvent {
+            from: from.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_treasury_withdrawal(env: &Env, token: &Address, to: &Address, amount: i128) {
+    publish(
+        env,
+        "treasury_withdrawal",
+        token.clone(),
+        TreasuryWithdrawalEvent {
+            to: to.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_reputation_changed(
+    env: &Env,
+    subject: &Address,
+    reporter: &Address,
+    outcome: Outcome,
+    score: u32,
+) {
+    publish(
+        env,
+        "reputation_changed",
+        subject.clone(),
+        ReputationChangedEvent {
+            reporter: reporter.clone(),
+            outcome,
+            score,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_badge_minted(
+    env: &Env,
+    holder: &Address,
+    issuer: &Address,
+    escrow_id: u64,
+    kind: BadgeKind,
+) {
+    publish(
+        env,
+        "badge_minted",
+        holder.clone(),
+        BadgeMintedEvent {
+            issuer: issuer.clone(),
+            escrow_id,
+            kind,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_kyc_attested(env: &Env, subject: &Address, attestor: &Address) {
+    publish(
+        env,
+        "kyc_attested",
+        subject.clone(),
+        KycChangedEvent {
+            attestor: attestor.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_kyc_revoked(env: &Env, subject: &Address, attestor: &Address) {
+    publish(
+        env,
+        "kyc_revoked",
+        subject.clone(),
+        KycChangedEvent {
+            attestor: attestor.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_sponsorship_approved(
+    env: &Env,
+    user: &Address,
+    operation: &SponsoredOperation,
+    nonce: u64,
+) {
+    publish(
+        env,
+        "sponsorship_approved",
+        user.clone(),
+        SponsorshipApprovedEvent {
+            operation: operation.clone(),
+            nonce,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_invoice_paid(env: &Env, invoice_id: &String, tx_hash: &String) {
+    publish(
+        env,
+        "invoice_paid",
+        invoice_id.clone(),
+        InvoicePaidEvent {
+            tx_hash: tx_hash.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_tx_proposed(env: &Env, proposer: &Address, amount: i128) {
+    publish(
+        env,
+        "tx_proposed",
+        proposer.clone(),
+        TxProposedEvent {
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_tx_executed(env: &Env, co_signer: &Address, tx_hash: i128) {
+    publish(
+        env,
+        "tx_executed",
+        co_signer.clone(),
+        TxExecutedEvent {
+            tx_hash,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_proposal_cancelled(env: &Env, proposal_id: u64, cancelled_by: &Address) {
+    publish(
+        env,
+        "proposal_cancelled",
+        proposal_id,
+        ProposalCancelledEvent {
+            cancelled_by: cancelled_by.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_proposal_queued(env: &Env, proposal_id: u64, executable_ledger: u32) {
+    publish(
+        env,
+        "proposal_queued",
+        proposal_id,
+        ProposalQueuedEvent {
+            executable_ledger,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_proposal_vetoed(env: &Env, proposal_id: u64, vetoed_by: &Address) {
+    publish(
+        env,
+        "proposal_vetoed",
+        proposal_id,
+        ProposalVetoedEvent {
+            vetoed_by: vetoed_by.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_proposal_expired(env: &Env, proposal_id: u64, expiry_ledger: u32) {
+    publish(
+        env,
+        "proposal_expired",
+        proposal_id,
+        ProposalExpiredEvent {
+            expiry_ledger,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_path_payment(env: &Env, from: &Address, source_amount: i128) {
+    publish(
+        env,
+        "path_payment_success",
+        from.clone(),
+        PathPaymentEvent {
+            source_amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_rebalance_executed(
+    env: &Env,
+    wallet: &Address,
+    amount: i128,
+    source_amount: i128,
+    max_source_amount: i128,
+) {
+    publish(
+        env,
+        "rebalance_executed",
+        wallet.clone(),
+        RebalanceExecutedEvent {
+            amount,
+            source_amount,
+            max_source_amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_keeper_bounty_paid(
+    env: &Env,
+    wallet: &Address,
+    keeper: &Address,
+    token: &Address,
+    amount: i128,
+) {
+    publish(
+        env,
+        "keeper_bounty_paid",
+        wallet.clone(),
+        KeeperBountyPaidEvent {
+            keeper: keeper.clone(),
+            token: token.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_trustline_configured(env: &Env, user: &Address, asset_code: &String) {
+    publish(
+        env,
+        "trustline_configured",
+        user.clone(),
+        TrustlineConfiguredEvent {
+            asset_code: asset_code.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_contract_upgraded(
+    env: &Env,
+    contract: &Address,
+    wasm_hash: &BytesN<32>,
+    version: (u32, u32, u32),
+) {
+    let (major, minor, patch) = version;
+    publish(
+        env,
+        "contract_upgraded",
+        contract.clone(),
+        ContractUpgradedEvent {
+            wasm_hash: wasm_hash.clone(),
+            major,
+            minor,
+            patch,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_feature_flag_set(env: &Env, flag: &Symbol, feature: &FeatureFlag) {
+    publish(
+        env,
+        "feature_flag_set",
+        flag.clone(),
+        FeatureFlagSetEvent {
+            min_protocol: feature.min_protocol,
+            admin_override: feature.admin_override,
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_role_granted(env: &Env, account: &Address, role: Role, sender: &Address) {
+    publish(
+        env,
+        "role_granted",
+        account.clone(),
+        RoleChangedEvent {
+            role,
+            sender: sender.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_role_revoked(env: &Env, account: &Address, role: Role, sender: &Address) {
+    publish(
+        env,
+        "role_revoked",
+        account.clone(),
+        RoleChangedEvent {
+            role,
+            sender: sender.clone(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_paused(env: &Env, admin: &Address) {
+    publish(
+        env,
+        "paused",
+        admin.clone(),
+        PauseEvent {
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
+
+pub fn emit_unpaused(env: &Env, admin: &Address) {
+    publish(
+        env,
+        "unpaused",
+        admin.clone(),
+        PauseEvent {
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+            version: EVENT_VERSION,
+        },
+    );
+}
@@ -1,34 +1,942 @@
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, Address, BytesN, Env,
+    IntoVal, Symbol, Vec,
+};
+
+use crate::admin::{self, KeyRole, Roles};
+use crate::rbac::{self, Role};
+use crate::{events, pausable, ttl};
+
+/// Number of ledgers in one sponsorship "day" (~5s close time).
+pub const LEDGERS_PER_DAY: u32 = 17_280;
+
+/// Sponsored transactions each user may request per ledger-day.
+pub const DAILY_SPONSORSHIP_LIMIT: u32 = 10;
+
+/// Fees (in stroops) sponsored for each user per ledger-day: 1 XLM.
+pub const DAILY_FEE_LIMIT: i128 = 10_000_000;
+
+/// Ledgers in one treasury reporting epoch (~1 week).
+pub const EPOCH_LEDGERS: u32 = 7 * LEDGERS_PER_DAY;
+
+/// Maximum number of users accepted by a single batch quota query.
+pub const MAX_QUOTA_BATCH: u32 = 50;
+
+/// Errors raised by sponsorship requests.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum GaslessError {
+    /// The request's nonce is not the user's next nonce (e.g. a replay)
+    InvalidNonce = 100,
+    /// The request is not eligible for sponsorship
+    NotEligible = 101,
+    /// The fee limit is negative
+    InvalidLimits = 102,
+    /// The amount ceiling is negative
+    InvalidCeiling = 103,
+    /// The funding amount is not positive
+    InvalidAmount = 104,
+    /// The bonus budget cannot cover the grant
+    InsufficientBudget = 105,
+    /// The caller does not hold the operator role needed to grant bonus quota
+    NotGranter = 106,
+    /// Too many users were requested at once
+    BatchTooLarge = 107,
+    /// Funding would take the bonus budget past `u32::MAX`
+    BudgetOverflow = 108,
+}
+
+/// Per-user sponsorship usage for the current ledger-day.
+#[derive(Clone)]
+#[contracttype]
+pub struct DailyUsage {
+    pub day: u32,
+    pub count: u32,
+    /// Fees sponsored so far, in stroops
+    pub fees: i128,
+}
+
+/// The operation a sponsored transaction performs, e.g. a USDC `transfer`
+/// into a platform escrow.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SponsoredOperation {
+    /// Contract the transaction invokes
+    pub contract: Address,
+    pub function: Symbol,
+    /// SHA-256 of the XDR-encoded call arguments
+    pub args_hash: BytesN<32>,
+    /// Amount moved by the call; zero if it moves none
+    pub amount: i128,
+    /// Most the platform will pay for the fee bump, in stroops
+    pub max_fee: i128,
+}
+
+/// A sponsored operation a user has approved, for the relayer to build the
+/// fee-bump transaction against.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SponsorshipApproval {
+    pub operation: SponsoredOperation,
+    pub approved_ledger: u32,
+}
+
+/// Daily gasless allowance of every user.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SponsorshipLimits {
+    /// Sponsored transactions per ledger-day, before bonus quota
+    pub max_count: u32,
+    /// Fees sponsored per ledger-day, in stroops
+    pub max_fees: i128,
+}
+
+/// What a user may still have sponsored today.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RemainingQuota {
+    /// Transactions left in the daily allowance
+    pub count: u32,
+    /// Unspent bonus quota, usable once `count` runs out
+    pub bonus: u32,
+    /// Fees left, in stroops
+    pub fees: i128,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Usage(Address),
+    /// Next nonce a user's sponsorship request must carry
+    Nonce(Address),
+    Limits,
+    BonusBudget,
+    BonusQuota(Address),
+    LastActivity(Address),
+    IdleWindow,
+    /// Amount ceiling of a whitelisted `(contract, function)`
+    AllowedOperation(Address, Symbol),
+    /// Approved sponsorship by user and the nonce it consumed
+    Approval(Address, u64),
+}
 
 #[contract]
 pub struct GaslessHandler;
 
 #[contractimpl]
 impl GaslessHandler {
-    /// sponsored_tx_xdr: The mock inner transaction signed by the user.
-    /// Returns: A mock "Fee Bump" transaction XDR signed by the platform.
-    pub fn sponsor_transaction(env: Env, inner_tx_xdr: String, user: Address) -> String {
-        // 1. Verify user signature on inner tx (implicit in real SDK usage, explicit here)
-        user.require_auth();
-
-        // 2. Validate the transaction (Anti-Spam / Abuse)
-        if !Self::validate_sponsorship(&env, &inner_tx_xdr) {
-            panic!("Transaction does not meet sponsorship criteria");
-        }
-
-        // 3. Mock "Wrapping" the transaction
-        // In reality: TransactionBuilder.buildFeeBumpTransaction(innerTx, feeSource: platform_wallet)
-        // Note: soroban_sdk::String doesn't have an append method.
-        // For the mock, we just return the inner_tx_xdr.
-        inner_tx_xdr
-    }
-
-    /// Checks if the transaction is eligible for sponsorship.
-    /// e.g., Is it a USDC transfer? Is the amount within limits?
-    fn validate_sponsorship(_env: &Env, _tx_xdr: &String) -> bool {
-        // Mock validation logic
-        // Check daily limit for user?
-        // Check if op is allowed?
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Assigns a key role (veto, recovery, fee collector, treasury). Admin only.
+    pub fn set_role(env: Env, role: KeyRole, address: Address) {
+        admin::set_role(&env, role, &address);
+    }
+
+    /// Returns the admin and every key role in a single read.
+    pub fn get_roles(env: Env) -> Roles {
+        admin::get_roles(&env)
+    }
+
+    /// Grants `role` to `account`. `caller` must be the role admin.
+    pub fn grant_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::grant_role(&env, &caller, role, &account);
+    }
+
+    /// Revokes `role` from `account`. `caller` must be the role admin.
+    pub fn revoke_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::revoke_role(&env, &caller, role, &account);
+    }
+
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        rbac::has_role(&env, role, &account)
+    }
+
+    /// Returns the account that grants and revokes roles.
+    pub fn role_admin(env: Env) -> Address {
+        rbac::role_admin(&env)
+    }
+
+    /// Hands role administration to `new_admin`, e.g. the multisig
+    /// governance contract. `caller` must be the current role admin.
+    pub fn transfer_role_admin(env: Env, caller: Address, new_admin: Address) {
+        rbac::transfer_role_admin(&env, &caller, &new_admin);
+    }
+
+    /// Extends the TTL of each persistent entry in `keys` that is running
+    /// low, so idle quotas are not archived. Meant for keepers; anyone may
+    /// call it. Returns how many of the entries exist.
+    pub fn bump_entries(env: Env, keys: Vec<DataKey>) -> u32 {
+        ttl::bump_all(&env, &keys)
+    }
+
+    /// Pauses every state-changing entrypoint. Admin or guardian only.
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause. Admin or guardian only.
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Sets the daily gasless allowance of every user. Admin only.
+    pub fn set_sponsorship_limits(env: Env, admin: Address, limits: SponsorshipLimits) {
+        admin::require_admin_caller(&env, &admin);
+        if limits.max_fees < 0 {
+            panic_with_error!(env, GaslessError::InvalidLimits);
+        }
+        env.storage().instance().set(&DataKey::Limits, &limits);
+    }
+
+    /// Returns the daily allowance, defaulting to `DAILY_SPONSORSHIP_LIMIT`
+    /// transactions and `DAILY_FEE_LIMIT` stroops.
+    pub fn sponsorship_limits(env: Env) -> SponsorshipLimits {
+        env.storage()
+            .instance()
+            .get(&DataKey::Limits)
+            .unwrap_or(SponsorshipLimits {
+                max_count: DAILY_SPONSORSHIP_LIMIT,
+                max_fees: DAILY_FEE_LIMIT,
+            })
+    }
+
+    /// Whitelists `function` on `contract` for sponsorship, for amounts up
+    /// to `max_amount`. Admin only.
+    pub fn allow_operation(
+        env: Env,
+        admin: Address,
+        contract: Address,
+        function: Symbol,
+        max_amount: i128,
+    ) {
+        admin::require_admin_caller(&env, &admin);
+        if max_amount < 0 {
+            panic_with_error!(env, GaslessError::InvalidCeiling);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::AllowedOperation(contract, function), &max_amount);
+    }
+
+    /// Removes `function` on `contract` from the whitelist. Admin only.
+    pub fn disallow_operation(env: Env, admin: Address, contract: Address, function: Symbol) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .instance()
+            .remove(&DataKey::AllowedOperation(contract, function));
+    }
+
+    /// Returns the amount ceiling of a whitelisted operation, or `None` if
+    /// it is not sponsored.
+    pub fn operation_ceiling(env: Env, contract: Address, function: Symbol) -> Option<i128> {
+        env.storage()
+            .instance()
+            .get(&DataKey::AllowedOperation(contract, function))
+    }
+
+    /// Returns the nonce `user`'s next sponsorship request must carry.
+    pub fn nonce(env: Env, user: Address) -> u64 {
+        ttl::get(&env, &DataKey::Nonce(user)).unwrap_or(0)
+    }
+
+    /// Returns what `user` may still have sponsored today.
+    pub fn get_remaining_quota(env: Env, user: Address) -> RemainingQuota {
+        let limits = Self::sponsorship_limits(env.clone());
+        let usage = Self::usage_today(&env, &user);
+        RemainingQuota {
+            count: limits.max_count.saturating_sub(usage.count),
+            bonus: Self::bonus_quota(env, user),
+            fees: (limits.max_fees - usage.fees).max(0),
+        }
+    }
+
+    /// Adds `amount` stroops to the pool that pays for fee bumps. Admin only.
+    pub fn fund_sponsorship_pool(env: Env, admin: Address, amount: i128) {
+        admin::require_admin_caller(&env, &admin);
+        if amount <= 0 {
+            panic_with_error!(env, GaslessError::InvalidAmount);
+        }
+        treasury::fund(&env, amount);
+    }
+
+    /// Returns the stroops left in the sponsorship pool.
+    pub fn pool_balance(env: Env) -> i128 {
+        treasury::balance(&env)
+    }
+
+    /// Returns the current treasury reporting epoch.
+    pub fn current_epoch(env: Env) -> u32 {
+        treasury::current_epoch(&env)
+    }
+
+    /// Returns the fees sponsored during `epoch`, in stroops.
+    pub fn epoch_spend(env: Env, epoch: u32) -> i128 {
+        treasury::epoch_spend(&env, epoch)
+    }
+
+    /// Adds `amount` sponsorships to the global bonus budget. Admin only.
+    ///
+    /// # Panics
+    /// With `BudgetOverflow` if the budget would exceed `u32::MAX`
+    pub fn fund_bonus_budget(env: Env, admin: Address, amount: u32) {
+        admin::require_admin_caller(&env, &admin);
+        let budget = Self::bonus_budget(env.clone())
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(env, GaslessError::BudgetOverflow));
+        env.storage().instance().set(&DataKey::BonusBudget, &budget);
+    }
+
+    /// Moves `amount` sponsorships from the global budget into `user`'s bonus
+    /// quota, spent once their daily allowance runs out. Admin only.
+    pub fn grant_bonus_quota(env: Env, admin: Address, user: Address, amount: u32) {
+        admin::require_admin_caller(&env, &admin);
+        Self::credit_bonus(&env, &user, amount);
+    }
+
+    /// Grants `user` bonus quota from the budget on behalf of an operator,
+    /// such as an escrow contract sponsoring a dispute winner's withdrawal.
+    pub fn grant_sponsorship(env: Env, granter: Address, user: Address, amount: u32) {
+        pausable::require_not_paused(&env);
+        granter.require_auth();
+        if !rbac::has_role(&env, Role::Operator, &granter) {
+            panic_with_error!(env, GaslessError::NotGranter);
+        }
+        Self::credit_bonus(&env, &user, amount);
+    }
+
+    /// Sets how many ledgers a user may stay inactive before their bonus
+    /// quota returns to the budget. Zero disables expiry. Admin only.
+    pub fn set_idle_window(env: Env, admin: Address, ledgers: u32) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage().instance().set(&DataKey::IdleWindow, &ledgers);
+    }
+
+    /// Returns the configured idle window in ledgers (zero when disabled).
+    pub fn idle_window(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::IdleWindow)
+            .unwrap_or(0)
+    }
+
+    /// Returns the sponsorships left in the global bonus budget.
+    pub fn bonus_budget(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::BonusBudget)
+            .unwrap_or(0)
+    }
+
+    /// Returns `user`'s unspent bonus quota.
+    pub fn bonus_quota(env: Env, user: Address) -> u32 {
+        ttl::get(&env, &DataKey::BonusQuota(user)).unwrap_or(0)
+    }
+
+    /// Returns the ledger of `user`'s last sponsorship or grant, if any.
+    pub fn last_activity(env: Env, user: Address) -> Option<u32> {
+        ttl::get(&env, &DataKey::LastActivity(user))
+    }
+
+    /// Returns an idle user's bonus quota to the global budget. Anyone may
+    /// call this; it is a no-op for users still inside the idle window.
+    /// Returns the number of sponsorships reclaimed.
+    pub fn reclaim_idle_quota(env: Env, user: Address) -> u32 {
+        pausable::require_not_paused(&env);
+        Self::reclaim_if_idle(&env, &user)
+    }
+
+    /// Records `user`'s approval of a sponsored `operation`, charging its
+    /// `max_fee` against the user's quota and the pool. The platform's
+    /// off-chain relayer builds the fee-bump transaction against the stored
+    /// approval (see `get_approval`).
+    ///
+    /// The user authorizes exactly `(operation, nonce)`, so an approval
+    /// cannot be replayed or reused for other arguments or a higher fee.
+    ///
+    /// # Errors
+    /// `InvalidNonce` on a replayed or out-of-order nonce, `NotEligible` if
+    /// the request fails the sponsorship checks
+    pub fn sponsor_transaction(
+        env: Env,
+        user: Address,
+        operation: SponsoredOperation,
+        nonce: u64,
+    ) -> Result<(), GaslessError> {
+        pausable::require_not_paused(&env);
+        user.require_auth_for_args((operation.clone(), nonce).into_val(&env));
+
+        // Reject replays: each nonce is used once, in order
+        let expected = Self::nonce(env.clone(), user.clone());
+        if nonce != expected {
+            return Err(GaslessError::InvalidNonce);
+        }
+        ttl::set(&env, &DataKey::Nonce(user.clone()), &(expected + 1));
+
+        if !Self::validate_sponsorship(&env, &user, &operation) {
+            return Err(GaslessError::NotEligible);
+        }
+        ttl::set(
+            &env,
+            &DataKey::Approval(user.clone(), nonce),
+            &SponsorshipApproval {
+                operation: operation.clone(),
+                approved_ledger: env.ledger().sequence(),
+            },
+        );
+        events::emit_sponsorship_approved(&env, &user, &operation, nonce);
+        Ok(())
+    }
+
+    /// Returns the sponsorship `user` approved with `nonce`, if any.
+    pub fn get_approval(env: Env, user: Address, nonce: u64) -> Option<SponsorshipApproval> {
+        ttl::get(&env, &DataKey::Approval(user, nonce))
+    }
+
+    /// Returns how many sponsored transactions each of `users` has left today,
+    /// in the same order as the input.
+    ///
+    /// # Panics
+    /// If more than `MAX_QUOTA_BATCH` users are requested
+    pub fn remaining_sponsorships_batch(env: Env, users: Vec<Address>) -> Vec<u32> {
+        if users.len() > MAX_QUOTA_BATCH {
+            panic_with_error!(env, GaslessError::BatchTooLarge);
+        }
+
+        let limit = Self::sponsorship_limits(env.clone()).max_count;
+        let mut remaining = Vec::new(&env);
+        for user in users.iter() {
+            let used = Self::usage_today(&env, &user).count;
+            remaining.push_back(limit.saturating_sub(used));
+        }
+        remaining
+    }
+
+    /// Checks if the transaction is eligible for sponsorship: a whitelisted
+    /// operation within its amount ceiling, inside the user's daily quota.
+    fn validate_sponsorship(env: &Env, user: &Address, operation: &SponsoredOperation) -> bool {
+        let fee = operation.max_fee;
+        if fee < 0 || operation.amount < 0 {
+            return false;
+        }
+        match Self::operation_ceiling(
+            env.clone(),
+            operation.contract.clone(),
+            operation.function.clone(),
+        ) {
+            Some(ceiling) if operation.amount <= ceiling => {}
+            _ => return false,
+        }
+        // Bonus quota of a user returning after the idle window is forfeited.
+        Self::reclaim_if_idle(env, user);
+
+        // Check the daily fee cap, which bonus quota does not extend
+        let limits = Self::sponsorship_limits(env.clone());
+        let mut usage = Self::usage_today(env, user);
+        if usage.fees + fee > limits.max_fees {
+            return false;
+        }
+        usage.fees += fee;
+
+        // Check daily limit for user, falling back to any bonus quota
+        if usage.count < limits.max_count {
+            usage.count += 1;
+        } else {
+            let bonus = Self::bonus_quota(env.clone(), user.clone());
+            if bonus == 0 {
+                return false;
+            }
+            ttl::set(env, &DataKey::BonusQuota(user.clone()), &(bonus - 1));
+        }
+
+        // Pay for the fee bump out of the pool
+        if !treasury::spend(env, fee) {
+            return false;
+        }
+        ttl::set(env, &DataKey::Usage(user.clone()), &usage);
+        ttl::set(
+            env,
+            &DataKey::LastActivity(user.clone()),
+            &env.ledger().sequence(),
+        );
+
         true
     }
+
+    /// Moves `amount` from the global budget to `user`'s bonus quota.
+    fn credit_bonus(env: &Env, user: &Address, amount: u32) {
+        let budget = Self::bonus_budget(env.clone());
+        if amount > budget {
+            panic_with_error!(env, GaslessError::InsufficientBudget);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::BonusBudget, &(budget - amount));
+
+        let quota = Self::bonus_quota(env.clone(), user.clone()) + amount;
+        ttl::set(env, &DataKey::BonusQuota(user.clone()), &quota);
+        // A grant (re)starts the idle clock.
+        ttl::set(
+            env,
+            &DataKey::LastActivity(user.clone()),
+            &env.ledger().sequence(),
+        );
+    }
+
+    /// Moves `user`'s bonus quota back to the budget if they have been
+    /// inactive for longer than the idle window.
+    fn reclaim_if_idle(env: &Env, user: &Address) -> u32 {
+        let window = Self::idle_window(env.clone());
+        let last = match Self::last_activity(env.clone(), user.clone()) {
+            Some(last) => last,
+            None => return 0,
+        };
+        if window == 0 || env.ledger().sequence() - last <= window {
+            return 0;
+        }
+        let quota = Self::bonus_quota(env.clone(), user.clone());
+        if quota == 0 {
+            return 0;
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::BonusQuota(user.clone()));
+        let budget = Self::bonus_budget(env.clone()) + quota;
+        env.storage().instance().set(&DataKey::BonusBudget, &budget);
+        quota
+    }
+
+    /// Loads the user's usage record, resetting it if it belongs to an earlier day.
+    fn usage_today(env: &Env, user: &Address) -> DailyUsage {
+        let day = env.ledger().sequence() / LEDGERS_PER_DAY;
+        ttl::get::<DataKey, DailyUsage>(env, &DataKey::Usage(user.clone()))
+            .filter(|usage| usage.day == day)
+            .unwrap_or(DailyUsage {
+                day,
+                count: 0,
+                fees: 0,
+            })
+    }
+}
+
+/// Accounting for the pool that pays for fee bumps: its balance and how
+/// much was spent in each reporting epoch.
+mod treasury {
+    use soroban_sdk::{contracttype, Env};
+
+    use super::EPOCH_LEDGERS;
+    use crate::ttl;
+
+    #[contracttype]
+    enum DataKey {
+        PoolBalance,
+        EpochSpend(u32),
+    }
+
+    pub(super) fn fund(env: &Env, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&DataKey::PoolBalance, &(balance(env) + amount));
+    }
+
+    pub(super) fn balance(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::PoolBalance)
+            .unwrap_or(0)
+    }
+
+    pub(super) fn current_epoch(env: &Env) -> u32 {
+        env.ledger().sequence() / EPOCH_LEDGERS
+    }
+
+    pub(super) fn epoch_spend(env: &Env, epoch: u32) -> i128 {
+        ttl::get(env, &DataKey::EpochSpend(epoch)).unwrap_or(0)
+    }
+
+    /// Takes `fee` out of the pool and books it to the current epoch.
+    /// Returns `false`, spending nothing, if the pool cannot cover it.
+    pub(super) fn spend(env: &Env, fee: i128) -> bool {
+        let pool = balance(env);
+        if fee > pool {
+            return false;
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::PoolBalance, &(pool - fee));
+        let epoch = current_epoch(env);
+        ttl::set(
+            env,
+            &DataKey::EpochSpend(epoch),
+            &(epoch_spend(env, epoch) + fee),
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, AuthorizedFunction, Ledger},
+        vec, Address, Env,
+    };
+
+    /// Fee-bump fee of a typical sponsored transaction, in stroops.
+    const FEE: i128 = 100;
+
+    struct Setup<'a> {
+        client: GaslessHandlerClient<'a>,
+        admin: Address,
+        /// A whitelisted 50 USDC transfer
+        op: SponsoredOperation,
+    }
+
+    /// Initializes the handler, whitelists USDC transfers up to 100 USDC and
+    /// funds the pool with 1 XLM.
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+        // Keep entries alive across the multi-day ledger jumps below.
+        env.ledger().with_mut(|l| {
+            l.min_persistent_entry_ttl = 10 * LEDGERS_PER_DAY;
+            l.max_entry_ttl = 20 * LEDGERS_PER_DAY;
+        });
+        let contract_id = env.register_contract(None, GaslessHandler);
+        let client = GaslessHandlerClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+        client.initialize(&admin);
+
+        let usdc = Address::generate(env);
+        let transfer = Symbol::new(env, "transfer");
+        client.allow_operation(&admin, &usdc, &transfer, &100_0000000);
+        let op = SponsoredOperation {
+            contract: usdc,
+            function: transfer,
+            args_hash: BytesN::from_array(env, &[7; 32]),
+            amount: 50_0000000,
+            max_fee: FEE,
+        };
+        client.fund_sponsorship_pool(&admin, &10_000_000);
+        Setup { client, admin, op }
+    }
+
+    /// Returns `op` with a different fee-bump ceiling.
+    fn with_fee(op: &SponsoredOperation, max_fee: i128) -> SponsoredOperation {
+        SponsoredOperation {
+            max_fee,
+            ..op.clone()
+        }
+    }
+
+    #[test]
+    fn test_user_authorizes_exact_operation() {
+        let env = Env::default();
+        let Setup { client, op, .. } = setup(&env);
+        let user = Address::generate(&env);
+
+        client.sponsor_transaction(&user, &op, &0);
+        let auths = env.auths();
+        assert_eq!(auths.len(), 1);
+        let (signer, invocation) = &auths[0];
+        assert_eq!(*signer, user);
+        match &invocation.function {
+            AuthorizedFunction::Contract((contract, _, args)) => {
+                assert_eq!(*contract, client.address);
+                assert_eq!(*args, (op.clone(), 0u64).into_val(&env));
+            }
+            _ => panic!("expected a contract authorization"),
+        }
+    }
+
+    #[test]
+    fn test_remaining_sponsorships_batch() {
+        let env = Env::default();
+        let Setup { client, op, .. } = setup(&env);
+
+        let heavy = Address::generate(&env);
+        let light = Address::generate(&env);
+        let idle = Address::generate(&env);
+
+        for _ in 0..DAILY_SPONSORSHIP_LIMIT {
+            client.sponsor_transaction(&heavy, &op, &client.nonce(&heavy));
+        }
+        client.sponsor_transaction(&light, &op, &client.nonce(&light));
+        client.sponsor_transaction(&light, &op, &client.nonce(&light));
+
+        let remaining = client.remaining_sponsorships_batch(&vec![
+            &env,
+            heavy.clone(),
+            light.clone(),
+            idle.clone(),
+        ]);
+        assert_eq!(
+            remaining,
+            vec![
+                &env,
+                0,
+                DAILY_SPONSORSHIP_LIMIT - 2,
+                DAILY_SPONSORSHIP_LIMIT
+            ]
+        );
+        assert!(client
+            .try_sponsor_transaction(&heavy, &op, &client.nonce(&heavy))
+            .is_err());
+
+        // Quotas reset on the next ledger-day.
+        env.ledger()
+            .with_mut(|l| l.sequence_number += LEDGERS_PER_DAY);
+        let remaining = client.remaining_sponsorships_batch(&vec![&env, heavy]);
+        assert_eq!(remaining, vec![&env, DAILY_SPONSORSHIP_LIMIT]);
+    }
+
+    #[test]
+    fn test_daily_fee_and_count_limits() {
+        let env = Env::default();
+        let Setup { client, admin, op } = setup(&env);
+        let user = Address::generate(&env);
+        client.set_sponsorship_limits(
+            &admin,
+            &SponsorshipLimits {
+                max_count: 3,
+                max_fees: 1_000,
+            },
+        );
+
+        client.sponsor_transaction(&user, &with_fee(&op, 600), &client.nonce(&user));
+        assert_eq!(
+            client.get_remaining_quota(&user),
+            RemainingQuota {
+                count: 2,
+                bonus: 0,
+                fees: 400,
+            }
+        );
+        // Within the count limit but over the fee cap.
+        assert_eq!(
+            client.try_sponsor_transaction(&user, &with_fee(&op, 401), &client.nonce(&user)),
+            Err(Ok(GaslessError::NotEligible))
+        );
+        client.sponsor_transaction(&user, &with_fee(&op, 300), &client.nonce(&user));
+        client.sponsor_transaction(&user, &with_fee(&op, 100), &client.nonce(&user));
+        assert!(client
+            .try_sponsor_transaction(&user, &with_fee(&op, 0), &client.nonce(&user))
+            .is_err());
+        assert!(client
+            .try_sponsor_transaction(&Address::generate(&env), &with_fee(&op, -1), &0)
+            .is_err());
+
+        env.ledger()
+            .with_mut(|l| l.sequence_number += LEDGERS_PER_DAY);
+        assert_eq!(
+            client.get_remaining_quota(&user),
+            RemainingQuota {
+                count: 3,
+                bonus: 0,
+                fees: 1_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_only_whitelisted_operations_are_sponsored() {
+        let env = Env::default();
+        let Setup { client, admin, op } = setup(&env);
+        let user = Address::generate(&env);
+
+        client.sponsor_transaction(&user, &op, &client.nonce(&user));
+        assert_eq!(
+            client.get_approval(&user, &0),
+            Some(SponsorshipApproval {
+                operation: op.clone(),
+                approved_ledger: env.ledger().sequence(),
+            })
+        );
+
+        // Unknown function on a whitelisted contract.
+        let mut other = op.clone();
+        other.function = Symbol::new(&env, "approve");
+        assert_eq!(
+            client.try_sponsor_transaction(&user, &other, &client.nonce(&user)),
+            Err(Ok(GaslessError::NotEligible))
+        );
+
+        // Above the amount ceiling.
+        let mut large = op.clone();
+        large.amount = 100_0000001;
+        assert!(client
+            .try_sponsor_transaction(&user, &large, &client.nonce(&user))
+            .is_err());
+
+        client.disallow_operation(&admin, &op.contract, &op.function);
+        assert_eq!(client.operation_ceiling(&op.contract, &op.function), None);
+        assert!(client
+            .try_sponsor_transaction(&user, &op, &client.nonce(&user))
+            .is_err());
+        // Rejected requests do not use up the quota.
+        assert_eq!(
+            client.get_remaining_quota(&user).count,
+            DAILY_SPONSORSHIP_LIMIT - 1
+        );
+    }
+
+    #[test]
+    fn test_sponsorship_draws_down_pool() {
+        let env = Env::default();
+        let Setup { client, admin, op } = setup(&env);
+        let user = Address::generate(&env);
+        let first = client.current_epoch();
+
+        client.sponsor_transaction(&user, &with_fee(&op, 9_999_000), &client.nonce(&user));
+        assert_eq!(client.pool_balance(), 1_000);
+        client.sponsor_transaction(&user, &op, &client.nonce(&user));
+        assert_eq!(client.pool_balance(), 900);
+
+        // The next epoch starts a fresh spend total.
+        env.ledger()
+            .with_mut(|l| l.sequence_number += EPOCH_LEDGERS);
+        client.sponsor_transaction(&user, &op, &client.nonce(&user));
+        assert_eq!(client.epoch_spend(&first), 9_999_100);
+        assert_eq!(client.epoch_spend(&(first + 1)), FEE);
+
+        // An exhausted pool refuses sponsorship until topped up.
+        assert!(client
+            .try_sponsor_transaction(&user, &with_fee(&op, 801), &client.nonce(&user))
+            .is_err());
+        assert_eq!(client.pool_balance(), 800);
+        client.fund_sponsorship_pool(&admin, &1);
+        client.sponsor_transaction(&user, &with_fee(&op, 801), &client.nonce(&user));
+        assert_eq!(client.pool_balance(), 0);
+        assert!(client.try_fund_sponsorship_pool(&admin, &0).is_err());
+    }
+
+    #[test]
+    fn test_replayed_request_is_rejected() {
+        let env = Env::default();
+        let Setup { client, op, .. } = setup(&env);
+        let user = Address::generate(&env);
+
+        assert_eq!(client.nonce(&user), 0);
+        client.sponsor_transaction(&user, &op, &0);
+        assert_eq!(client.nonce(&user), 1);
+        assert_eq!(
+            client.try_sponsor_transaction(&user, &op, &0),
+            Err(Ok(GaslessError::InvalidNonce))
+        );
+        assert_eq!(
+            client.try_sponsor_transaction(&user, &op, &2),
+            Err(Ok(GaslessError::InvalidNonce))
+        );
+
+        // A rejected request leaves the nonce unused.
+        assert!(client
+            .try_sponsor_transaction(&user, &with_fee(&op, -1), &1)
+            .is_err());
+        client.sponsor_transaction(&user, &op, &1);
+        assert_eq!(client.nonce(&user), 2);
+    }
+
+    #[test]
+    fn test_remaining_sponsorships_batch_is_capped() {
+        let env = Env::default();
+        let Setup { client, .. } = setup(&env);
+
+        let mut users = Vec::new(&env);
+        for _ in 0..=MAX_QUOTA_BATCH {
+            users.push_back(Address::generate(&env));
+        }
+        assert!(client.try_remaining_sponsorships_batch(&users).is_err());
+    }
+
+    #[test]
+    fn test_pause_blocks_sponsorship() {
+        let env = Env::default();
+        let Setup { client, admin, op } = setup(&env);
+        let user = Address::generate(&env);
+
+        client.pause(&admin);
+        assert!(client
+            .try_sponsor_transaction(&user, &op, &client.nonce(&user))
+            .is_err());
+        assert_eq!(
+            client.remaining_sponsorships_batch(&vec![&env, user.clone()]),
+            vec![&env, DAILY_SPONSORSHIP_LIMIT]
+        );
+
+        client.unpause(&admin);
+        client.sponsor_transaction(&user, &op, &client.nonce(&user));
+    }
+
+    #[test]
+    fn test_idle_bonus_quota_is_reclaimed() {
+        let env = Env::default();
+        let Setup { client, admin, op } = setup(&env);
+        let idle = Address::generate(&env);
+        let active = Address::generate(&env);
+
+        client.fund_bonus_budget(&admin, &20);
+        client.set_idle_window(&admin, &(3 * LEDGERS_PER_DAY));
+        client.grant_bonus_quota(&admin, &idle, &5);
+        client.grant_bonus_quota(&admin, &active, &5);
+        assert_eq!(client.bonus_budget(), 10);
+
+        // Still inside the window: nothing to reclaim.
+        env.ledger()
+            .with_mut(|l| l.sequence_number += 2 * LEDGERS_PER_DAY);
+        assert_eq!(client.reclaim_idle_quota(&idle), 0);
+        client.sponsor_transaction(&active, &op, &client.nonce(&active));
+
+        env.ledger()
+            .with_mut(|l| l.sequence_number += 2 * LEDGERS_PER_DAY);
+        assert_eq!(client.reclaim_idle_quota(&idle), 5);
+        assert_eq!(client.bonus_quota(&idle), 0);
+        assert_eq!(client.reclaim_idle_quota(&active), 0);
+        assert_eq!(client.bonus_quota(&active), 5);
+        assert_eq!(client.bonus_budget(), 15);
+    }
+
+    #[test]
+    fn test_bonus_quota_extends_daily_limit() {
+        let env = Env::default();
+        let Setup { client, admin, op } = setup(&env);
+        let user = Address::generate(&env);
+
+        client.fund_bonus_budget(&admin, &2);
+        assert_eq!(
+            client.try_fund_bonus_budget(&admin, &u32::MAX),
+            Err(Ok(GaslessError::BudgetOverflow.into()))
+        );
+        client.grant_bonus_quota(&admin, &user, &2);
+        assert!(client.try_grant_bonus_quota(&admin, &user, &1).is_err());
+
+        for _ in 0..DAILY_SPONSORSHIP_LIMIT + 2 {
+            client.sponsor_transaction(&user, &op, &client.nonce(&user));
+        }
+        assert_eq!(client.bonus_quota(&user), 0);
+        assert!(client
+            .try_sponsor_transaction(&user, &op, &client.nonce(&user))
+            .is_err());
+    }
 }
@@ -0,0 +1,469 @@
+//! On-chain invoices.
+//!
+//! A freelancer issues an invoice naming the payer, asset, amount, a hash of
+//! the off-chain memo and a due date. The payer settles it in a single
+//! `pay_invoice` call that transfers the tokens straight to the freelancer
+//! and marks the invoice paid, so the contract doubles as the canonical
+//! payment record for the backend. Invoices are indexed by both parties.
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, BytesN,
+    Env, String, Vec,
+};
+
+use crate::admin;
+use crate::assets::{self, AssetInfo};
+use crate::errors::CommonError;
+use crate::rbac::{self, Role};
+use crate::{events, pausable, ttl};
+
+/// Number of invoice ids held by each page of a user's invoice index.
+pub const INDEX_PAGE_SIZE: u32 = 50;
+
+/// Maximum number of invoice ids returned by a single `invoices_of` call.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum InvoiceStatus {
+    /// Issued and awaiting payment
+    Pending = 0,
+    /// Paid in full by the payer
+    Paid = 1,
+    /// Withdrawn by the freelancer before payment
+    Cancelled = 2,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InvoiceRecord {
+    pub freelancer: Address,
+    pub payer: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Hash of the off-chain memo (line items, notes, ...)
+    pub memo_hash: BytesN<32>,
+    /// Ledger timestamp after which the invoice is overdue
+    pub due_date: u64,
+    pub created_at: u64,
+    pub paid_at: Option<u64>,
+    pub status: InvoiceStatus,
+}
+
+/// Errors raised by the invoice contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum InvoiceError {
+    /// No invoice with this id
+    InvoiceNotFound = 100,
+    /// The amount is not positive
+    InvalidAmount = 101,
+    /// The due date has already passed
+    DueDateInPast = 102,
+    /// The token is not a supported asset
+    UnsupportedToken = 103,
+    /// The invoice was already paid or cancelled
+    NotPending = 104,
+    /// The freelancer cannot invoice themselves
+    SelfInvoice = 105,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    InvoiceCount,
+    Invoice(u64),
+    /// One page of the invoices a user issued or was billed, oldest first,
+    /// by user and page number
+    UserInvoices(Address, u32),
+    /// Number of invoices a user issued or was billed
+    UserInvoiceCount(Address),
+}
+
+#[contract]
+pub struct Invoice;
+
+#[contractimpl]
+impl Invoice {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Grants `role` to `account`. `caller` must be the role admin.
+    pub fn grant_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::grant_role(&env, &caller, role, &account);
+    }
+
+    /// Revokes `role` from `account`. `caller` must be the role admin.
+    pub fn revoke_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::revoke_role(&env, &caller, role, &account);
+    }
+
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        rbac::has_role(&env, role, &account)
+    }
+
+    /// Pauses issuing and paying invoices. Admin or guardian only.
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause. Admin or guardian only.
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Registers (or re-points) a supported asset. Admin or operator only.
+    pub fn register_asset(env: Env, caller: Address, asset_code: String, asset: AssetInfo) {
+        assets::register_asset(&env, &caller, asset_code, asset);
+    }
+
+    /// Returns every supported `(code, asset)`, ordered by code.
+    pub fn supported_assets(env: Env) -> Vec<(String, AssetInfo)> {
+        assets::supported_assets(&env)
+    }
+
+    /// Issues an invoice from `freelancer` to `payer`. Requires the
+    /// freelancer's auth.
+    ///
+    /// # Returns
+    /// The new invoice's id
+    ///
+    /// # Panics
+    /// If the amount is not positive, the due date has passed, the parties
+    /// are the same or the token is not a supported asset
+    pub fn create_invoice(
+        env: Env,
+        freelancer: Address,
+        payer: Address,
+        token: Address,
+        amount: i128,
+        memo_hash: BytesN<32>,
+        due_date: u64,
+    ) -> u64 {
+        pausable::require_not_paused(&env);
+        freelancer.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(env, InvoiceError::InvalidAmount);
+        }
+        if freelancer == payer {
+            panic_with_error!(env, InvoiceError::SelfInvoice);
+        }
+        let now = env.ledger().timestamp();
+        if due_date < now {
+            panic_with_error!(env, InvoiceError::DueDateInPast);
+        }
+        if !assets::is_empty(&env) && !assets::is_supported_token(&env, &token) {
+            panic_with_error!(env, InvoiceError::UnsupportedToken);
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::InvoiceCount)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::InvoiceCount, &(id + 1));
+        Self::save(
+            &env,
+            id,
+            &InvoiceRecord {
+                freelancer: freelancer.clone(),
+                payer: payer.clone(),
+                token,
+                amount,
+                memo_hash,
+                due_date,
+                created_at: now,
+                paid_at: None,
+                status: InvoiceStatus::Pending,
+            },
+        );
+        Self::index(&env, &freelancer, id);
+        Self::index(&env, &payer, id);
+        events::emit_invoice_created(&env, id, &freelancer, &payer, amount);
+        id
+    }
+
+    /// Pays an invoice in full, transferring the tokens from the payer to the
+    /// freelancer. Overdue invoices can still be paid. Requires the payer's
+    /// auth.
+    ///
+    /// # Panics
+    /// If the invoice was already paid or cancelled
+    pub fn pay_invoice(env: Env, invoice_id: u64) {
+        pausable::require_not_paused(&env);
+        let mut invoice = Self::load(&env, invoice_id);
+        invoice.payer.require_auth();
+        if invoice.status != InvoiceStatus::Pending {
+            panic_with_error!(env, InvoiceError::NotPending);
+        }
+
+        token::Client::new(&env, &invoice.token).transfer(
+            &invoice.payer,
+            &invoice.freelancer,
+            &invoice.amount,
+        );
+        invoice.status = InvoiceStatus::Paid;
+        invoice.paid_at = Some(env.ledger().timestamp());
+        Self::save(&env, invoice_id, &invoice);
+        events::emit_invoice_settled(&env, invoice_id, &invoice.payer, invoice.amount);
+    }
+
+    /// Withdraws an unpaid invoice. Requires the freelancer's auth.
+    ///
+    /// # Panics
+    /// If the invoice was already paid or cancelled
+    pub fn cancel_invoice(env: Env, invoice_id: u64) {
+        let mut invoice = Self::load(&env, invoice_id);
+        invoice.freelancer.require_auth();
+        if invoice.status != InvoiceStatus::Pending {
+            panic_with_error!(env, InvoiceError::NotPending);
+        }
+        invoice.status = InvoiceStatus::Cancelled;
+        Self::save(&env, invoice_id, &invoice);
+        events::emit_invoice_cancelled(&env, invoice_id, &invoice.freelancer);
+    }
+
+    /// # Panics
+    /// If the invoice does not exist
+    pub fn get_invoice(env: Env, invoice_id: u64) -> InvoiceRecord {
+        Self::load(&env, invoice_id)
+    }
+
+    /// # Panics
+    /// If the invoice does not exist
+    pub fn invoice_status(env: Env, invoice_id: u64) -> InvoiceStatus {
+        Self::load(&env, invoice_id).status
+    }
+
+    /// Returns `true` if the invoice is still pending past its due date.
+    pub fn is_overdue(env: Env, invoice_id: u64) -> bool {
+        let invoice = Self::load(&env, invoice_id);
+        invoice.status == InvoiceStatus::Pending && env.ledger().timestamp() > invoice.due_date
+    }
+
+    /// Returns the ids of up to `limit` invoices `user` issued or was
+    /// billed, oldest first, skipping the first `start`.
+    ///
+    /// # Panics
+    /// If `limit` is above `MAX_PAGE_SIZE`
+    pub fn invoices_of(env: Env, user: Address, start: u32, limit: u32) -> Vec<u64> {
+        if limit > MAX_PAGE_SIZE {
+            panic_with_error!(env, CommonError::BatchTooLarge);
+        }
+        let end =
+            Self::invoice_count_of(env.clone(), user.clone()).min(start.saturating_add(limit));
+        let mut ids = Vec::new(&env);
+        let mut page = Vec::new(&env);
+        for i in start..end {
+            if i == start || i % INDEX_PAGE_SIZE == 0 {
+                page = Self::page(&env, &user, i / INDEX_PAGE_SIZE);
+            }
+            ids.push_back(page.get_unchecked(i % INDEX_PAGE_SIZE));
+        }
+        ids
+    }
+
+    /// Returns how many invoices `user` issued or was billed.
+    pub fn invoice_count_of(env: Env, user: Address) -> u32 {
+        ttl::get(&env, &DataKey::UserInvoiceCount(user)).unwrap_or(0)
+    }
+
+    fn page(env: &Env, user: &Address, page: u32) -> Vec<u64> {
+        ttl::get(env, &DataKey::UserInvoices(user.clone(), page)).unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Appends an invoice to the last page of `user`'s index, starting a new
+    /// page once it is full.
+    fn index(env: &Env, user: &Address, invoice_id: u64) {
+        let count = Self::invoice_count_of(env.clone(), user.clone());
+        let page = count / INDEX_PAGE_SIZE;
+        let mut ids = Self::page(env, user, page);
+        ids.push_back(invoice_id);
+        ttl::set(env, &DataKey::UserInvoices(user.clone(), page), &ids);
+        ttl::set(env, &DataKey::UserInvoiceCount(user.clone()), &(count + 1));
+    }
+
+    fn load(env: &Env, invoice_id: u64) -> InvoiceRecord {
+        ttl::get(env, &DataKey::Invoice(invoice_id))
+            .unwrap_or_else(|| panic_with_error!(env, InvoiceError::InvoiceNotFound))
+    }
+
+    fn save(env: &Env, invoice_id: u64, invoice: &InvoiceRecord) {
+        ttl::set(env, &DataKey::Invoice(invoice_id), invoice);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+    use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+    const DUE: u64 = 10_000;
+
+    struct Setup<'a> {
+        contract: InvoiceClient<'a>,
+        token: TokenClient<'a>,
+        freelancer: Address,
+        payer: Address,
+    }
+
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+        let contract = InvoiceClient::new(env, &env.register_contract(None, Invoice));
+        let admin = Address::generate(env);
+        contract.initialize(&admin);
+
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        let payer = Address::generate(env);
+        StellarAssetClient::new(env, &token).mint(&payer, &5_000);
+        Setup {
+            contract,
+            token: TokenClient::new(env, &token),
+            freelancer: Address::generate(env),
+            payer,
+        }
+    }
+
+    fn create(env: &Env, s: &Setup, amount: i128) -> u64 {
+        s.contract.create_invoice(
+            &s.freelancer,
+            &s.payer,
+            &s.token.address,
+            &amount,
+            &BytesN::from_array(env, &[7; 32]),
+            &DUE,
+        )
+    }
+
+    #[test]
+    fn test_pay_invoice() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&env, &s, 1_200);
+
+        env.ledger().with_mut(|l| l.timestamp = DUE + 1);
+        assert!(s.contract.is_overdue(&id));
+        s.contract.pay_invoice(&id);
+
+        assert_eq!(s.token.balance(&s.freelancer), 1_200);
+        let invoice = s.contract.get_invoice(&id);
+        assert_eq!(invoice.status, InvoiceStatus::Paid);
+        assert_eq!(invoice.paid_at, Some(DUE + 1));
+        assert!(!s.contract.is_overdue(&id));
+        assert_eq!(
+            s.contract.try_pay_invoice(&id),
+            Err(Ok(InvoiceError::NotPending.into()))
+        );
+    }
+
+    #[test]
+    fn test_lists_invoices_for_both_parties() {
+        let env = Env::default();
+        let s = setup(&env);
+        let first = create(&env, &s, 100);
+        let second = create(&env, &s, 200);
+        s.contract.cancel_invoice(&second);
+
+        let ids = Vec::from_array(&env, [first, second]);
+        assert_eq!(s.contract.invoices_of(&s.freelancer, &0, &10), ids);
+        assert_eq!(s.contract.invoices_of(&s.payer, &0, &10), ids);
+        let stranger = Address::generate(&env);
+        assert!(s.contract.invoices_of(&stranger, &0, &10).is_empty());
+        assert_eq!(
+            s.contract.try_pay_invoice(&second),
+            Err(Ok(InvoiceError::NotPending.into()))
+        );
+
+        // The index is paged in storage; reads run across page boundaries.
+        let mut last = second;
+        for _ in 0..INDEX_PAGE_SIZE {
+            last = create(&env, &s, 1);
+        }
+        let count = s.contract.invoice_count_of(&s.payer);
+        assert_eq!(count, INDEX_PAGE_SIZE + 2);
+        let tail = s
+            .contract
+            .invoices_of(&s.payer, &(INDEX_PAGE_SIZE - 1), &10);
+        assert_eq!(tail.len(), 3);
+        assert_eq!(tail.get(2), Some(last));
+        assert_eq!(
+            s.contract
+                .try_invoices_of(&s.payer, &0, &(MAX_PAGE_SIZE + 1)),
+            Err(Ok(CommonError::BatchTooLarge.into()))
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_invoices() {
+        let env = Env::default();
+        let s = setup(&env);
+        let memo = BytesN::from_array(&env, &[0; 32]);
+        assert_eq!(
+            s.contract.try_create_invoice(
+                &s.freelancer,
+                &s.payer,
+                &s.token.address,
+                &0,
+                &memo,
+                &DUE
+            ),
+            Err(Ok(InvoiceError::InvalidAmount.into()))
+        );
+        assert_eq!(
+            s.contract.try_create_invoice(
+                &s.freelancer,
+                &s.freelancer,
+                &s.token.address,
+                &100,
+                &memo,
+                &DUE
+            ),
+            Err(Ok(InvoiceError::SelfInvoice.into()))
+        );
+
+        env.ledger().with_mut(|l| l.timestamp = DUE + 1);
+        assert_eq!(
+            s.contract.try_create_invoice(
+                &s.freelancer,
+                &s.payer,
+                &s.token.address,
+                &100,
+                &memo,
+                &DUE
+            ),
+            Err(Ok(InvoiceError::DueDateInPast.into()))
+        );
+        assert_eq!(
+            s.contract.try_get_invoice(&99),
+            Err(Ok(InvoiceError::InvoiceNotFound.into()))
+        );
+    }
+}
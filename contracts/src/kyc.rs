@@ -0,0 +1,177 @@
+//! KYC allowlist.
+//!
+//! Attestors the admin has registered mark addresses as identity-verified
+//! after an off-chain check. Contracts that handle high-value flows, such as
+//! the escrow above its configured threshold, refuse parties that are not on
+//! the allowlist; everything below the threshold stays permissionless.
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, Address, Env,
+};
+
+use crate::admin;
+use crate::{events, ttl};
+
+/// Errors raised by the KYC registry.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum KycError {
+    /// The caller is not a registered attestor
+    NotAttestor = 100,
+}
+
+/// Who verified an address and when.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Attestation {
+    pub attestor: Address,
+    pub attested_ledger: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Account allowed to verify addresses
+    Attestor(Address),
+    Attestation(Address),
+}
+
+/// Returns `true` if `subject` is verified with `registry`.
+pub fn is_verified(env: &Env, registry: &Address, subject: &Address) -> bool {
+    KycRegistryClient::new(env, registry).is_verified(subject)
+}
+
+#[contract]
+pub struct KycRegistry;
+
+#[contractimpl]
+impl KycRegistry {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Allows `attestor` to verify addresses. Admin only.
+    pub fn add_attestor(env: Env, admin: Address, attestor: Address) {
+        admin::require_admin_caller(&env, &admin);
+        ttl::set(&env, &DataKey::Attestor(attestor), &true);
+    }
+
+    /// Stops `attestor` from verifying addresses. Addresses it already
+    /// verified stay verified. Admin only.
+    pub fn remove_attestor(env: Env, admin: Address, attestor: Address) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Attestor(attestor));
+    }
+
+    /// Returns `true` if `attestor` may verify addresses.
+    pub fn is_attestor(env: Env, attestor: Address) -> bool {
+        ttl::get(&env, &DataKey::Attestor(attestor)).unwrap_or(false)
+    }
+
+    /// Adds `subject` to the allowlist.
+    ///
+    /// # Panics
+    /// With `NotAttestor` if `attestor` is not registered
+    pub fn attest(env: Env, attestor: Address, subject: Address) {
+        Self::require_attestor(&env, &attestor);
+        ttl::set(
+            &env,
+            &DataKey::Attestation(subject.clone()),
+            &Attestation {
+                attestor: attestor.clone(),
+                attested_ledger: env.ledger().sequence(),
+            },
+        );
+        events::emit_kyc_attested(&env, &subject, &attestor);
+    }
+
+    /// Removes `subject` from the allowlist.
+    ///
+    /// # Panics
+    /// With `NotAttestor` if `attestor` is not registered
+    pub fn revoke(env: Env, attestor: Address, subject: Address) {
+        Self::require_attestor(&env, &attestor);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Attestation(subject.clone()));
+        events::emit_kyc_revoked(&env, &subject, &attestor);
+    }
+
+    /// Returns `true` if `subject` is on the allowlist.
+    pub fn is_verified(env: Env, subject: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Attestation(subject))
+    }
+
+    /// Returns who verified `subject` and when, if anyone did.
+    pub fn get_attestation(env: Env, subject: Address) -> Option<Attestation> {
+        ttl::get(&env, &DataKey::Attestation(subject))
+    }
+}
+
+impl KycRegistry {
+    fn require_attestor(env: &Env, attestor: &Address) {
+        attestor.require_auth();
+        if !Self::is_attestor(env.clone(), attestor.clone()) {
+            panic_with_error!(env, KycError::NotAttestor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_only_attestors_verify() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let registry = KycRegistryClient::new(&env, &env.register_contract(None, KycRegistry));
+        let admin = Address::generate(&env);
+        registry.initialize(&admin);
+        let attestor = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        assert_eq!(
+            registry.try_attest(&attestor, &user),
+            Err(Ok(KycError::NotAttestor.into()))
+        );
+        registry.add_attestor(&admin, &attestor);
+        registry.attest(&attestor, &user);
+        assert!(registry.is_verified(&user));
+        assert_eq!(
+            registry.get_attestation(&user),
+            Some(Attestation {
+                attestor: attestor.clone(),
+                attested_ledger: env.ledger().sequence(),
+            })
+        );
+
+        registry.remove_attestor(&admin, &attestor);
+        assert!(registry.is_verified(&user));
+        assert!(registry.try_revoke(&attestor, &user).is_err());
+        registry.add_attestor(&admin, &attestor);
+        registry.revoke(&attestor, &user);
+        assert!(!registry.is_verified(&user));
+    }
+}
@@ -1,17 +1,37 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, Env,
+};
 
+pub mod admin;
+pub mod assets;
 pub mod auth;
+pub mod badge;
+pub mod batch_payout;
+pub mod claimable;
 pub mod dispute_resolution;
+pub mod errors;
+pub mod escrow;
+pub mod escrow_factory;
+pub mod events;
 pub mod gasless;
+pub mod invoice;
+pub mod kyc;
 pub mod monitor;
 pub mod multisig_governance;
 pub mod path_payment;
+pub mod pausable;
+pub mod rbac;
 pub mod rebalancer;
+pub mod recurring;
+pub mod reputation;
+pub mod stream;
+pub mod treasury;
 pub mod trustline;
+pub mod ttl;
 pub mod upgrade_utils;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum Status {
     Pending = 0,
@@ -19,6 +39,19 @@ pub enum Status {
     Completed = 2,
 }
 
+/// Errors raised by the single-milestone escrow.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MilestoneEscrowError {
+    /// Only the client may fund the escrow
+    NotClient = 100,
+    /// Only the client or arbiter may release funds
+    NotAuthorized = 101,
+    /// The funds were already released
+    AlreadyReleased = 102,
+}
+
 #[contracttype]
 pub enum DataKey {
     Client,
@@ -52,7 +85,7 @@ impl MilestoneEscrow {
 
         let client: Address = env.storage().instance().get(&DataKey::Client).unwrap();
         if from != client {
-            panic!("Only client can fund");
+            panic_with_error!(env, MilestoneEscrowError::NotClient);
         }
 
         let token_address: Address = env
@@ -77,12 +110,12 @@ impl MilestoneEscrow {
         let arbiter: Address = env.storage().instance().get(&DataKey::Arbiter).unwrap();
 
         if caller != client && caller != arbiter {
-            panic!("Only client or arbiter can release funds");
+            panic_with_error!(env, MilestoneEscrowError::NotAuthorized);
         }
 
         let status: Status = env.storage().instance().get(&DataKey::Status).unwrap();
         if matches!(status, Status::Completed) {
-            panic!("Funds already released");
+            panic_with_error!(env, MilestoneEscrowError::AlreadyReleased);
         }
 
         let freelancer: Address = env.storage().instance().get(&DataKey::Freelancer).unwrap();
@@ -134,4 +167,38 @@ mod test {
 
         assert_eq!(client.status(), Status::Pending);
     }
+
+    #[test]
+    fn test_rejects_unauthorized_and_repeated_calls() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, MilestoneEscrow);
+        let client = MilestoneEscrowClient::new(&env, &contract_id);
+        let token_addr = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+
+        let client_addr = Address::generate(&env);
+        let freelancer_addr = Address::generate(&env);
+        let arbiter_addr = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        soroban_sdk::token::StellarAssetClient::new(&env, &token_addr).mint(&client_addr, &500);
+        client.init(&client_addr, &freelancer_addr, &arbiter_addr, &token_addr);
+
+        assert_eq!(
+            client.try_fund_milestone(&stranger, &500),
+            Err(Ok(MilestoneEscrowError::NotClient.into()))
+        );
+        client.fund_milestone(&client_addr, &500);
+        assert_eq!(
+            client.try_release_funds(&stranger),
+            Err(Ok(MilestoneEscrowError::NotAuthorized.into()))
+        );
+        client.release_funds(&arbiter_addr);
+        assert_eq!(client.status(), Status::Completed);
+        assert_eq!(
+            client.try_release_funds(&client_addr),
+            Err(Ok(MilestoneEscrowError::AlreadyReleased.into()))
+        );
+    }
 }
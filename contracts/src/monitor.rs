@@ -1,5 +1,7 @@
 use soroban_sdk::{contract, contractimpl, contracttype, Env, String};
 
+use crate::events;
+
 #[contracttype]
 #[derive(Copy, Clone)]
 pub enum TxStatus {
@@ -26,10 +28,7 @@ impl TransactionMonitor {
 
         // 2. "Process" the transaction (Simulate DB update)
         // In a contract usage, we might emit an event or update state.
-        env.events().publish(
-            (String::from_str(&env, "invoice_paid"), invoice_memo),
-            tx_hash,
-        );
+        events::emit_invoice_paid(&env, &invoice_memo, &tx_hash);
 
         // 3. Mark as processed
         // env.storage().instance().set(&tx_hash, &TxStatus::Processed);
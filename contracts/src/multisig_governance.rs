@@ -1,97 +1,865 @@
-use soroban_sdk::{contract, contractimpl, Address, Env};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, xdr::ToXdr, Address,
+    BytesN, Env, Map, Symbol, Val, Vec,
+};
 
-/// MultisigGovernance contract for managing 2-of-3 multi-signature authorization.
+use crate::admin::{self, KeyRole, Roles};
+use crate::{events, pausable, ttl};
+
+/// Ledgers a proposal stays open after its timelock when no lifetime is
+/// configured (about 30 days).
+pub const DEFAULT_PROPOSAL_LIFETIME: u32 = 30 * 17_280;
+
+/// Approval rules for one kind of proposal (e.g. "payment", "upgrade").
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ProposalCategory {
+    /// Approvals required, including the proposer's
+    pub quorum: u32,
+    /// Ledgers that must pass after proposal, or after approval in sensitive
+    /// categories, before it can execute
+    pub timelock: u32,
+    /// Contracts proposals in this category may target; empty allows any
+    pub allowed_targets: Vec<Address>,
+    /// Whether approved proposals are queued: the timelock then runs from
+    /// the ledger the quorum is reached, and the veto holder may block the
+    /// proposal until it ends. Meant for fee rates, arbiter lists and
+    /// sponsorship limits.
+    pub sensitive: bool,
+}
+
+/// What a proposal does once executed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ProposalAction {
+    /// Approves the proposal's `target`/`amount` transaction
+    Transaction,
+    /// Adds a signer with the given weight
+    AddSigner(Address, u32),
+    RemoveSigner(Address),
+    /// Changes an existing signer's weight
+    SetSignerWeight(Address, u32),
+    SetThreshold(u32),
+    /// Calls a function on the proposal's target with the given arguments
+    Invoke(Symbol, Vec<Val>),
+}
+
+/// A sensitive transaction awaiting co-signatures.
+///
+/// The signer set, weights and threshold are snapshotted when the proposal is
+/// created so later reconfiguration cannot change who may approve it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Proposal {
+    pub proposer: Address,
+    pub category: Symbol,
+    pub target: Address,
+    pub amount: i128,
+    pub action: ProposalAction,
+    /// Signers eligible to approve, as configured at creation
+    pub signers: Vec<Address>,
+    /// Weight of each snapshot signer
+    pub weights: Map<Address, u32>,
+    /// Approval weight required, as configured at creation
+    pub threshold: u32,
+    /// SHA-256 of the XDR-encoded `(signers, weights, threshold)` snapshot
+    pub snapshot_hash: BytesN<32>,
+    pub approvals: Vec<Address>,
+    /// First ledger on which the proposal may execute (its category timelock)
+    pub executable_ledger: u32,
+    /// Timelock a sensitive proposal waits out once queued; `None` for
+    /// proposals in ordinary categories
+    pub queue_timelock: Option<u32>,
+    /// Ledger a sensitive proposal reached its quorum and was queued on
+    pub queued_ledger: Option<u32>,
+    /// Last ledger on which the proposal may be approved or executed
+    pub expiry_ledger: u32,
+    pub executed: bool,
+    /// Snapshot signers, other than the proposer, who voted to cancel
+    pub cancel_votes: Vec<Address>,
+    pub cancelled: bool,
+}
+
+/// Errors raised by multisig governance.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MultisigError {
+    /// The multisig is not configured
+    NotConfigured = 100,
+    /// The caller is not a signer
+    NotSigner = 101,
+    /// The address is already a signer
+    AlreadySigner = 102,
+    /// A signer is listed twice
+    DuplicateSigner = 103,
+    /// A signer weight is zero
+    ZeroSignerWeight = 104,
+    /// The threshold is zero or above the total signer weight
+    InvalidThreshold = 105,
+    /// A category quorum is zero
+    ZeroQuorum = 106,
+    /// The quorum is above the total signer weight
+    QuorumTooHigh = 107,
+    /// The proposal category is not configured
+    UnknownCategory = 108,
+    /// The target is not allowed for the category
+    TargetNotAllowed = 109,
+    /// The proposal lifetime is zero
+    ZeroLifetime = 110,
+    /// No proposal with this id
+    ProposalNotFound = 111,
+    /// The proposal is executed, cancelled or expired
+    ProposalClosed = 112,
+    /// The proposal was already executed
+    AlreadyExecuted = 113,
+    /// The proposal was cancelled
+    ProposalCancelled = 114,
+    /// The proposal has expired
+    ProposalExpired = 115,
+    /// The proposal has not expired yet
+    NotExpired = 116,
+    /// The proposal is still timelocked
+    Timelocked = 117,
+    /// The signer already approved the proposal
+    AlreadyApproved = 118,
+    /// The signer already voted to cancel
+    AlreadyVotedToCancel = 119,
+    /// The proposal lacks the approvals to execute
+    QuorumNotReached = 120,
+    /// The caller is not the veto holder
+    NotVetoer = 121,
+    /// The proposal is not queued behind a sensitive-category timelock
+    NotQueued = 122,
+    /// The proposal's timelock has ended, so it can no longer be vetoed
+    VetoWindowClosed = 123,
+    /// The signer set was already configured
+    AlreadyConfigured = 124,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Current signer set, in configuration order
+    Signers,
+    /// Current weight of each signer
+    Weights,
+    /// Approval weight required by newly created proposals
+    Threshold,
+    ProposalLifetime,
+    ProposalCount,
+    /// Ids of proposals not yet executed, cancelled or expired, oldest first
+    Pending,
+    Proposal(u64),
+    Category(Symbol),
+}
+
+/// MultisigGovernance contract for managing N-of-M multi-signature authorization.
 ///
-/// Implements multi-signature governance requiring at least 2 of 3 authorized signers
-/// for sensitive transactions. Uses Stellar's SetOptions operation semantics.
+/// Sensitive transactions are stored as proposals and execute once signers
+/// holding enough weight approve them. The signer set itself is changed
+/// through proposals.
 #[contract]
 pub struct MultisigGovernance;
 
 #[contractimpl]
 impl MultisigGovernance {
-    /// Configures multi-signature requirements for the contract.
+    /// Initializes the contract with its admin.
+    ///
+    /// # Arguments
+    /// * `env` - The Soroban environment
+    /// * `admin` - Address allowed to manage the contract's configuration
+    ///
+    /// # Panics
+    /// If the contract has already been initialized
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin.
+    ///
+    /// Ownership only moves once `new_admin` calls `accept_admin`.
+    ///
+    /// # Panics
+    /// If the current admin has not authorized the call
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer.
+    ///
+    /// # Panics
+    /// If `new_admin` is not the proposed successor
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Assigns a key role (veto, recovery, fee collector, treasury).
+    ///
+    /// # Panics
+    /// If the current admin has not authorized the call
+    pub fn set_role(env: Env, role: KeyRole, address: Address) {
+        admin::set_role(&env, role, &address);
+    }
+
+    /// Returns the admin and every key role in a single read.
+    pub fn get_roles(env: Env) -> Roles {
+        admin::get_roles(&env)
+    }
+
+    /// Extends the TTL of each persistent entry in `keys` that is running
+    /// low, so idle proposals are not archived. Meant for keepers; anyone may
+    /// call it. Returns how many of the entries exist.
+    pub fn bump_entries(env: Env, keys: Vec<DataKey>) -> u32 {
+        ttl::bump_all(&env, &keys)
+    }
+
+    /// Pauses every state-changing entrypoint.
     ///
-    /// Sets up a 2-of-3 multi-sig scheme with configurable thresholds:
-    /// - Master Weight: 1
-    /// - Signer Weights: 1 each
-    /// - Low Threshold: 0 (no signature needed)
-    /// - Medium Threshold: 2 (requires 2 signatures)
-    /// - High Threshold: 2 (requires 2 signatures)
+    /// # Panics
+    /// If `caller` is neither the contract admin nor a guardian, or has not
+    /// authorized the call
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause.
+    ///
+    /// # Panics
+    /// If `caller` is neither the contract admin nor a guardian, or has not
+    /// authorized the call
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Bootstraps the contract's N-of-M signer set. Can only be called once:
+    /// later changes go through `propose_action`. Every signer starts with
+    /// weight 1. Proposals need `threshold` approval weight, or their
+    /// category quorum if that is higher.
     ///
     /// # Arguments
     /// * `env` - The Soroban environment
-    /// * `contract_owner` - Address of the contract owner/initial signer
-    /// * `additional_signer_1` - Address of the first additional signer
-    /// * `additional_signer_2` - Address of the second additional signer
+    /// * `admin` - The contract admin
+    /// * `signers` - Addresses allowed to propose and approve
+    /// * `threshold` - Approval weight required, including the proposer's
     ///
     /// # Panics
-    /// If contract_owner is not authorized
-    pub fn configure_multisig(
-        env: Env,
-        contract_owner: Address,
-        additional_signer_1: Address,
-        additional_signer_2: Address,
-    ) {
-        contract_owner.require_auth();
-
-        // Note: In production, this would execute Stellar SetOptions operations:
-        // 1. Set Master Weight = 1
-        // 2. Add signer_1 with weight 1
-        // 3. Add signer_2 with weight 1
-        // 4. Set Low Threshold = 0
-        // 5. Set Medium Threshold = 2
-        // 6. Set High Threshold = 2
-        //
-        // This creates a 2-of-3 signature scheme for most operations.
-
-        // Publish configuration event (for indexing/monitoring)
+    /// If `admin` is not the contract admin, the signers are already
+    /// configured, `signers` contains duplicates, or `threshold` is zero or
+    /// exceeds the number of signers
+    pub fn configure_multisig(env: Env, admin: Address, signers: Vec<Address>, threshold: u32) {
+        admin::require_admin_caller(&env, &admin);
+        if env.storage().instance().has(&DataKey::Signers) {
+            panic_with_error!(env, MultisigError::AlreadyConfigured);
+        }
+        let mut weights = Map::new(&env);
+        for signer in signers.iter() {
+            if weights.contains_key(signer.clone()) {
+                panic_with_error!(env, MultisigError::DuplicateSigner);
+            }
+            weights.set(signer, 1);
+        }
+        Self::store_signers(&env, &signers, &weights, threshold);
+    }
+
+    /// Returns the configured signers, in configuration order.
+    pub fn signers(env: Env) -> Vec<Address> {
         env.storage()
             .instance()
-            .set::<Address, bool>(&contract_owner, &true);
+            .get(&DataKey::Signers)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
 
-        // Record additional signers
+    /// Returns a signer's current weight (zero for non-signers).
+    pub fn signer_weight(env: Env, signer: Address) -> u32 {
+        Self::weights(&env).get(signer).unwrap_or(0)
+    }
+
+    /// Returns the configured approval threshold (zero if unconfigured).
+    pub fn threshold(env: Env) -> u32 {
         env.storage()
             .instance()
-            .set::<Address, bool>(&additional_signer_1, &true);
+            .get(&DataKey::Threshold)
+            .unwrap_or(0)
+    }
 
+    /// Defines or replaces a proposal category.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin, or `quorum` is zero or above
+    /// the total signer weight
+    pub fn set_category(env: Env, admin: Address, name: Symbol, category: ProposalCategory) {
+        admin::require_admin_caller(&env, &admin);
+        if category.quorum == 0 {
+            panic_with_error!(env, MultisigError::ZeroQuorum);
+        }
+        if category.quorum > Self::total_weight(&Self::weights(&env)) {
+            panic_with_error!(env, MultisigError::QuorumTooHigh);
+        }
         env.storage()
             .instance()
-            .set::<Address, bool>(&additional_signer_2, &true);
+            .set(&DataKey::Category(name), &category);
+    }
+
+    /// Returns a proposal category, if defined.
+    pub fn get_category(env: Env, name: Symbol) -> Option<ProposalCategory> {
+        env.storage().instance().get(&DataKey::Category(name))
     }
 
     /// Proposes a sensitive transaction requiring multi-signature approval.
     ///
-    /// Initiates a proposal workflow where one signer proposes a transaction
-    /// that must be co-signed by at least one other authorized signer.
-    /// Returns `false` as transaction execution is pending co-signer approval.
+    /// The category selects the timelock and allowed targets. The current
+    /// signers and the higher of the signer threshold and category quorum
+    /// are snapshotted so later reconfiguration cannot change who may
+    /// approve. The proposer's own signature counts as the first approval.
     ///
     /// # Arguments
     /// * `env` - The Soroban environment
     /// * `proposer` - Address of the signer proposing the transaction
+    /// * `category` - Name of the proposal category
+    /// * `target` - Contract the transaction acts on
     /// * `amount` - Transaction amount in stroops
     ///
     /// # Returns
-    /// `false` - Transaction is pending co-signer signatures
+    /// The new proposal's id
     ///
     /// # Panics
-    /// If proposer is not authorized
-    pub fn propose_sensitive_tx(env: Env, proposer: Address, amount: i128) -> bool {
+    /// If multisig is not configured, the proposer is not a signer, the
+    /// category is unknown or does not allow `target`, or its quorum exceeds
+    /// the number of signers
+    pub fn propose_sensitive_tx(
+        env: Env,
+        proposer: Address,
+        category: Symbol,
+        target: Address,
+        amount: i128,
+    ) -> u64 {
+        Self::create_proposal(
+            &env,
+            proposer,
+            category,
+            target,
+            amount,
+            ProposalAction::Transaction,
+        )
+    }
+
+    /// Proposes a change to the multisig itself, such as rotating a signer
+    /// or changing the threshold. The proposal targets this contract and is
+    /// approved like any other; the change applies when it executes, and
+    /// execution fails if the resulting threshold could not be met by the
+    /// remaining signer weights.
+    ///
+    /// # Returns
+    /// The new proposal's id
+    ///
+    /// # Panics
+    /// As `propose_sensitive_tx`
+    pub fn propose_action(
+        env: Env,
+        proposer: Address,
+        category: Symbol,
+        action: ProposalAction,
+    ) -> u64 {
+        let target = env.current_contract_address();
+        Self::create_proposal(&env, proposer, category, target, 0, action)
+    }
+
+    /// Proposes a call to `function` on `target`, made by this contract once
+    /// the proposal executes. Contracts that make the multisig their admin
+    /// can be administered this way, e.g. to upgrade them or change their
+    /// configuration.
+    ///
+    /// # Returns
+    /// The new proposal's id
+    ///
+    /// # Panics
+    /// As `propose_sensitive_tx`
+    pub fn propose_call(
+        env: Env,
+        proposer: Address,
+        category: Symbol,
+        target: Address,
+        function: Symbol,
+        args: Vec<Val>,
+    ) -> u64 {
+        Self::create_proposal(
+            &env,
+            proposer,
+            category,
+            target,
+            0,
+            ProposalAction::Invoke(function, args),
+        )
+    }
+
+    /// Adds a co-signer's approval, executing the proposal once its
+    /// snapshot threshold is met and its timelock has passed.
+    ///
+    /// # Returns
+    /// `true` if this approval executed the proposal
+    ///
+    /// # Panics
+    /// If the proposal does not exist or is no longer open, or `signer` is
+    /// not in its snapshot or has already approved
+    pub fn approve_proposal(env: Env, proposal_id: u64, signer: Address) -> bool {
+        pausable::require_not_paused(&env);
+        signer.require_auth();
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id);
+        Self::require_open(&env, &proposal);
+        if !proposal.signers.contains(&signer) {
+            panic_with_error!(env, MultisigError::NotSigner);
+        }
+        if proposal.approvals.contains(&signer) {
+            panic_with_error!(env, MultisigError::AlreadyApproved);
+        }
+        proposal.approvals.push_back(signer);
+        Self::try_execute(&env, proposal_id, &mut proposal)
+    }
+
+    /// Executes an approved proposal whose timelock has since passed.
+    /// Anyone may call this.
+    ///
+    /// # Panics
+    /// If the proposal does not exist, is no longer open, lacks its quorum or
+    /// is still timelocked
+    pub fn execute_proposal(env: Env, proposal_id: u64) {
+        pausable::require_not_paused(&env);
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id);
+        Self::require_open(&env, &proposal);
+        if Self::voted_weight(&proposal, &proposal.approvals) < proposal.threshold {
+            panic_with_error!(env, MultisigError::QuorumNotReached);
+        }
+        if !Self::try_execute(&env, proposal_id, &mut proposal) {
+            panic_with_error!(env, MultisigError::Timelocked);
+        }
+    }
+
+    /// Cancels an open proposal. The proposer cancels it outright; any other
+    /// snapshot signer casts a cancel vote, and the proposal is cancelled
+    /// once signers holding its threshold weight have voted.
+    ///
+    /// # Returns
+    /// `true` if the proposal is now cancelled
+    ///
+    /// # Panics
+    /// If the proposal does not exist or is no longer open, or `signer` is
+    /// not in its snapshot or has already voted to cancel
+    pub fn cancel_proposal(env: Env, proposal_id: u64, signer: Address) -> bool {
+        pausable::require_not_paused(&env);
+        signer.require_auth();
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id);
+        Self::require_open(&env, &proposal);
+        if signer != proposal.proposer {
+            if !proposal.signers.contains(&signer) {
+                panic_with_error!(env, MultisigError::NotSigner);
+            }
+            if proposal.cancel_votes.contains(&signer) {
+                panic_with_error!(env, MultisigError::AlreadyVotedToCancel);
+            }
+            proposal.cancel_votes.push_back(signer.clone());
+        }
+        proposal.cancelled = signer == proposal.proposer
+            || Self::voted_weight(&proposal, &proposal.cancel_votes) >= proposal.threshold;
+        ttl::set(&env, &DataKey::Proposal(proposal_id), &proposal);
+
+        if proposal.cancelled {
+            Self::remove_pending(&env, proposal_id);
+            events::emit_proposal_cancelled(&env, proposal_id, &signer);
+        }
+        proposal.cancelled
+    }
+
+    /// Blocks a queued sensitive proposal before its timelock ends. Only the
+    /// `Veto` key role holder may call this.
+    ///
+    /// # Panics
+    /// If `vetoer` is not the veto holder, or the proposal is not open,
+    /// not queued or already executable
+    pub fn veto_proposal(env: Env, proposal_id: u64, vetoer: Address) {
+        vetoer.require_auth();
+        if admin::get_role(&env, KeyRole::Veto) != Some(vetoer.clone()) {
+            panic_with_error!(env, MultisigError::NotVetoer);
+        }
+
+        let mut proposal = Self::get_proposal(env.clone(), proposal_id);
+        Self::require_open(&env, &proposal);
+        if proposal.queued_ledger.is_none() {
+            panic_with_error!(env, MultisigError::NotQueued);
+        }
+        if env.ledger().sequence() >= proposal.executable_ledger {
+            panic_with_error!(env, MultisigError::VetoWindowClosed);
+        }
+        proposal.cancelled = true;
+        ttl::set(&env, &DataKey::Proposal(proposal_id), &proposal);
+        Self::remove_pending(&env, proposal_id);
+        events::emit_proposal_vetoed(&env, proposal_id, &vetoer);
+    }
+
+    /// Drops an expired proposal from the pending list. Anyone may call this.
+    ///
+    /// # Panics
+    /// If the proposal does not exist, was executed or cancelled, has not
+    /// expired, or was already dropped
+    pub fn expire_proposal(env: Env, proposal_id: u64) {
+        let proposal = Self::get_proposal(env.clone(), proposal_id);
+        if proposal.executed || proposal.cancelled {
+            panic_with_error!(env, MultisigError::ProposalClosed);
+        }
+        if env.ledger().sequence() <= proposal.expiry_ledger {
+            panic_with_error!(env, MultisigError::NotExpired);
+        }
+        if !Self::remove_pending(&env, proposal_id) {
+            panic_with_error!(env, MultisigError::ProposalExpired);
+        }
+        events::emit_proposal_expired(&env, proposal_id, proposal.expiry_ledger);
+    }
+
+    /// Sets how many ledgers proposals stay open after their timelock.
+    /// Applies to proposals created afterwards.
+    ///
+    /// # Panics
+    /// If `admin` is not the contract admin or `ledgers` is zero
+    pub fn set_proposal_lifetime(env: Env, admin: Address, ledgers: u32) {
+        admin::require_admin_caller(&env, &admin);
+        if ledgers == 0 {
+            panic_with_error!(env, MultisigError::ZeroLifetime);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposalLifetime, &ledgers);
+    }
+
+    /// Returns the proposal lifetime (`DEFAULT_PROPOSAL_LIFETIME` by default).
+    pub fn proposal_lifetime(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ProposalLifetime)
+            .unwrap_or(DEFAULT_PROPOSAL_LIFETIME)
+    }
+
+    /// Returns a stored proposal.
+    ///
+    /// # Panics
+    /// If the proposal does not exist
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Proposal {
+        ttl::get(&env, &DataKey::Proposal(proposal_id))
+            .unwrap_or_else(|| panic_with_error!(env, MultisigError::ProposalNotFound))
+    }
+
+    /// Returns the ids of proposals awaiting approval or execution, oldest
+    /// first. Expired proposals are left out.
+    pub fn pending_proposals(env: Env) -> Vec<u64> {
+        let mut open = Vec::new(&env);
+        for id in Self::pending_ids(&env).iter() {
+            if env.ledger().sequence() <= Self::get_proposal(env.clone(), id).expiry_ledger {
+                open.push_back(id);
+            }
+        }
+        open
+    }
+
+    /// Checks that a proposal's signer snapshot is intact.
+    ///
+    /// Re-derives the snapshot hash from the stored signers, weights and
+    /// threshold and confirms the snapshot is internally consistent: a
+    /// non-zero threshold, no duplicate signers, a weight for every signer
+    /// and enough total weight to reach the threshold, and approvals only
+    /// from snapshot signers.
+    ///
+    /// # Returns
+    /// `true` if the snapshot verifies, `false` if it does not or the
+    /// proposal does not exist
+    pub fn verify_proposal_snapshot(env: Env, proposal_id: u64) -> bool {
+        let proposal = match ttl::get::<DataKey, Proposal>(&env, &DataKey::Proposal(proposal_id)) {
+            Some(proposal) => proposal,
+            None => return false,
+        };
+
+        if Self::snapshot_hash(
+            &env,
+            &proposal.signers,
+            &proposal.weights,
+            proposal.threshold,
+        ) != proposal.snapshot_hash
+        {
+            return false;
+        }
+        if proposal.threshold == 0
+            || proposal.weights.len() != proposal.signers.len()
+            || Self::total_weight(&proposal.weights) < proposal.threshold
+        {
+            return false;
+        }
+        for (i, signer) in proposal.signers.iter().enumerate() {
+            if proposal.signers.first_index_of(&signer) != Some(i as u32)
+                || !proposal.weights.contains_key(signer)
+            {
+                return false;
+            }
+        }
+        proposal
+            .approvals
+            .iter()
+            .all(|approver| proposal.signers.contains(&approver))
+    }
+
+    fn create_proposal(
+        env: &Env,
+        proposer: Address,
+        category: Symbol,
+        target: Address,
+        amount: i128,
+        action: ProposalAction,
+    ) -> u64 {
+        pausable::require_not_paused(env);
         proposer.require_auth();
 
-        // Emit proposal event with transaction details for co-signers
-        env.events().publish(
-            (soroban_sdk::Symbol::new(&env, "tx_proposed"), proposer),
+        let rules = Self::get_category(env.clone(), category.clone())
+            .unwrap_or_else(|| panic_with_error!(env, MultisigError::UnknownCategory));
+        if !rules.allowed_targets.is_empty() && !rules.allowed_targets.contains(&target) {
+            panic_with_error!(env, MultisigError::TargetNotAllowed);
+        }
+
+        let signers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Signers)
+            .unwrap_or_else(|| panic_with_error!(env, MultisigError::NotConfigured));
+        if !signers.contains(&proposer) {
+            panic_with_error!(env, MultisigError::NotSigner);
+        }
+        let weights = Self::weights(env);
+        let threshold = rules.quorum.max(Self::threshold(env.clone()));
+        if threshold > Self::total_weight(&weights) {
+            panic_with_error!(env, MultisigError::QuorumTooHigh);
+        }
+
+        let id = env
+            .storage()
+            .instance()
+            .get::<DataKey, u64>(&DataKey::ProposalCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage().instance().set(&DataKey::ProposalCount, &id);
+
+        let mut approvals = Vec::new(env);
+        approvals.push_back(proposer.clone());
+        let mut proposal = Proposal {
+            snapshot_hash: Self::snapshot_hash(env, &signers, &weights, threshold),
+            proposer: proposer.clone(),
+            category,
+            target,
             amount,
-        );
+            action,
+            signers,
+            weights,
+            threshold,
+            approvals,
+            executable_ledger: env.ledger().sequence() + rules.timelock,
+            expiry_ledger: env.ledger().sequence()
+                + rules.timelock
+                + Self::proposal_lifetime(env.clone()),
+            queue_timelock: rules.sensitive.then_some(rules.timelock),
+            queued_ledger: None,
+            executed: false,
+            cancel_votes: Vec::new(env),
+            cancelled: false,
+        };
+        // A proposer holding the whole quorum queues a sensitive proposal
+        // straight away.
+        Self::queue_if_approved(env, id, &mut proposal);
+        ttl::set(env, &DataKey::Proposal(id), &proposal);
+        let mut pending = Self::pending_ids(env);
+        pending.push_back(id);
+        ttl::set(env, &DataKey::Pending, &pending);
 
-        false // Execution pending additional signature
+        events::emit_tx_proposed(env, &proposer, amount);
+        id
+    }
+
+    /// Marks the proposal executed if it has its quorum and the timelock has
+    /// passed, applying its action, then stores it. Returns whether it
+    /// executed.
+    fn try_execute(env: &Env, proposal_id: u64, proposal: &mut Proposal) -> bool {
+        Self::queue_if_approved(env, proposal_id, proposal);
+        proposal.executed = Self::voted_weight(proposal, &proposal.approvals) >= proposal.threshold
+            && env.ledger().sequence() >= proposal.executable_ledger;
+        ttl::set(env, &DataKey::Proposal(proposal_id), proposal);
+
+        if proposal.executed {
+            Self::apply_action(env, proposal);
+            Self::remove_pending(env, proposal_id);
+            let last_signer = proposal.approvals.last().unwrap();
+            events::emit_tx_executed(env, &last_signer, proposal_id as i128);
+        }
+        proposal.executed
+    }
+
+    /// Queues a sensitive proposal the first time it has its quorum,
+    /// restarting its timelock from the current ledger and pushing its
+    /// expiry back by the same amount.
+    fn queue_if_approved(env: &Env, proposal_id: u64, proposal: &mut Proposal) {
+        let Some(timelock) = proposal.queue_timelock else {
+            return;
+        };
+        if proposal.queued_ledger.is_some()
+            || Self::voted_weight(proposal, &proposal.approvals) < proposal.threshold
+        {
+            return;
+        }
+        let now = env.ledger().sequence();
+        let delay = now + timelock - proposal.executable_ledger;
+        proposal.executable_ledger += delay;
+        proposal.expiry_ledger += delay;
+        proposal.queued_ledger = Some(now);
+        events::emit_proposal_queued(env, proposal_id, proposal.executable_ledger);
+    }
+
+    fn require_open(env: &Env, proposal: &Proposal) {
+        if proposal.executed {
+            panic_with_error!(env, MultisigError::AlreadyExecuted);
+        }
+        if proposal.cancelled {
+            panic_with_error!(env, MultisigError::ProposalCancelled);
+        }
+        if env.ledger().sequence() > proposal.expiry_ledger {
+            panic_with_error!(env, MultisigError::ProposalExpired);
+        }
+    }
+
+    fn pending_ids(env: &Env) -> Vec<u64> {
+        ttl::get(env, &DataKey::Pending).unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Removes a proposal from the pending list. Returns whether it was listed.
+    fn remove_pending(env: &Env, proposal_id: u64) -> bool {
+        let mut pending = Self::pending_ids(env);
+        match pending.first_index_of(proposal_id) {
+            Some(index) => {
+                pending.remove(index);
+                ttl::set(env, &DataKey::Pending, &pending);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn snapshot_hash(
+        env: &Env,
+        signers: &Vec<Address>,
+        weights: &Map<Address, u32>,
+        threshold: u32,
+    ) -> BytesN<32> {
+        env.crypto()
+            .sha256(&(signers.clone(), weights.clone(), threshold).to_xdr(env))
+            .into()
+    }
+
+    fn weights(env: &Env) -> Map<Address, u32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Weights)
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    fn total_weight(weights: &Map<Address, u32>) -> u32 {
+        weights.values().iter().sum()
+    }
+
+    /// Sums the snapshot weight of `voters`.
+    fn voted_weight(proposal: &Proposal, voters: &Vec<Address>) -> u32 {
+        voters
+            .iter()
+            .map(|voter| proposal.weights.get(voter).unwrap_or(0))
+            .sum()
+    }
+
+    /// Validates and stores a signer configuration.
+    fn store_signers(
+        env: &Env,
+        signers: &Vec<Address>,
+        weights: &Map<Address, u32>,
+        threshold: u32,
+    ) {
+        if weights.values().iter().any(|weight| weight == 0) {
+            panic_with_error!(env, MultisigError::ZeroSignerWeight);
+        }
+        if threshold == 0 || threshold > Self::total_weight(weights) {
+            panic_with_error!(env, MultisigError::InvalidThreshold);
+        }
+
+        // Keep the per-signer flags `execute_with_second_sig` checks in step.
+        for old in Self::signers(env.clone()).iter() {
+            env.storage().instance().remove(&old);
+        }
+        for signer in signers.iter() {
+            env.storage()
+                .instance()
+                .set::<Address, bool>(&signer, &true);
+        }
+        env.storage().instance().set(&DataKey::Signers, signers);
+        env.storage().instance().set(&DataKey::Weights, weights);
+        env.storage()
+            .instance()
+            .set(&DataKey::Threshold, &threshold);
+    }
+
+    /// Carries out an executed proposal's action. A failing call reverts the
+    /// execution.
+    fn apply_action(env: &Env, proposal: &Proposal) {
+        let mut signers = Self::signers(env.clone());
+        let mut weights = Self::weights(env);
+        let mut threshold = Self::threshold(env.clone());
+        match &proposal.action {
+            ProposalAction::Transaction => return,
+            ProposalAction::Invoke(function, args) => {
+                env.invoke_contract::<Val>(&proposal.target, function, args.clone());
+                return;
+            }
+            ProposalAction::AddSigner(signer, weight) => {
+                if weights.contains_key(signer.clone()) {
+                    panic_with_error!(env, MultisigError::AlreadySigner);
+                }
+                signers.push_back(signer.clone());
+                weights.set(signer.clone(), *weight);
+            }
+            ProposalAction::RemoveSigner(signer) => {
+                let index = signers
+                    .first_index_of(signer)
+                    .unwrap_or_else(|| panic_with_error!(env, MultisigError::NotSigner));
+                signers.remove(index);
+                weights.remove(signer.clone());
+            }
+            ProposalAction::SetSignerWeight(signer, weight) => {
+                if !weights.contains_key(signer.clone()) {
+                    panic_with_error!(env, MultisigError::NotSigner);
+                }
+                weights.set(signer.clone(), *weight);
+            }
+            ProposalAction::SetThreshold(new_threshold) => threshold = *new_threshold,
+        }
+        Self::store_signers(env, &signers, &weights, threshold);
     }
 
     /// Executes a proposed transaction with co-signer approval.
     ///
-    /// Adds the co-signer's signature to the pending transaction.
-    /// If the combined weight meets or exceeds the Medium Threshold (2),
-    /// the transaction is executed.
+    /// Legacy entrypoint: it only records the co-signature and emits
+    /// `tx_executed`. Use `propose_call` for proposals that carry and
+    /// perform an actual call.
     ///
     /// # Arguments
     /// * `env` - The Soroban environment
@@ -104,6 +872,7 @@ impl MultisigGovernance {
     /// # Panics
     /// If co_signer is not authorized
     pub fn execute_with_second_sig(env: Env, co_signer: Address, tx_hash: i128) -> bool {
+        pausable::require_not_paused(&env);
         co_signer.require_auth();
 
         // Verify co-signer is authorized (basic check for compiled contracts)
@@ -114,15 +883,506 @@ impl MultisigGovernance {
             .unwrap_or(false);
 
         if !is_authorized {
-            panic!("co_signer is not authorized");
+            panic_with_error!(env, MultisigError::NotSigner);
         }
 
         // Emit execution event
-        env.events().publish(
-            (soroban_sdk::Symbol::new(&env, "tx_executed"), co_signer),
-            tx_hash,
-        );
+        events::emit_tx_executed(&env, &co_signer, tx_hash);
 
         true // Transaction executed successfully
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::{ProposalCancelledEvent, ProposalExpiredEvent, EVENT_VERSION};
+    use crate::rebalancer::{LiquidityRebalancer, LiquidityRebalancerClient};
+    use soroban_sdk::{
+        symbol_short,
+        testutils::{Address as _, Events, Ledger},
+        vec, Address, Env, IntoVal, Val,
+    };
+
+    struct Setup<'a> {
+        client: MultisigGovernanceClient<'a>,
+        admin: Address,
+        owner: Address,
+        signer_1: Address,
+        signer_2: Address,
+        target: Address,
+    }
+
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+        // Keep entries alive across the timelock jumps below.
+        env.ledger().with_mut(|l| {
+            l.min_persistent_entry_ttl = 100_000;
+            l.max_entry_ttl = 200_000;
+        });
+        let contract_id = env.register_contract(None, MultisigGovernance);
+        let s = Setup {
+            client: MultisigGovernanceClient::new(env, &contract_id),
+            admin: Address::generate(env),
+            owner: Address::generate(env),
+            signer_1: Address::generate(env),
+            signer_2: Address::generate(env),
+            target: Address::generate(env),
+        };
+        s.client.initialize(&s.admin);
+        s.client.configure_multisig(
+            &s.admin,
+            &vec![env, s.owner.clone(), s.signer_1.clone(), s.signer_2.clone()],
+            &2,
+        );
+        s.client.set_category(
+            &s.admin,
+            &symbol_short!("payment"),
+            &ProposalCategory {
+                quorum: 2,
+                timelock: 0,
+                allowed_targets: vec![env],
+                sensitive: false,
+            },
+        );
+        s
+    }
+
+    /// Returns the most recent event as a one-element vec for comparison.
+    fn last_event(env: &Env) -> Vec<(Address, Vec<Val>, Val)> {
+        let all = env.events().all();
+        all.slice(all.len() - 1..)
+    }
+
+    #[test]
+    fn test_pause_blocks_proposals() {
+        let env = Env::default();
+        let s = setup(&env);
+        let payment = symbol_short!("payment");
+
+        s.client.pause(&s.admin);
+        assert!(s
+            .client
+            .try_propose_sensitive_tx(&s.owner, &payment, &s.target, &1_000)
+            .is_err());
+        assert!(s
+            .client
+            .try_execute_with_second_sig(&s.signer_1, &1)
+            .is_err());
+
+        s.client.unpause(&s.admin);
+        let id = s
+            .client
+            .propose_sensitive_tx(&s.owner, &payment, &s.target, &1_000);
+        assert!(!s.client.get_proposal(&id).executed);
+        assert!(s.client.execute_with_second_sig(&s.signer_1, &1));
+    }
+
+    #[test]
+    fn test_proposal_snapshot_verifies() {
+        let env = Env::default();
+        let s = setup(&env);
+        let outsider = Address::generate(&env);
+
+        let id =
+            s.client
+                .propose_sensitive_tx(&s.owner, &symbol_short!("payment"), &s.target, &5_000);
+        let proposal = s.client.get_proposal(&id);
+        assert_eq!(proposal.threshold, 2);
+        assert_eq!(proposal.signers.len(), 3);
+        assert!(s.client.verify_proposal_snapshot(&id));
+        assert!(!s.client.verify_proposal_snapshot(&(id + 1)));
+
+        assert!(s.client.try_approve_proposal(&id, &outsider).is_err());
+        assert!(s.client.try_approve_proposal(&id, &s.owner).is_err());
+        assert!(s.client.approve_proposal(&id, &s.signer_2));
+        assert!(s.client.get_proposal(&id).executed);
+        assert!(s.client.verify_proposal_snapshot(&id));
+    }
+
+    #[test]
+    fn test_category_quorums_and_timelocks() {
+        let env = Env::default();
+        let s = setup(&env);
+        let emergency = symbol_short!("emergency");
+        let upgrade = symbol_short!("upgrade");
+        s.client.set_category(
+            &s.admin,
+            &emergency,
+            &ProposalCategory {
+                quorum: 2,
+                timelock: 0,
+                allowed_targets: vec![&env],
+                sensitive: false,
+            },
+        );
+        s.client.set_category(
+            &s.admin,
+            &upgrade,
+            &ProposalCategory {
+                quorum: 3,
+                timelock: 10_000,
+                allowed_targets: vec![&env, s.target.clone()],
+                sensitive: false,
+            },
+        );
+        // Three signers of weight one cannot reach a quorum of four.
+        assert_eq!(
+            s.client.try_set_category(
+                &s.admin,
+                &upgrade,
+                &ProposalCategory {
+                    quorum: 4,
+                    timelock: 0,
+                    allowed_targets: vec![&env],
+                    sensitive: false,
+                },
+            ),
+            Err(Ok(MultisigError::QuorumTooHigh.into()))
+        );
+
+        // Emergency proposals execute as soon as they reach quorum.
+        let id = s
+            .client
+            .propose_sensitive_tx(&s.owner, &emergency, &s.target, &0);
+        assert!(s.client.approve_proposal(&id, &s.signer_1));
+
+        // Upgrades need every signer and wait out the timelock.
+        let other = Address::generate(&env);
+        assert!(s
+            .client
+            .try_propose_sensitive_tx(&s.owner, &upgrade, &other, &0)
+            .is_err());
+        let id = s
+            .client
+            .propose_sensitive_tx(&s.owner, &upgrade, &s.target, &0);
+        assert!(!s.client.approve_proposal(&id, &s.signer_1));
+        assert!(s.client.try_execute_proposal(&id).is_err());
+        assert!(!s.client.approve_proposal(&id, &s.signer_2));
+        assert!(s.client.try_execute_proposal(&id).is_err());
+
+        env.ledger().with_mut(|l| l.sequence_number += 10_000);
+        s.client.execute_proposal(&id);
+        assert!(s.client.get_proposal(&id).executed);
+
+        assert!(s
+            .client
+            .try_propose_sensitive_tx(&s.owner, &symbol_short!("unknown"), &s.target, &0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_sensitive_proposals_queue_for_veto() {
+        let env = Env::default();
+        let s = setup(&env);
+        let fees = symbol_short!("fees");
+        let vetoer = Address::generate(&env);
+        s.client.set_role(&KeyRole::Veto, &vetoer);
+        s.client.set_category(
+            &s.admin,
+            &fees,
+            &ProposalCategory {
+                quorum: 2,
+                timelock: 100,
+                allowed_targets: vec![&env],
+                sensitive: true,
+            },
+        );
+
+        // The timelock starts once the quorum is reached, not at proposal.
+        let id = s
+            .client
+            .propose_sensitive_tx(&s.owner, &fees, &s.target, &0);
+        assert_eq!(
+            s.client.try_veto_proposal(&id, &vetoer),
+            Err(Ok(MultisigError::NotQueued.into()))
+        );
+        env.ledger().with_mut(|l| l.sequence_number += 50);
+        let queued = env.ledger().sequence();
+        assert!(!s.client.approve_proposal(&id, &s.signer_1));
+        let proposal = s.client.get_proposal(&id);
+        assert_eq!(proposal.queued_ledger, Some(queued));
+        assert_eq!(proposal.executable_ledger, queued + 100);
+        assert_eq!(
+            s.client.try_execute_proposal(&id),
+            Err(Ok(MultisigError::Timelocked.into()))
+        );
+
+        assert_eq!(
+            s.client.try_veto_proposal(&id, &s.owner),
+            Err(Ok(MultisigError::NotVetoer.into()))
+        );
+        s.client.veto_proposal(&id, &vetoer);
+        assert!(s.client.get_proposal(&id).cancelled);
+        assert!(!s.client.pending_proposals().contains(id));
+        env.ledger().with_mut(|l| l.sequence_number += 100);
+        assert_eq!(
+            s.client.try_execute_proposal(&id),
+            Err(Ok(MultisigError::ProposalCancelled.into()))
+        );
+
+        // Once the timelock ends the veto window is closed.
+        let id = s
+            .client
+            .propose_sensitive_tx(&s.owner, &fees, &s.target, &0);
+        s.client.approve_proposal(&id, &s.signer_2);
+        env.ledger().with_mut(|l| l.sequence_number += 100);
+        assert_eq!(
+            s.client.try_veto_proposal(&id, &vetoer),
+            Err(Ok(MultisigError::VetoWindowClosed.into()))
+        );
+        s.client.execute_proposal(&id);
+        assert!(s.client.get_proposal(&id).executed);
+    }
+
+    #[test]
+    fn test_n_of_m_threshold_and_pending_proposals() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let client =
+            MultisigGovernanceClient::new(&env, &env.register_contract(None, MultisigGovernance));
+        client.initialize(&admin);
+        let s = Setup {
+            client,
+            admin,
+            owner: Address::generate(&env),
+            signer_1: Address::generate(&env),
+            signer_2: Address::generate(&env),
+            target: Address::generate(&env),
+        };
+        let payment = symbol_short!("payment");
+        let signer_3 = Address::generate(&env);
+        let signers = vec![
+            &env,
+            s.owner.clone(),
+            s.signer_1.clone(),
+            s.signer_2.clone(),
+            signer_3.clone(),
+        ];
+
+        assert!(s
+            .client
+            .try_configure_multisig(&s.admin, &signers, &5)
+            .is_err());
+        assert!(s
+            .client
+            .try_configure_multisig(&s.admin, &vec![&env, s.owner.clone(), s.owner.clone()], &1)
+            .is_err());
+        s.client.configure_multisig(&s.admin, &signers, &3);
+        assert_eq!(s.client.signers(), signers);
+        assert_eq!(s.client.threshold(), 3);
+        // The admin cannot swap the signers out afterwards.
+        assert_eq!(
+            s.client
+                .try_configure_multisig(&s.admin, &vec![&env, s.owner.clone()], &1),
+            Err(Ok(MultisigError::AlreadyConfigured.into()))
+        );
+        s.client.set_category(
+            &s.admin,
+            &payment,
+            &ProposalCategory {
+                quorum: 2,
+                timelock: 0,
+                allowed_targets: vec![&env],
+                sensitive: false,
+            },
+        );
+
+        // The signer threshold overrides the lower "payment" quorum.
+        let first = s
+            .client
+            .propose_sensitive_tx(&signer_3, &payment, &s.target, &100);
+        let second = s
+            .client
+            .propose_sensitive_tx(&s.owner, &payment, &s.target, &200);
+        assert_eq!(s.client.get_proposal(&first).threshold, 3);
+        assert_eq!(s.client.pending_proposals(), vec![&env, first, second]);
+
+        assert!(!s.client.approve_proposal(&first, &s.signer_1));
+        assert!(s.client.approve_proposal(&first, &s.signer_2));
+        assert_eq!(s.client.pending_proposals(), vec![&env, second]);
+
+        // Shrinking the signer set revokes legacy co-signing for removed signers.
+        let id = s.client.propose_action(
+            &s.owner,
+            &payment,
+            &ProposalAction::RemoveSigner(signer_3.clone()),
+        );
+        assert!(!s.client.approve_proposal(&id, &s.signer_1));
+        assert!(s.client.approve_proposal(&id, &s.signer_2));
+        assert_eq!(s.client.signer_weight(&signer_3), 0);
+        assert!(s.client.try_execute_with_second_sig(&signer_3, &1).is_err());
+    }
+
+    #[test]
+    fn test_proposal_cancellation() {
+        let env = Env::default();
+        let s = setup(&env);
+        let payment = symbol_short!("payment");
+
+        // The proposer may withdraw outright.
+        let id = s
+            .client
+            .propose_sensitive_tx(&s.owner, &payment, &s.target, &100);
+        assert!(s.client.cancel_proposal(&id, &s.owner));
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    s.client.address.clone(),
+                    (Symbol::new(&env, "proposal_cancelled"), id).into_val(&env),
+                    ProposalCancelledEvent {
+                        cancelled_by: s.owner.clone(),
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+        assert!(s.client.get_proposal(&id).cancelled);
+        assert!(s.client.try_approve_proposal(&id, &s.signer_1).is_err());
+        assert!(s.client.try_cancel_proposal(&id, &s.owner).is_err());
+
+        // Other signers need the proposal's threshold of cancel votes.
+        let id = s
+            .client
+            .propose_sensitive_tx(&s.owner, &payment, &s.target, &200);
+        let outsider = Address::generate(&env);
+        assert!(s.client.try_cancel_proposal(&id, &outsider).is_err());
+        assert!(!s.client.cancel_proposal(&id, &s.signer_1));
+        assert!(s.client.try_cancel_proposal(&id, &s.signer_1).is_err());
+        assert!(s.client.cancel_proposal(&id, &s.signer_2));
+        assert!(s.client.try_execute_proposal(&id).is_err());
+        assert_eq!(s.client.pending_proposals(), vec![&env]);
+    }
+
+    #[test]
+    fn test_proposal_expiry() {
+        let env = Env::default();
+        let s = setup(&env);
+        s.client.set_proposal_lifetime(&s.admin, &100);
+        let id =
+            s.client
+                .propose_sensitive_tx(&s.owner, &symbol_short!("payment"), &s.target, &100);
+        assert_eq!(s.client.get_proposal(&id).expiry_ledger, 100);
+        assert!(s.client.try_expire_proposal(&id).is_err());
+
+        env.ledger().with_mut(|l| l.sequence_number += 101);
+        assert_eq!(s.client.pending_proposals(), vec![&env]);
+        assert!(s.client.try_approve_proposal(&id, &s.signer_1).is_err());
+        assert!(s.client.try_cancel_proposal(&id, &s.owner).is_err());
+
+        s.client.expire_proposal(&id);
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    s.client.address.clone(),
+                    (Symbol::new(&env, "proposal_expired"), id).into_val(&env),
+                    ProposalExpiredEvent {
+                        expiry_ledger: 100,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+        assert!(s.client.try_expire_proposal(&id).is_err());
+    }
+
+    #[test]
+    fn test_signer_rotation_through_proposals() {
+        let env = Env::default();
+        let s = setup(&env);
+        let payment = symbol_short!("payment");
+        let newcomer = Address::generate(&env);
+
+        let id = s.client.propose_action(
+            &s.owner,
+            &payment,
+            &ProposalAction::AddSigner(newcomer.clone(), 2),
+        );
+        assert_eq!(s.client.get_proposal(&id).target, s.client.address);
+        assert!(s.client.approve_proposal(&id, &s.signer_1));
+        assert_eq!(s.client.signers().len(), 4);
+        assert_eq!(s.client.signer_weight(&newcomer), 2);
+
+        // A threshold the signers' total weight (5) cannot reach is rejected.
+        let id = s
+            .client
+            .propose_action(&s.owner, &payment, &ProposalAction::SetThreshold(6));
+        assert!(s.client.try_approve_proposal(&id, &s.signer_1).is_err());
+        assert!(!s.client.get_proposal(&id).executed);
+
+        let id = s
+            .client
+            .propose_action(&newcomer, &payment, &ProposalAction::SetThreshold(3));
+        assert!(s.client.approve_proposal(&id, &s.owner));
+        assert_eq!(s.client.threshold(), 3);
+
+        // Weighted approvals: the newcomer alone carries two thirds of the quorum.
+        let id = s.client.propose_action(
+            &newcomer,
+            &payment,
+            &ProposalAction::SetSignerWeight(s.signer_2.clone(), 2),
+        );
+        assert!(s.client.approve_proposal(&id, &s.signer_1));
+        assert_eq!(s.client.signer_weight(&s.signer_2), 2);
+
+        let id = s.client.propose_action(
+            &s.owner,
+            &payment,
+            &ProposalAction::RemoveSigner(newcomer.clone()),
+        );
+        assert!(!s.client.approve_proposal(&id, &s.signer_1));
+        assert!(s.client.approve_proposal(&id, &s.signer_2));
+        assert_eq!(s.client.signer_weight(&newcomer), 0);
+        assert!(s.client.try_execute_with_second_sig(&newcomer, &1).is_err());
+
+        // Removing more weight than the threshold allows is rejected.
+        let id = s.client.propose_action(
+            &s.signer_2,
+            &payment,
+            &ProposalAction::RemoveSigner(s.signer_2.clone()),
+        );
+        assert!(s.client.try_approve_proposal(&id, &s.owner).is_err());
+    }
+
+    #[test]
+    fn test_executed_proposal_invokes_target() {
+        let env = Env::default();
+        let s = setup(&env);
+        let payment = symbol_short!("payment");
+        let rebalancer =
+            LiquidityRebalancerClient::new(&env, &env.register_contract(None, LiquidityRebalancer));
+        rebalancer.initialize(&s.client.address);
+
+        let args: Vec<Val> = vec![&env, s.client.address.into_val(&env)];
+        let id = s.client.propose_call(
+            &s.owner,
+            &payment,
+            &rebalancer.address,
+            &symbol_short!("pause"),
+            &args,
+        );
+        assert!(!rebalancer.is_paused());
+        assert!(s.client.approve_proposal(&id, &s.signer_1));
+        assert!(rebalancer.is_paused());
+
+        // A failing call leaves the proposal unexecuted.
+        let id = s.client.propose_call(
+            &s.owner,
+            &payment,
+            &rebalancer.address,
+            &symbol_short!("missing"),
+            &args,
+        );
+        assert!(s.client.try_approve_proposal(&id, &s.signer_1).is_err());
+        assert!(!s.client.get_proposal(&id).executed);
+    }
+}
@@ -1,5 +1,7 @@
 use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec};
 
+use crate::events;
+
 #[contracttype]
 pub struct PaymentPath {
     pub source_asset: String,
@@ -67,10 +69,7 @@ impl PathPayment {
         }
 
         // Emit success event
-        env.events().publish(
-            (String::from_str(&env, "path_payment_success"), from),
-            current_required,
-        );
+        events::emit_path_payment(&env, &from, current_required);
 
         true
     }
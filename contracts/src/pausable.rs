@@ -0,0 +1,46 @@
+//! Global pause / emergency-stop switch.
+//!
+//! State-changing entrypoints call [`require_not_paused`] before doing any
+//! work; read-only getters never check the flag so they keep working while
+//! the contract is halted. Only the contract admin or a guardian (see
+//! [`crate::rbac::Role::Guardian`]) may toggle the flag.
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+use crate::errors::CommonError;
+use crate::events;
+use crate::rbac::{self, Role};
+
+#[contracttype]
+enum DataKey {
+    Paused,
+}
+
+/// Returns `true` while the contract is paused.
+pub fn is_paused(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Paused)
+        .unwrap_or(false)
+}
+
+/// # Panics
+/// With `ContractPaused` if the contract is paused
+pub fn require_not_paused(env: &Env) {
+    if is_paused(env) {
+        panic_with_error!(env, CommonError::ContractPaused);
+    }
+}
+
+/// Halts every guarded entrypoint. `caller` must be the admin or a guardian.
+pub fn pause(env: &Env, caller: &Address) {
+    rbac::require_admin_or_role(env, Role::Guardian, caller);
+    env.storage().instance().set(&DataKey::Paused, &true);
+    events::emit_paused(env, caller);
+}
+
+/// Lifts a previous [`pause`]. `caller` must be the admin or a guardian.
+pub fn unpause(env: &Env, caller: &Address) {
+    rbac::require_admin_or_role(env, Role::Guardian, caller);
+    env.storage().instance().set(&DataKey::Paused, &false);
+    events::emit_unpaused(env, caller);
+}
@@ -0,0 +1,197 @@
+//! Shared role-based access control.
+//!
+//! Complements the single admin in [`crate::admin`] with roles that any
+//! number of accounts may hold at once (see [`Role`]). Membership is granted
+//! and revoked by the role admin, which starts out as the contract admin and
+//! can be handed to another account, typically the multisig governance
+//! contract, with [`transfer_role_admin`]. Entrypoints gate on membership
+//! with [`require_role`] or [`require_admin_or_role`].
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+use crate::errors::CommonError;
+use crate::{admin, events, ttl};
+
+/// Roles an account can be granted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Role {
+    /// May rule on disputes without staking in the arbiter registry
+    Arbiter = 0,
+    /// Runs day-to-day configuration, such as the asset registry
+    Operator = 1,
+    /// Triggers routine maintenance, such as liquidity rebalancing
+    Keeper = 2,
+    /// May pause and unpause the contract, e.g. the multisig governance
+    /// contract
+    Guardian = 3,
+    /// May claw back frozen escrows for fraud and chargeback handling
+    Compliance = 4,
+}
+
+#[contracttype]
+enum RbacKey {
+    RoleAdmin,
+    Member(Role, Address),
+}
+
+/// Returns the account that grants and revokes roles, which is the
+/// contract admin until [`transfer_role_admin`] is called.
+pub fn role_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&RbacKey::RoleAdmin)
+        .unwrap_or_else(|| admin::get_admin(env))
+}
+
+/// Requires `caller` to be the role admin and to have authorized the call.
+///
+/// # Panics
+/// With `NotRoleAdmin` if `caller` is not the role admin
+pub fn require_role_admin(env: &Env, caller: &Address) {
+    caller.require_auth();
+    if *caller != role_admin(env) {
+        panic_with_error!(env, CommonError::NotRoleAdmin);
+    }
+}
+
+/// Hands role administration to `new_admin`. `caller` must be the current
+/// role admin.
+pub fn transfer_role_admin(env: &Env, caller: &Address, new_admin: &Address) {
+    require_role_admin(env, caller);
+    env.storage().instance().set(&RbacKey::RoleAdmin, new_admin);
+}
+
+/// Grants `role` to `account`. `caller` must be the role admin.
+pub fn grant_role(env: &Env, caller: &Address, role: Role, account: &Address) {
+    require_role_admin(env, caller);
+    ttl::set(env, &RbacKey::Member(role, account.clone()), &true);
+    events::emit_role_granted(env, account, role, caller);
+}
+
+/// Revokes `role` from `account`. `caller` must be the role admin.
+pub fn revoke_role(env: &Env, caller: &Address, role: Role, account: &Address) {
+    require_role_admin(env, caller);
+    env.storage()
+        .persistent()
+        .remove(&RbacKey::Member(role, account.clone()));
+    events::emit_role_revoked(env, account, role, caller);
+}
+
+/// Returns `true` if `account` holds `role`.
+pub fn has_role(env: &Env, role: Role, account: &Address) -> bool {
+    ttl::get(env, &RbacKey::Member(role, account.clone())).unwrap_or(false)
+}
+
+/// Requires `account` to hold `role` and to have authorized the call.
+///
+/// # Panics
+/// With `MissingRole` if `account` does not hold `role`
+pub fn require_role(env: &Env, role: Role, account: &Address) {
+    account.require_auth();
+    if !has_role(env, role, account) {
+        panic_with_error!(env, CommonError::MissingRole);
+    }
+}
+
+/// Like [`require_role`], but the contract admin passes as well.
+pub fn require_admin_or_role(env: &Env, role: Role, account: &Address) {
+    account.require_auth();
+    if *account != admin::get_admin(env) && !has_role(env, role, account) {
+        panic_with_error!(env, CommonError::MissingRole);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env};
+
+    #[contract]
+    struct RbacHarness;
+
+    #[contractimpl]
+    impl RbacHarness {
+        pub fn initialize(env: Env, admin: Address) {
+            admin::set_admin(&env, &admin);
+        }
+
+        pub fn grant_role(env: Env, caller: Address, role: Role, account: Address) {
+            grant_role(&env, &caller, role, &account);
+        }
+
+        pub fn revoke_role(env: Env, caller: Address, role: Role, account: Address) {
+            revoke_role(&env, &caller, role, &account);
+        }
+
+        pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+            has_role(&env, role, &account)
+        }
+
+        pub fn role_admin(env: Env) -> Address {
+            role_admin(&env)
+        }
+
+        pub fn transfer_role_admin(env: Env, caller: Address, new_admin: Address) {
+            transfer_role_admin(&env, &caller, &new_admin);
+        }
+
+        pub fn keeper_only(env: Env, keeper: Address) {
+            require_role(&env, Role::Keeper, &keeper);
+        }
+    }
+
+    fn setup(env: &Env) -> (RbacHarnessClient<'_>, Address) {
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, RbacHarness);
+        let client = RbacHarnessClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+        client.initialize(&admin);
+        (client, admin)
+    }
+
+    #[test]
+    fn test_grant_and_revoke() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let keeper = Address::generate(&env);
+
+        assert!(!client.has_role(&Role::Keeper, &keeper));
+        assert_eq!(
+            client.try_keeper_only(&keeper),
+            Err(Ok(CommonError::MissingRole.into()))
+        );
+
+        client.grant_role(&admin, &Role::Keeper, &keeper);
+        assert!(client.has_role(&Role::Keeper, &keeper));
+        // Roles are independent of one another.
+        assert!(!client.has_role(&Role::Operator, &keeper));
+        client.keeper_only(&keeper);
+
+        client.revoke_role(&admin, &Role::Keeper, &keeper);
+        assert!(!client.has_role(&Role::Keeper, &keeper));
+        assert!(client.try_keeper_only(&keeper).is_err());
+    }
+
+    #[test]
+    fn test_role_admin_transfer() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let multisig = Address::generate(&env);
+        let operator = Address::generate(&env);
+        assert_eq!(client.role_admin(), admin);
+
+        assert_eq!(
+            client.try_grant_role(&operator, &Role::Operator, &operator),
+            Err(Ok(CommonError::NotRoleAdmin.into()))
+        );
+
+        client.transfer_role_admin(&admin, &multisig);
+        assert_eq!(client.role_admin(), multisig);
+        // The contract admin no longer manages roles.
+        assert!(client
+            .try_grant_role(&admin, &Role::Operator, &operator)
+            .is_err());
+        client.grant_role(&multisig, &Role::Operator, &operator);
+        assert!(client.has_role(&Role::Operator, &operator));
+    }
+}
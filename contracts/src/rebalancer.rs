@@ -1,45 +1,1313 @@
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractimpl, contracttype, panic_with_error, token,
+    Address, Env, Map, String, Symbol, Vec,
+};
+
+use crate::admin::{self, KeyRole, Roles};
+use crate::assets::{self, AssetInfo};
+use crate::rbac::{self, Role};
+use crate::{events, pausable};
+
+/// Denominator for basis-point rates.
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Number of ledgers in one swap-volume "day" (~5s close time).
+pub const LEDGERS_PER_DAY: u32 = 17_280;
+
+/// Errors raised when a rebalance would break the configured limits.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RebalancerError {
+    /// The wallet was rebalanced less than the cooldown ago
+    CooldownActive = 100,
+    /// The swap would take today's USDC volume over the daily maximum
+    DailyVolumeExceeded = 101,
+    /// The daily maximum volume is not positive
+    InvalidLimits = 102,
+    /// The slippage cap exceeds 100%
+    InvalidSlippage = 103,
+    /// The volume window is empty
+    InvalidVolumeWindow = 104,
+    /// The volume window is not configured
+    VolumeWindowNotConfigured = 105,
+    /// The payout or bounty amount is not positive
+    InvalidAmount = 106,
+    /// The wallet target is below its threshold or the threshold is not positive
+    InvalidPolicy = 107,
+    /// The wallet is not monitored
+    WalletNotRegistered = 108,
+    /// The price feed is not configured
+    OracleNotConfigured = 109,
+    /// The price feed has no price for the asset
+    NoPrice = 110,
+    /// The price feed returned a non-positive price
+    InvalidPrice = 111,
+    /// The price is older than the maximum age
+    StalePrice = 112,
+    /// The wallet cannot cover the swap including slippage
+    InsufficientUsdc = 113,
+    /// The AMM router is not configured
+    RouterNotConfigured = 114,
+    /// The router delivered less XLM than requested
+    SwapUnderdelivered = 115,
+    /// The router charged more than the slippage cap
+    SlippageExceeded = 116,
+    /// A keeper bounty needs limits with a non-zero cooldown
+    BountyWithoutCooldown = 117,
+    /// An XLM bounty would undo a wallet's top-up margin
+    BountyTooLarge = 118,
+}
+
+/// Safety limits on how often and how much the rebalancer may swap.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RebalanceLimits {
+    /// Minimum ledgers between two rebalances of the same wallet
+    pub cooldown_ledgers: u32,
+    /// Most USDC all swaps may spend per ledger-day
+    pub daily_max_volume: i128,
+}
+
+/// USDC spent by swaps during one ledger-day.
+#[derive(Clone)]
+#[contracttype]
+pub struct DailyVolume {
+    pub day: u32,
+    pub amount: i128,
+}
+
+/// How recent payout volume feeds into the top-up target.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct VolumeWindow {
+    /// Ledgers covered by each volume bucket
+    pub bucket_ledgers: u32,
+    /// Number of most recent buckets averaged
+    pub buckets: u32,
+    /// Share of the average bucket volume added to the target, in basis points
+    pub multiplier_bps: u32,
+}
+
+/// Asset identifier understood by the price feed (Reflector-style).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum OracleAsset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// A price quote from the feed, scaled by its `decimals()`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PriceData {
+    pub price: i128,
+    /// Unix time the price was recorded, in seconds
+    pub timestamp: u64,
+}
+
+/// Interface of the price feed contract.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData>;
+}
+
+/// Interface of the AMM router swaps are routed through (Soroswap-style).
+#[contractclient(name = "RouterClient")]
+pub trait Router {
+    /// Buys exactly `amount_out` of the last token in `path` for at most
+    /// `amount_in_max` of the first, pulled from `to` under an allowance.
+    /// Returns the amounts along the path.
+    fn swap_tokens_for_exact_tokens(
+        env: Env,
+        amount_out: i128,
+        amount_in_max: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128>;
+
+    /// Sells exactly `amount_in` of the first token in `path`, pulled from
+    /// `to` under an allowance, for at least `amount_out_min` of the last,
+    /// delivered to `to`. Returns the amounts along the path.
+    fn swap_exact_tokens_for_tokens(
+        env: Env,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128>;
+}
+
+/// Price feed used to size swaps.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct OracleConfig {
+    pub oracle: Address,
+    /// Most the swap may spend above the oracle quote, in basis points
+    pub max_slippage_bps: u32,
+    /// Oldest price accepted, in seconds
+    pub max_price_age: u64,
+}
+
+/// Per-wallet rebalance policy, in XLM stroops.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct WalletPolicy {
+    /// Balance below which the wallet is topped up
+    pub threshold: i128,
+    /// Balance the wallet is topped up to, before volume adjustment
+    pub target: i128,
+}
+
+/// Asset a keeper bounty is paid in, from the rebalanced wallet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum BountyAsset {
+    Xlm = 0,
+    Usdc = 1,
+}
+
+/// Reward paid to whoever triggers a rebalance that executes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct KeeperBounty {
+    pub asset: BountyAsset,
+    pub amount: i128,
+}
+
+#[contracttype]
+pub enum DataKey {
+    VolumeWindow,
+    Oracle,
+    Router,
+    Limits,
+    /// USDC spent by swaps today
+    DailyVolume,
+    /// Ledger of a wallet's last rebalance
+    LastRebalance(Address),
+    /// Monitored wallets and their policies
+    Wallets,
+    /// Payout volume per bucket index, pruned to the window on every write
+    Volume,
+    KeeperBounty,
+}
 
 #[contract]
 pub struct LiquidityRebalancer;
 
 #[contractimpl]
 impl LiquidityRebalancer {
-    /// Checks if the funding wallet's XLM balance is below the threshold.
-    /// If so, it simulates a swap (USDC -> XLM) to top it up.
-    pub fn check_and_rebalance(
-        env: Env,
-        funding_wallet: Address,
-        threshold: i128,
-        target: i128,
-    ) -> bool {
-        // 1. Check current balance (Mock)
-        // In reality: env.client().get_balance(funding_wallet)
-        let current_balance = 15_0000000; // Mock: 15 XLM (Assume threshold is 20)
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Assigns a key role (veto, recovery, fee collector, treasury). Admin only.
+    pub fn set_role(env: Env, role: KeyRole, address: Address) {
+        admin::set_role(&env, role, &address);
+    }
+
+    /// Returns the admin and every key role in a single read.
+    pub fn get_roles(env: Env) -> Roles {
+        admin::get_roles(&env)
+    }
+
+    /// Grants `role` to `account`. `caller` must be the role admin.
+    pub fn grant_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::grant_role(&env, &caller, role, &account);
+    }
+
+    /// Revokes `role` from `account`. `caller` must be the role admin.
+    pub fn revoke_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::revoke_role(&env, &caller, role, &account);
+    }
+
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        rbac::has_role(&env, role, &account)
+    }
+
+    /// Returns the account that grants and revokes roles.
+    pub fn role_admin(env: Env) -> Address {
+        rbac::role_admin(&env)
+    }
+
+    /// Hands role administration to `new_admin`, e.g. the multisig
+    /// governance contract. `caller` must be the current role admin.
+    pub fn transfer_role_admin(env: Env, caller: Address, new_admin: Address) {
+        rbac::transfer_role_admin(&env, &caller, &new_admin);
+    }
+
+    /// Pauses every state-changing entrypoint. Admin or guardian only.
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause. Admin or guardian only.
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Registers (or re-points) a supported asset, such as the USDC used to
+    /// fund swaps. Admin or operator only.
+    pub fn register_asset(env: Env, caller: Address, asset_code: String, asset: AssetInfo) {
+        assets::register_asset(&env, &caller, asset_code, asset);
+    }
+
+    /// Returns every supported `(asset_code, asset)`, ordered by code.
+    pub fn supported_assets(env: Env) -> Vec<(String, AssetInfo)> {
+        assets::supported_assets(&env)
+    }
+
+    /// Configures the price feed that sizes swaps. Admin only.
+    pub fn set_oracle(env: Env, admin: Address, config: OracleConfig) {
+        admin::require_admin_caller(&env, &admin);
+        if config.max_slippage_bps as i128 > BPS_DENOMINATOR {
+            panic_with_error!(env, RebalancerError::InvalidSlippage);
+        }
+        env.storage().instance().set(&DataKey::Oracle, &config);
+    }
+
+    pub fn get_oracle(env: Env) -> Option<OracleConfig> {
+        env.storage().instance().get(&DataKey::Oracle)
+    }
+
+    /// Sets the AMM router swaps are executed through. Admin only.
+    pub fn set_router(env: Env, admin: Address, router: Address) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage().instance().set(&DataKey::Router, &router);
+    }
+
+    pub fn get_router(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Router)
+    }
+
+    /// Sets the cooldown and daily volume limits. While a keeper bounty is
+    /// set the cooldown cannot be zero. Admin only.
+    pub fn set_limits(env: Env, admin: Address, limits: RebalanceLimits) {
+        admin::require_admin_caller(&env, &admin);
+        if limits.daily_max_volume <= 0 {
+            panic_with_error!(env, RebalancerError::InvalidLimits);
+        }
+        if limits.cooldown_ledgers == 0 && Self::get_keeper_bounty(env.clone()).is_some() {
+            panic_with_error!(env, RebalancerError::BountyWithoutCooldown);
+        }
+        env.storage().instance().set(&DataKey::Limits, &limits);
+    }
+
+    pub fn get_limits(env: Env) -> Option<RebalanceLimits> {
+        env.storage().instance().get(&DataKey::Limits)
+    }
+
+    /// Returns the USDC spent by swaps so far this ledger-day.
+    pub fn daily_volume(env: Env) -> i128 {
+        Self::volume_today(&env).amount
+    }
+
+    /// Returns the ledger `wallet` was last rebalanced at, if ever.
+    pub fn last_rebalance(env: Env, wallet: Address) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::LastRebalance(wallet))
+    }
+
+    /// Adds `wallet` to the monitored registry, or replaces its policy.
+    /// The wallet must approve this contract to spend its USDC, and its XLM
+    /// if the keeper bounty is paid in XLM, for rebalances to go through.
+    /// Admin only.
+    ///
+    /// # Panics
+    /// With `BountyTooLarge` if an XLM keeper bounty is at least the margin
+    /// between the policy's threshold and target
+    pub fn register_wallet(env: Env, admin: Address, wallet: Address, policy: WalletPolicy) {
+        admin::require_admin_caller(&env, &admin);
+        if policy.threshold <= 0 || policy.target < policy.threshold {
+            panic_with_error!(env, RebalancerError::InvalidPolicy);
+        }
+        if let Some(bounty) = Self::get_keeper_bounty(env.clone()) {
+            Self::check_bounty_margin(&env, &bounty, &policy);
+        }
+        let mut wallets = Self::wallets(env.clone());
+        wallets.set(wallet, policy);
+        env.storage().instance().set(&DataKey::Wallets, &wallets);
+    }
+
+    /// Stops monitoring `wallet`. Admin only.
+    pub fn remove_wallet(env: Env, admin: Address, wallet: Address) {
+        admin::require_admin_caller(&env, &admin);
+        let mut wallets = Self::wallets(env.clone());
+        if wallets.remove(wallet).is_none() {
+            panic_with_error!(env, RebalancerError::WalletNotRegistered);
+        }
+        env.storage().instance().set(&DataKey::Wallets, &wallets);
+    }
+
+    /// Returns every monitored wallet and its policy.
+    pub fn wallets(env: Env) -> Map<Address, WalletPolicy> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Wallets)
+            .unwrap_or_else(|| Map::new(&env))
+    }
+
+    /// Runs `check_and_rebalance` for every monitored wallet under its own
+    /// policy and returns the wallets that were topped up. Keeper only.
+    pub fn rebalance_all(env: Env, keeper: Address) -> Vec<Address> {
+        pausable::require_not_paused(&env);
+        rbac::require_role(&env, Role::Keeper, &keeper);
+        let mut topped_up = Vec::new(&env);
+        for (wallet, policy) in Self::wallets(env.clone()).iter() {
+            if Self::rebalance(&env, &wallet, policy.threshold, policy.target) {
+                topped_up.push_back(wallet);
+            }
+        }
+        topped_up
+    }
+
+    /// Sets the bounty paid to callers of `check_and_rebalance` whenever a
+    /// rebalance executes, or removes it with `None`. The cooldown in the
+    /// limits lets each wallet pay it at most once per cooldown. Admin only.
+    ///
+    /// # Panics
+    /// With `BountyWithoutCooldown` unless limits with a non-zero cooldown
+    /// are set, or `BountyTooLarge` if an XLM bounty is at least the margin
+    /// between a monitored wallet's threshold and target
+    pub fn set_keeper_bounty(env: Env, admin: Address, bounty: Option<KeeperBounty>) {
+        admin::require_admin_caller(&env, &admin);
+        match bounty {
+            Some(bounty) => {
+                if bounty.amount <= 0 {
+                    panic_with_error!(env, RebalancerError::InvalidAmount);
+                }
+                if Self::get_limits(env.clone()).is_none_or(|l| l.cooldown_ledgers == 0) {
+                    panic_with_error!(env, RebalancerError::BountyWithoutCooldown);
+                }
+                for policy in Self::wallets(env.clone()).values().iter() {
+                    Self::check_bounty_margin(&env, &bounty, &policy);
+                }
+                env.storage()
+                    .instance()
+                    .set(&DataKey::KeeperBounty, &bounty);
+            }
+            None => env.storage().instance().remove(&DataKey::KeeperBounty),
+        }
+    }
+
+    pub fn get_keeper_bounty(env: Env) -> Option<KeeperBounty> {
+        env.storage().instance().get(&DataKey::KeeperBounty)
+    }
+
+    /// Configures the rolling payout-volume window. Admin only.
+    pub fn set_volume_window(env: Env, admin: Address, window: VolumeWindow) {
+        admin::require_admin_caller(&env, &admin);
+        if window.bucket_ledgers == 0 || window.buckets == 0 {
+            panic_with_error!(env, RebalancerError::InvalidVolumeWindow);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::VolumeWindow, &window);
+    }
+
+    pub fn get_volume_window(env: Env) -> Option<VolumeWindow> {
+        env.storage().instance().get(&DataKey::VolumeWindow)
+    }
+
+    /// Adds a payout to the rolling volume counter. Admin or operator only.
+    pub fn record_payout(env: Env, caller: Address, amount: i128) {
+        pausable::require_not_paused(&env);
+        rbac::require_admin_or_role(&env, Role::Operator, &caller);
+        if amount <= 0 {
+            panic_with_error!(env, RebalancerError::InvalidAmount);
+        }
+        let window = Self::get_volume_window(env.clone())
+            .unwrap_or_else(|| panic_with_error!(env, RebalancerError::VolumeWindowNotConfigured));
+
+        let current = env.ledger().sequence() / window.bucket_ledgers;
+        let mut volume = Self::live_buckets(&env, &window);
+        volume.set(current, volume.get(current).unwrap_or(0) + amount);
+        env.storage().instance().set(&DataKey::Volume, &volume);
+    }
+
+    /// Returns the average payout volume per bucket over the window.
+    pub fn average_volume(env: Env) -> i128 {
+        match Self::get_volume_window(env.clone()) {
+            Some(window) => {
+                let total: i128 = Self::live_buckets(&env, &window).values().iter().sum();
+                total / window.buckets as i128
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns `base_target` raised by the configured share of recent
+    /// average payout volume.
+    pub fn computed_target(env: Env, base_target: i128) -> i128 {
+        let multiplier = Self::get_volume_window(env.clone())
+            .map(|window| window.multiplier_bps)
+            .unwrap_or(0);
+        base_target + Self::average_volume(env) * multiplier as i128 / BPS_DENOMINATOR
+    }
+
+    /// Checks if a monitored wallet's XLM balance is below its policy
+    /// threshold. If so, it swaps USDC -> XLM to top it up to the policy
+    /// target, raised by recent payout volume (see `computed_target`), and
+    /// pays `caller` the keeper bounty, if one is set, from the wallet.
+    /// Anyone may call this; nothing is paid when no rebalance is needed.
+    /// The wallet does not sign: the swap and bounty are drawn on the
+    /// allowances it granted this contract.
+    ///
+    /// # Panics
+    /// With `WalletNotRegistered` if `funding_wallet` is not monitored
+    pub fn check_and_rebalance(env: Env, caller: Address, funding_wallet: Address) -> bool {
+        pausable::require_not_paused(&env);
+        caller.require_auth();
+        let policy = Self::wallets(env.clone())
+            .get(funding_wallet.clone())
+            .unwrap_or_else(|| panic_with_error!(env, RebalancerError::WalletNotRegistered));
+        if !Self::rebalance(&env, &funding_wallet, policy.threshold, policy.target) {
+            return false;
+        }
+        if let Some(bounty) = Self::get_keeper_bounty(env.clone()) {
+            let code = match bounty.asset {
+                BountyAsset::Xlm => "XLM",
+                BountyAsset::Usdc => "USDC",
+            };
+            let asset = assets::require_asset(&env, String::from_str(&env, code));
+            token::Client::new(&env, &asset.token).transfer_from(
+                &env.current_contract_address(),
+                &funding_wallet,
+                &caller,
+                &bounty.amount,
+            );
+            events::emit_keeper_bounty_paid(
+                &env,
+                &funding_wallet,
+                &caller,
+                &asset.token,
+                bounty.amount,
+            );
+        }
+        true
+    }
+
+    /// Tops `funding_wallet` up to `target` if it is below `threshold`.
+    fn rebalance(env: &Env, funding_wallet: &Address, threshold: i128, target: i128) -> bool {
+        // 1. Check current balance
+        let xlm = assets::require_asset(env, String::from_str(env, "XLM"));
+        let current_balance = token::Client::new(env, &xlm.token).balance(funding_wallet);
 
         // 2. Evaluate Threshold
         if current_balance >= threshold {
             return false; // No rebalance needed
         }
 
-        // 3. Calculate needed amount
-        let needed = target - current_balance;
+        // 3. Calculate needed amount and its USDC cost at the oracle price
+        let needed = Self::computed_target(env.clone(), target) - current_balance;
+        let usdc = assets::require_asset(env, String::from_str(env, "USDC"));
+        let config = Self::get_oracle(env.clone())
+            .unwrap_or_else(|| panic_with_error!(env, RebalancerError::OracleNotConfigured));
+        let xlm_price = Self::price(env, &config, &xlm.token);
+        let usdc_price = Self::price(env, &config, &usdc.token);
+        // Round up so the swap is never sized short of the shortfall.
+        let cost = (needed * xlm_price + usdc_price - 1) / usdc_price;
+        let max_cost = cost * (BPS_DENOMINATOR + config.max_slippage_bps as i128) / BPS_DENOMINATOR;
+        if token::Client::new(env, &usdc.token).balance(funding_wallet) < max_cost {
+            panic_with_error!(env, RebalancerError::InsufficientUsdc);
+        }
+        let limits = Self::get_limits(env.clone());
+        if let Some(limits) = &limits {
+            Self::enforce_limits(env, limits, funding_wallet, max_cost);
+        }
 
-        // 4. Simulate Swap (USDC -> XLM)
-        // In reality: path_payment_strict_receive or manage_buy_offer
-        Self::execute_swap(&env, &funding_wallet, needed);
+        // 4. Swap USDC -> XLM, spending at most `max_cost`
+        let spent = Self::execute_swap(
+            env,
+            funding_wallet,
+            &usdc.token,
+            &xlm.token,
+            needed,
+            max_cost,
+        );
+        if limits.is_some() {
+            Self::record_swap(env, funding_wallet, spent);
+        }
 
         true
     }
 
-    /// Internal helper to simulate the DEX swap execution.
-    fn execute_swap(env: &Env, wallet: &Address, amount_xlm: i128) {
-        wallet.require_auth();
+    /// # Panics
+    /// With `CooldownActive` or `DailyVolumeExceeded` if a swap of up to
+    /// `max_cost` would break `limits`
+    fn enforce_limits(env: &Env, limits: &RebalanceLimits, wallet: &Address, max_cost: i128) {
+        if let Some(last) = Self::last_rebalance(env.clone(), wallet.clone()) {
+            if env.ledger().sequence() < last + limits.cooldown_ledgers {
+                panic_with_error!(env, RebalancerError::CooldownActive);
+            }
+        }
+        if Self::volume_today(env).amount + max_cost > limits.daily_max_volume {
+            panic_with_error!(env, RebalancerError::DailyVolumeExceeded);
+        }
+    }
+
+    /// Panics with `BountyTooLarge` if an XLM `bounty` would take back the
+    /// margin a top-up under `policy` leaves above its threshold.
+    fn check_bounty_margin(env: &Env, bounty: &KeeperBounty, policy: &WalletPolicy) {
+        if bounty.asset == BountyAsset::Xlm && bounty.amount >= policy.target - policy.threshold {
+            panic_with_error!(env, RebalancerError::BountyTooLarge);
+        }
+    }
+
+    /// Starts `wallet`'s cooldown and adds `spent` to today's volume.
+    fn record_swap(env: &Env, wallet: &Address, spent: i128) {
+        env.storage().persistent().set(
+            &DataKey::LastRebalance(wallet.clone()),
+            &env.ledger().sequence(),
+        );
+        let mut volume = Self::volume_today(env);
+        volume.amount += spent;
+        env.storage().instance().set(&DataKey::DailyVolume, &volume);
+    }
+
+    /// Loads today's swap volume, resetting it if it belongs to an earlier day.
+    fn volume_today(env: &Env) -> DailyVolume {
+        let day = env.ledger().sequence() / LEDGERS_PER_DAY;
+        env.storage()
+            .instance()
+            .get::<DataKey, DailyVolume>(&DataKey::DailyVolume)
+            .filter(|volume| volume.day == day)
+            .unwrap_or(DailyVolume { day, amount: 0 })
+    }
+
+    /// Reads a fresh price for `token` from the feed.
+    fn price(env: &Env, config: &OracleConfig, token: &Address) -> i128 {
+        let quote = PriceOracleClient::new(env, &config.oracle)
+            .lastprice(&OracleAsset::Stellar(token.clone()))
+            .unwrap_or_else(|| panic_with_error!(env, RebalancerError::NoPrice));
+        if quote.price <= 0 {
+            panic_with_error!(env, RebalancerError::InvalidPrice);
+        }
+        if env.ledger().timestamp().saturating_sub(quote.timestamp) > config.max_price_age {
+            panic_with_error!(env, RebalancerError::StalePrice);
+        }
+        quote.price
+    }
+
+    /// Loads the volume buckets still inside the window.
+    fn live_buckets(env: &Env, window: &VolumeWindow) -> Map<u32, i128> {
+        let current = env.ledger().sequence() / window.bucket_ledgers;
+        let oldest = (current + 1).saturating_sub(window.buckets);
+        let mut volume: Map<u32, i128> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Volume)
+            .unwrap_or_else(|| Map::new(env));
+        for bucket in volume.keys().iter() {
+            if bucket < oldest {
+                volume.remove(bucket);
+            }
+        }
+        volume
+    }
+
+    /// Buys exactly `amount_xlm` for `wallet` through the router and
+    /// returns the USDC spent. Up to `max_cost` is drawn on the wallet's
+    /// USDC allowance and swapped by this contract; the XLM bought and any
+    /// unspent USDC go back to the wallet.
+    ///
+    /// # Panics
+    /// If the router delivers less than `amount_xlm` or charges more than
+    /// `max_cost`, reverting the whole rebalance
+    fn execute_swap(
+        env: &Env,
+        wallet: &Address,
+        usdc: &Address,
+        xlm: &Address,
+        amount_xlm: i128,
+        max_cost: i128,
+    ) -> i128 {
+        let router = Self::get_router(env.clone())
+            .unwrap_or_else(|| panic_with_error!(env, RebalancerError::RouterNotConfigured));
+        let this = env.current_contract_address();
+        let usdc_client = token::Client::new(env, usdc);
+        let xlm_client = token::Client::new(env, xlm);
+        usdc_client.transfer_from(&this, wallet, &this, &max_cost);
+        let usdc_before = usdc_client.balance(&this);
+        let xlm_before = xlm_client.balance(&this);
+
+        usdc_client.approve(&this, &router, &max_cost, &env.ledger().sequence());
+        RouterClient::new(env, &router).swap_tokens_for_exact_tokens(
+            &amount_xlm,
+            &max_cost,
+            &Vec::from_array(env, [usdc.clone(), xlm.clone()]),
+            &this,
+            &env.ledger().timestamp(),
+        );
+
+        // Trust balances, not the router's reported amounts.
+        let received = xlm_client.balance(&this) - xlm_before;
+        let spent = usdc_before - usdc_client.balance(&this);
+        if received < amount_xlm {
+            panic_with_error!(env, RebalancerError::SwapUnderdelivered);
+        }
+        if spent > max_cost {
+            panic_with_error!(env, RebalancerError::SlippageExceeded);
+        }
+        xlm_client.transfer(&this, wallet, &received);
+        if spent < max_cost {
+            usdc_client.transfer(&this, wallet, &(max_cost - spent));
+        }
 
         // Log the rebalancing action
-        env.events().publish(
-            (String::from_str(env, "rebalance_executed"), wallet.clone()),
-            amount_xlm,
+        events::emit_rebalance_executed(env, wallet, received, spent, max_cost);
+        spent
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::errors::CommonError;
+    use crate::events::{KeeperBountyPaidEvent, RebalanceExecutedEvent, EVENT_VERSION};
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+        token::StellarAssetClient,
+        vec, Address, Env, IntoVal, Symbol, Val, Vec,
+    };
+
+    mod oracle {
+        use super::super::{OracleAsset, PriceData};
+        use soroban_sdk::{contract, contractimpl, Env};
+
+        /// Minimal price feed returning whatever was last set per asset.
+        #[contract]
+        pub struct MockOracle;
+
+        #[contractimpl]
+        impl MockOracle {
+            pub fn set_price(env: Env, asset: OracleAsset, price: i128, timestamp: u64) {
+                env.storage()
+                    .instance()
+                    .set(&asset, &PriceData { price, timestamp });
+            }
+
+            pub fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData> {
+                env.storage().instance().get(&asset)
+            }
+        }
+    }
+
+    mod router {
+        use soroban_sdk::{contract, contractimpl, token, Address, Env, Vec};
+
+        /// AMM stand-in that charges `amount_in_max` and delivers
+        /// `amount_out` scaled by the configured fill rate.
+        #[contract]
+        pub struct MockRouter;
+
+        #[contractimpl]
+        impl MockRouter {
+            pub fn set_fill_bps(env: Env, fill_bps: i128) {
+                env.storage().instance().set(&0u32, &fill_bps);
+            }
+
+            pub fn swap_tokens_for_exact_tokens(
+                env: Env,
+                amount_out: i128,
+                amount_in_max: i128,
+                path: Vec<Address>,
+                to: Address,
+                deadline: u64,
+            ) -> Vec<i128> {
+                to.require_auth();
+                assert!(deadline >= env.ledger().timestamp());
+                let fill_bps: i128 = env.storage().instance().get(&0u32).unwrap_or(10_000);
+                let delivered = amount_out * fill_bps / 10_000;
+                let this = env.current_contract_address();
+                token::Client::new(&env, &path.first().unwrap()).transfer_from(
+                    &this,
+                    &to,
+                    &this,
+                    &amount_in_max,
+                );
+                token::Client::new(&env, &path.last().unwrap()).transfer(&this, &to, &delivered);
+                Vec::from_array(&env, [amount_in_max, delivered])
+            }
+        }
+    }
+
+    use oracle::{MockOracle, MockOracleClient};
+    use router::{MockRouter, MockRouterClient};
+
+    const NOW: u64 = 1_000_000;
+
+    struct Setup<'a> {
+        client: LiquidityRebalancerClient<'a>,
+        oracle: MockOracleClient<'a>,
+        router: MockRouterClient<'a>,
+        admin: Address,
+        keeper: Address,
+        wallet: Address,
+        xlm: Address,
+        usdc: Address,
+    }
+
+    /// Registers XLM and USDC, prices XLM at $0.10 with a 1% slippage cap,
+    /// funds the wallet with 15 XLM and 100 USDC and a router with 1,000 XLM,
+    /// and monitors the wallet with a 20 XLM threshold and 50 XLM target.
+    /// The wallet approves the rebalancer to spend both assets.
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+        // Keep entries alive across the day-long ledger jumps below.
+        env.ledger().with_mut(|l| {
+            l.timestamp = NOW;
+            l.min_persistent_entry_ttl = 2 * LEDGERS_PER_DAY;
+            l.min_temp_entry_ttl = 2 * LEDGERS_PER_DAY;
+            l.max_entry_ttl = 4 * LEDGERS_PER_DAY;
+        });
+        let contract_id = env.register_contract(None, LiquidityRebalancer);
+        let client = LiquidityRebalancerClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+        client.initialize(&admin);
+
+        let wallet = Address::generate(env);
+        let mut tokens = Vec::new(env);
+        for (code, amount) in [("XLM", 15_0000000), ("USDC", 100_0000000)] {
+            let issuer = Address::generate(env);
+            let token = env
+                .register_stellar_asset_contract_v2(issuer.clone())
+                .address();
+            StellarAssetClient::new(env, &token).mint(&wallet, &amount);
+            token::Client::new(env, &token).approve(
+                &wallet,
+                &contract_id,
+                &amount,
+                &(env.ledger().sequence() + 2 * LEDGERS_PER_DAY),
+            );
+            client.register_asset(
+                &admin,
+                &String::from_str(env, code),
+                &AssetInfo {
+                    issuer,
+                    token: token.clone(),
+                },
+            );
+            tokens.push_back(token);
+        }
+        let (xlm, usdc) = (tokens.get(0).unwrap(), tokens.get(1).unwrap());
+
+        let oracle = MockOracleClient::new(env, &env.register_contract(None, MockOracle));
+        oracle.set_price(
+            &OracleAsset::Stellar(xlm.clone()),
+            &10_000_000_000_000,
+            &NOW,
+        );
+        oracle.set_price(
+            &OracleAsset::Stellar(usdc.clone()),
+            &100_000_000_000_000,
+            &NOW,
+        );
+        client.set_oracle(
+            &admin,
+            &OracleConfig {
+                oracle: oracle.address.clone(),
+                max_slippage_bps: 100,
+                max_price_age: 300,
+            },
+        );
+
+        let router = MockRouterClient::new(env, &env.register_contract(None, MockRouter));
+        StellarAssetClient::new(env, &xlm).mint(&router.address, &1000_0000000);
+        client.set_router(&admin, &router.address);
+        let keeper = Address::generate(env);
+        client.grant_role(&admin, &Role::Keeper, &keeper);
+        client.register_wallet(
+            &admin,
+            &wallet,
+            &WalletPolicy {
+                threshold: 20_0000000,
+                target: 50_0000000,
+            },
+        );
+
+        Setup {
+            client,
+            oracle,
+            router,
+            admin,
+            keeper,
+            wallet,
+            xlm,
+            usdc,
+        }
+    }
+
+    fn set_policy(s: &Setup, threshold: i128, target: i128) {
+        s.client
+            .register_wallet(&s.admin, &s.wallet, &WalletPolicy { threshold, target });
+    }
+
+    fn last_event(env: &Env) -> Vec<(Address, Vec<Val>, Val)> {
+        let all = env.events().all();
+        all.slice(all.len() - 1..)
+    }
+
+    #[test]
+    fn test_pause_blocks_rebalance() {
+        let env = Env::default();
+        let s = setup(&env);
+
+        s.client.pause(&s.admin);
+        assert!(s
+            .client
+            .try_check_and_rebalance(&s.keeper, &s.wallet)
+            .is_err());
+
+        s.client.unpause(&s.admin);
+        assert!(s.client.check_and_rebalance(&s.keeper, &s.wallet));
+    }
+
+    #[test]
+    fn test_rebalance_reads_real_balance() {
+        let env = Env::default();
+        let s = setup(&env);
+
+        // 15 XLM is above a 10 XLM threshold: nothing to do.
+        set_policy(&s, 10_0000000, 50_0000000);
+        assert!(!s.client.check_and_rebalance(&s.keeper, &s.wallet));
+
+        StellarAssetClient::new(&env, &s.xlm).mint(&s.wallet, &10_0000000);
+        set_policy(&s, 20_0000000, 50_0000000);
+        assert!(!s.client.check_and_rebalance(&s.keeper, &s.wallet));
+    }
+
+    #[test]
+    fn test_swap_sized_from_oracle_price() {
+        let env = Env::default();
+        let s = setup(&env);
+
+        assert!(s.client.check_and_rebalance(&s.keeper, &s.wallet));
+        // 35 XLM short at $0.10 costs 3.5 USDC, plus at most 1% slippage,
+        // all of which the mock router charges.
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    s.client.address.clone(),
+                    (Symbol::new(&env, "rebalance_executed"), s.wallet.clone()).into_val(&env),
+                    RebalanceExecutedEvent {
+                        amount: 35_0000000,
+                        source_amount: 3_5350000,
+                        max_source_amount: 3_5350000,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rebalance_rejects_stale_or_missing_price() {
+        let env = Env::default();
+        let s = setup(&env);
+
+        env.ledger().with_mut(|l| l.timestamp = NOW + 301);
+        assert!(s
+            .client
+            .try_check_and_rebalance(&s.keeper, &s.wallet)
+            .is_err());
+
+        s.oracle.set_price(
+            &OracleAsset::Stellar(s.xlm.clone()),
+            &10_000_000_000_000,
+            &(NOW + 301),
+        );
+        s.oracle.set_price(
+            &OracleAsset::Stellar(s.usdc.clone()),
+            &100_000_000_000_000,
+            &(NOW + 301),
         );
+        assert!(s.client.check_and_rebalance(&s.keeper, &s.wallet));
+
+        let unpriced = Address::generate(&env);
+        s.client.register_asset(
+            &s.admin,
+            &String::from_str(&env, "USDC"),
+            &AssetInfo {
+                issuer: Address::generate(&env),
+                token: unpriced,
+            },
+        );
+        set_policy(&s, 60_0000000, 80_0000000);
+        assert!(s
+            .client
+            .try_check_and_rebalance(&s.keeper, &s.wallet)
+            .is_err());
+    }
+
+    #[test]
+    fn test_rebalance_requires_usdc_for_slippage() {
+        let env = Env::default();
+        let s = setup(&env);
+
+        // XLM at $3.00 makes 35 XLM cost 105 USDC, above the wallet's 100.
+        s.oracle.set_price(
+            &OracleAsset::Stellar(s.xlm.clone()),
+            &300_000_000_000_000,
+            &NOW,
+        );
+        assert!(s
+            .client
+            .try_check_and_rebalance(&s.keeper, &s.wallet)
+            .is_err());
+
+        // At $2.00 it costs 70 USDC, 70.7 with slippage.
+        s.oracle.set_price(
+            &OracleAsset::Stellar(s.xlm.clone()),
+            &200_000_000_000_000,
+            &NOW,
+        );
+        assert!(s.client.check_and_rebalance(&s.keeper, &s.wallet));
+        assert!(s
+            .client
+            .try_set_oracle(
+                &s.admin,
+                &OracleConfig {
+                    oracle: s.oracle.address.clone(),
+                    max_slippage_bps: 10_001,
+                    max_price_age: 300,
+                },
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_swap_moves_funds_through_router() {
+        let env = Env::default();
+        let s = setup(&env);
+        let xlm = token::Client::new(&env, &s.xlm);
+        let usdc = token::Client::new(&env, &s.usdc);
+
+        assert!(s.client.check_and_rebalance(&s.keeper, &s.wallet));
+        assert_eq!(xlm.balance(&s.wallet), 50_0000000);
+        assert_eq!(usdc.balance(&s.wallet), 100_0000000 - 3_5350000);
+        assert_eq!(usdc.balance(&s.router.address), 3_5350000);
+    }
+
+    #[test]
+    fn test_underdelivered_swap_reverts_rebalance() {
+        let env = Env::default();
+        let s = setup(&env);
+        s.router.set_fill_bps(&9_999);
+
+        assert!(s
+            .client
+            .try_check_and_rebalance(&s.keeper, &s.wallet)
+            .is_err());
+        assert_eq!(
+            token::Client::new(&env, &s.xlm).balance(&s.wallet),
+            15_0000000
+        );
+        assert_eq!(
+            token::Client::new(&env, &s.usdc).balance(&s.wallet),
+            100_0000000
+        );
+    }
+
+    #[test]
+    fn test_cooldown_blocks_repeat_rebalance() {
+        let env = Env::default();
+        let s = setup(&env);
+        s.client.set_limits(
+            &s.admin,
+            &RebalanceLimits {
+                cooldown_ledgers: 100,
+                daily_max_volume: 100_0000000,
+            },
+        );
+        let xlm = token::Client::new(&env, &s.xlm);
+
+        assert!(s.client.check_and_rebalance(&s.keeper, &s.wallet));
+        assert_eq!(
+            s.client.last_rebalance(&s.wallet),
+            Some(env.ledger().sequence())
+        );
+
+        // Drain the wallet again: the swap is refused until the cooldown ends.
+        xlm.transfer(&s.wallet, &s.router.address, &40_0000000);
+        assert_eq!(
+            s.client.try_check_and_rebalance(&s.keeper, &s.wallet),
+            Err(Ok(RebalancerError::CooldownActive.into()))
+        );
+        env.ledger().with_mut(|l| l.sequence_number += 100);
+        assert!(s.client.check_and_rebalance(&s.keeper, &s.wallet));
+    }
+
+    #[test]
+    fn test_daily_volume_cap() {
+        let env = Env::default();
+        let s = setup(&env);
+        s.client.set_limits(
+            &s.admin,
+            &RebalanceLimits {
+                cooldown_ledgers: 0,
+                daily_max_volume: 5_0000000,
+            },
+        );
+        let xlm = token::Client::new(&env, &s.xlm);
+
+        // The first swap spends 3.535 USDC of the 5 USDC allowance.
+        assert!(s.client.check_and_rebalance(&s.keeper, &s.wallet));
+        assert_eq!(s.client.daily_volume(), 3_5350000);
+
+        xlm.transfer(&s.wallet, &s.router.address, &35_0000000);
+        assert_eq!(
+            s.client.try_check_and_rebalance(&s.keeper, &s.wallet),
+            Err(Ok(RebalancerError::DailyVolumeExceeded.into()))
+        );
+
+        // The allowance resets on the next ledger-day.
+        env.ledger()
+            .with_mut(|l| l.sequence_number += LEDGERS_PER_DAY);
+        assert_eq!(s.client.daily_volume(), 0);
+        assert!(s.client.check_and_rebalance(&s.keeper, &s.wallet));
+    }
+
+    #[test]
+    fn test_anyone_triggers_rebalance_for_bounty() {
+        let env = Env::default();
+        let s = setup(&env);
+        let stranger = Address::generate(&env);
+        let usdc = token::Client::new(&env, &s.usdc);
+        let bounty = KeeperBounty {
+            asset: BountyAsset::Usdc,
+            amount: 1_0000000,
+        };
+        // Without a cooldown a bounty could be farmed on every call.
+        assert_eq!(
+            s.client
+                .try_set_keeper_bounty(&s.admin, &Some(bounty.clone())),
+            Err(Ok(RebalancerError::BountyWithoutCooldown.into()))
+        );
+        s.client.set_limits(
+            &s.admin,
+            &RebalanceLimits {
+                cooldown_ledgers: 100,
+                daily_max_volume: 100_0000000,
+            },
+        );
+        assert_eq!(
+            s.client.try_set_keeper_bounty(
+                &s.admin,
+                &Some(KeeperBounty {
+                    asset: BountyAsset::Usdc,
+                    amount: 0,
+                }),
+            ),
+            Err(Ok(RebalancerError::InvalidAmount.into()))
+        );
+        // An XLM bounty of the 30 XLM margin would undo the top-up.
+        assert_eq!(
+            s.client.try_set_keeper_bounty(
+                &s.admin,
+                &Some(KeeperBounty {
+                    asset: BountyAsset::Xlm,
+                    amount: 30_0000000,
+                }),
+            ),
+            Err(Ok(RebalancerError::BountyTooLarge.into()))
+        );
+        s.client.set_keeper_bounty(&s.admin, &Some(bounty));
+        assert_eq!(
+            s.client.try_set_limits(
+                &s.admin,
+                &RebalanceLimits {
+                    cooldown_ledgers: 0,
+                    daily_max_volume: 100_0000000,
+                },
+            ),
+            Err(Ok(RebalancerError::BountyWithoutCooldown.into()))
+        );
+
+        assert_eq!(
+            s.client
+                .try_check_and_rebalance(&stranger, &Address::generate(&env)),
+            Err(Ok(RebalancerError::WalletNotRegistered.into()))
+        );
+        // Only the stranger signs; the wallet's allowances cover the rest.
+        env.mock_auths(&[MockAuth {
+            address: &stranger,
+            invoke: &MockAuthInvoke {
+                contract: &s.client.address,
+                fn_name: "check_and_rebalance",
+                args: (stranger.clone(), s.wallet.clone()).into_val(&env),
+                sub_invokes: &[],
+            },
+        }]);
+        assert!(s.client.check_and_rebalance(&stranger, &s.wallet));
+        assert_eq!(env.auths().len(), 1);
+        assert_eq!(
+            token::Client::new(&env, &s.xlm).balance(&s.wallet),
+            50_0000000
+        );
+        assert_eq!(usdc.balance(&stranger), 1_0000000);
+        assert_eq!(usdc.balance(&s.wallet), 100_0000000 - 3_5350000 - 1_0000000);
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    s.client.address.clone(),
+                    (Symbol::new(&env, "keeper_bounty_paid"), s.wallet.clone()).into_val(&env),
+                    KeeperBountyPaidEvent {
+                        keeper: stranger.clone(),
+                        token: s.usdc.clone(),
+                        amount: 1_0000000,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+
+        // Nothing to top up, nothing paid.
+        env.mock_all_auths();
+        assert!(!s.client.check_and_rebalance(&stranger, &s.wallet));
+        // The cooldown stops the wallet being drained and re-triggered.
+        token::Client::new(&env, &s.xlm).transfer(&s.wallet, &s.router.address, &40_0000000);
+        assert_eq!(
+            s.client.try_check_and_rebalance(&stranger, &s.wallet),
+            Err(Ok(RebalancerError::CooldownActive.into()))
+        );
+        assert_eq!(usdc.balance(&stranger), 1_0000000);
+
+        // Batch top-ups stay with keepers.
+        assert_eq!(
+            s.client.try_rebalance_all(&stranger),
+            Err(Ok(CommonError::MissingRole.into()))
+        );
+    }
+
+    #[test]
+    fn test_rebalance_all_tops_up_registered_wallets() {
+        let env = Env::default();
+        let s = setup(&env);
+        let policy = WalletPolicy {
+            threshold: 20_0000000,
+            target: 50_0000000,
+        };
+        let healthy = Address::generate(&env);
+        StellarAssetClient::new(&env, &s.xlm).mint(&healthy, &30_0000000);
+        let other = Address::generate(&env);
+        s.client.register_wallet(&s.admin, &s.wallet, &policy);
+        s.client.register_wallet(&s.admin, &healthy, &policy);
+        s.client.register_wallet(&s.admin, &other, &policy);
+        assert_eq!(s.client.wallets().len(), 3);
+
+        // `other` holds no USDC to swap from, so it must be dropped first.
+        assert!(s.client.try_rebalance_all(&s.keeper).is_err());
+        s.client.remove_wallet(&s.admin, &other);
+        assert!(s.client.try_remove_wallet(&s.admin, &other).is_err());
+
+        assert_eq!(
+            s.client.rebalance_all(&s.keeper),
+            vec![&env, s.wallet.clone()]
+        );
+
+        // A laxer policy leaves the 15 XLM wallet alone.
+        s.client.register_wallet(
+            &s.admin,
+            &s.wallet,
+            &WalletPolicy {
+                threshold: 10_0000000,
+                target: 50_0000000,
+            },
+        );
+        assert!(s.client.rebalance_all(&s.keeper).is_empty());
+        assert!(s
+            .client
+            .try_register_wallet(
+                &s.admin,
+                &other,
+                &WalletPolicy {
+                    threshold: 20_0000000,
+                    target: 10_0000000,
+                },
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_recent_volume_raises_target() {
+        let env = Env::default();
+        let s = setup(&env);
+        let client = &s.client;
+        client.set_volume_window(
+            &s.admin,
+            &VolumeWindow {
+                bucket_ledgers: 100,
+                buckets: 4,
+                multiplier_bps: 5_000,
+            },
+        );
+
+        let base = 50_0000000;
+        assert_eq!(client.computed_target(&base), base);
+
+        client.record_payout(&s.admin, &20_0000000);
+        env.ledger().with_mut(|l| l.sequence_number += 100);
+        client.record_payout(&s.admin, &60_0000000);
+        // (20 + 60) / 4 buckets = 20 XLM average, half of which is added.
+        assert_eq!(client.average_volume(), 20_0000000);
+        assert_eq!(client.computed_target(&base), 60_0000000);
+
+        client.check_and_rebalance(&s.keeper, &s.wallet);
+        // Topped up from the wallet's 15 XLM balance to the raised 60 XLM target.
+        assert_eq!(
+            last_event(&env),
+            vec![
+                &env,
+                (
+                    client.address.clone(),
+                    (Symbol::new(&env, "rebalance_executed"), s.wallet.clone()).into_val(&env),
+                    RebalanceExecutedEvent {
+                        amount: 45_0000000,
+                        source_amount: 4_5450000,
+                        max_source_amount: 4_5450000,
+                        timestamp: env.ledger().timestamp(),
+                        version: EVENT_VERSION,
+                    }
+                    .into_val(&env),
+                )
+            ]
+        );
+
+        // Volume older than the window no longer counts.
+        env.ledger().with_mut(|l| l.sequence_number += 400);
+        assert_eq!(client.computed_target(&base), base);
     }
 }
@@ -0,0 +1,450 @@
+//! Retainer-style recurring payments.
+//!
+//! A client deposits `amount * occurrences` up front and the freelancer
+//! collects `amount` once per `interval_ledgers`. Missed periods accumulate,
+//! so a late `collect` pulls everything due so far. The client can cancel at
+//! any time: whatever is already due still goes to the freelancer and the
+//! unspent balance is refunded.
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, Env,
+    String, Vec,
+};
+
+use crate::admin::{self, KeyRole, Roles};
+use crate::assets::{self, AssetInfo};
+use crate::rbac::{self, Role};
+use crate::{events, pausable, ttl};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum AgreementStatus {
+    /// Payments are still being collected
+    Active = 0,
+    /// Every occurrence has been paid out
+    Completed = 1,
+    /// The client cancelled and was refunded the unspent balance
+    Cancelled = 2,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Agreement {
+    pub client: Address,
+    pub freelancer: Address,
+    pub token: Address,
+    /// Paid out per interval
+    pub amount: i128,
+    pub interval_ledgers: u32,
+    /// Total number of payments
+    pub occurrences: u32,
+    /// Payments collected so far
+    pub collected: u32,
+    /// Ledger the first interval starts from
+    pub start_ledger: u32,
+    pub status: AgreementStatus,
+}
+
+/// Errors raised by the recurring payments contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RecurringError {
+    /// No agreement with this id
+    AgreementNotFound = 100,
+    /// The amount, interval or number of occurrences is not positive
+    InvalidTerms = 101,
+    /// The token is not a supported asset
+    UnsupportedToken = 102,
+    /// The agreement is completed or cancelled
+    NotActive = 103,
+    /// No payment has come due since the last collection
+    NothingDue = 104,
+    /// The caller is neither the freelancer nor a keeper
+    NotCollector = 105,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    AgreementCount,
+    Agreement(u64),
+}
+
+#[contract]
+pub struct RecurringPayments;
+
+#[contractimpl]
+impl RecurringPayments {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Assigns a key role.
+    pub fn set_role(env: Env, role: KeyRole, address: Address) {
+        admin::set_role(&env, role, &address);
+    }
+
+    /// Returns the admin and every key role in a single read.
+    pub fn get_roles(env: Env) -> Roles {
+        admin::get_roles(&env)
+    }
+
+    /// Grants `role` to `account`. `caller` must be the role admin.
+    pub fn grant_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::grant_role(&env, &caller, role, &account);
+    }
+
+    /// Revokes `role` from `account`. `caller` must be the role admin.
+    pub fn revoke_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::revoke_role(&env, &caller, role, &account);
+    }
+
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        rbac::has_role(&env, role, &account)
+    }
+
+    /// Pauses deposits, collections and cancellations. Admin or guardian only.
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause. Admin or guardian only.
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Registers (or re-points) a supported asset. Admin or operator only.
+    pub fn register_asset(env: Env, caller: Address, asset_code: String, asset: AssetInfo) {
+        assets::register_asset(&env, &caller, asset_code, asset);
+    }
+
+    /// Returns every supported `(code, asset)`, ordered by code.
+    pub fn supported_assets(env: Env) -> Vec<(String, AssetInfo)> {
+        assets::supported_assets(&env)
+    }
+
+    /// Creates an agreement and deposits every payment from the client.
+    /// The first payment comes due one interval after creation.
+    ///
+    /// # Returns
+    /// The new agreement's id
+    ///
+    /// # Panics
+    /// If any term is not positive or the token is not a supported asset
+    pub fn create_agreement(
+        env: Env,
+        client: Address,
+        freelancer: Address,
+        token: Address,
+        amount: i128,
+        interval_ledgers: u32,
+        occurrences: u32,
+    ) -> u64 {
+        pausable::require_not_paused(&env);
+        client.require_auth();
+
+        if amount <= 0 || interval_ledgers == 0 || occurrences == 0 {
+            panic_with_error!(env, RecurringError::InvalidTerms);
+        }
+        if !assets::is_empty(&env) && !assets::is_supported_token(&env, &token) {
+            panic_with_error!(env, RecurringError::UnsupportedToken);
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &client,
+            &env.current_contract_address(),
+            &(amount * occurrences as i128),
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AgreementCount)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::AgreementCount, &(id + 1));
+        Self::save(
+            &env,
+            id,
+            &Agreement {
+                client,
+                freelancer,
+                token,
+                amount,
+                interval_ledgers,
+                occurrences,
+                collected: 0,
+                start_ledger: env.ledger().sequence(),
+                status: AgreementStatus::Active,
+            },
+        );
+        id
+    }
+
+    /// Pays the freelancer every payment that has come due since the last
+    /// collection. `caller` must be the freelancer or a keeper; the funds
+    /// always go to the freelancer.
+    ///
+    /// # Returns
+    /// The amount paid out
+    ///
+    /// # Panics
+    /// If the agreement is not active, `caller` may not collect, or nothing
+    /// is due
+    pub fn collect(env: Env, agreement_id: u64, caller: Address) -> i128 {
+        pausable::require_not_paused(&env);
+        caller.require_auth();
+
+        let mut agreement = Self::load(&env, agreement_id);
+        if caller != agreement.freelancer && !rbac::has_role(&env, Role::Keeper, &caller) {
+            panic_with_error!(env, RecurringError::NotCollector);
+        }
+        if agreement.status != AgreementStatus::Active {
+            panic_with_error!(env, RecurringError::NotActive);
+        }
+        let due = Self::due_occurrences(&env, &agreement);
+        if due == 0 {
+            panic_with_error!(env, RecurringError::NothingDue);
+        }
+
+        let payout = Self::pay_due(&env, &mut agreement, due);
+        if agreement.collected == agreement.occurrences {
+            agreement.status = AgreementStatus::Completed;
+        }
+        Self::save(&env, agreement_id, &agreement);
+        events::emit_recurring_collected(&env, agreement_id, payout, agreement.collected);
+        payout
+    }
+
+    /// Ends an agreement. Payments already due still go to the freelancer;
+    /// the rest of the deposit is refunded to the client. Requires the
+    /// client's auth.
+    ///
+    /// # Returns
+    /// The amount refunded
+    ///
+    /// # Panics
+    /// If the agreement is not active
+    pub fn cancel(env: Env, agreement_id: u64) -> i128 {
+        pausable::require_not_paused(&env);
+        let mut agreement = Self::load(&env, agreement_id);
+        agreement.client.require_auth();
+        if agreement.status != AgreementStatus::Active {
+            panic_with_error!(env, RecurringError::NotActive);
+        }
+
+        let due = Self::due_occurrences(&env, &agreement);
+        Self::pay_due(&env, &mut agreement, due);
+        let refund = agreement.amount * (agreement.occurrences - agreement.collected) as i128;
+        if refund > 0 {
+            token::Client::new(&env, &agreement.token).transfer(
+                &env.current_contract_address(),
+                &agreement.client,
+                &refund,
+            );
+        }
+
+        agreement.status = AgreementStatus::Cancelled;
+        Self::save(&env, agreement_id, &agreement);
+        events::emit_agreement_cancelled(&env, agreement_id, refund);
+        refund
+    }
+
+    /// Returns what `collect` would pay out right now.
+    pub fn due_amount(env: Env, agreement_id: u64) -> i128 {
+        let agreement = Self::load(&env, agreement_id);
+        if agreement.status != AgreementStatus::Active {
+            return 0;
+        }
+        agreement.amount * Self::due_occurrences(&env, &agreement) as i128
+    }
+
+    /// # Panics
+    /// If the agreement does not exist
+    pub fn get_agreement(env: Env, agreement_id: u64) -> Agreement {
+        Self::load(&env, agreement_id)
+    }
+
+    /// Payments that have come due but were not collected yet.
+    fn due_occurrences(env: &Env, agreement: &Agreement) -> u32 {
+        let elapsed = env.ledger().sequence() - agreement.start_ledger;
+        (elapsed / agreement.interval_ledgers).min(agreement.occurrences) - agreement.collected
+    }
+
+    /// Transfers `due` payments to the freelancer and returns the total.
+    fn pay_due(env: &Env, agreement: &mut Agreement, due: u32) -> i128 {
+        let payout = agreement.amount * due as i128;
+        if payout > 0 {
+            token::Client::new(env, &agreement.token).transfer(
+                &env.current_contract_address(),
+                &agreement.freelancer,
+                &payout,
+            );
+        }
+        agreement.collected += due;
+        payout
+    }
+
+    fn load(env: &Env, agreement_id: u64) -> Agreement {
+        ttl::get(env, &DataKey::Agreement(agreement_id))
+            .unwrap_or_else(|| panic_with_error!(env, RecurringError::AgreementNotFound))
+    }
+
+    fn save(env: &Env, agreement_id: u64, agreement: &Agreement) {
+        ttl::set(env, &DataKey::Agreement(agreement_id), agreement);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+    use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+    const INTERVAL: u32 = 100;
+
+    struct Setup<'a> {
+        contract: RecurringPaymentsClient<'a>,
+        token: TokenClient<'a>,
+        admin: Address,
+        client: Address,
+        freelancer: Address,
+    }
+
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+        let contract =
+            RecurringPaymentsClient::new(env, &env.register_contract(None, RecurringPayments));
+        let admin = Address::generate(env);
+        contract.initialize(&admin);
+
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        let client = Address::generate(env);
+        StellarAssetClient::new(env, &token).mint(&client, &10_000);
+        Setup {
+            contract,
+            token: TokenClient::new(env, &token),
+            admin,
+            client,
+            freelancer: Address::generate(env),
+        }
+    }
+
+    fn create(s: &Setup) -> u64 {
+        s.contract.create_agreement(
+            &s.client,
+            &s.freelancer,
+            &s.token.address,
+            &500,
+            &INTERVAL,
+            &3,
+        )
+    }
+
+    fn advance(env: &Env, ledgers: u32) {
+        env.ledger().with_mut(|l| l.sequence_number += ledgers);
+    }
+
+    #[test]
+    fn test_collects_each_period_until_complete() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&s);
+        assert_eq!(s.token.balance(&s.contract.address), 1_500);
+
+        assert_eq!(
+            s.contract.try_collect(&id, &s.freelancer),
+            Err(Ok(RecurringError::NothingDue.into()))
+        );
+
+        advance(&env, INTERVAL);
+        assert_eq!(s.contract.due_amount(&id), 500);
+        assert_eq!(s.contract.collect(&id, &s.freelancer), 500);
+
+        // Missed periods accumulate, capped at the number of occurrences.
+        advance(&env, 5 * INTERVAL);
+        assert_eq!(s.contract.collect(&id, &s.freelancer), 1_000);
+        assert_eq!(s.token.balance(&s.freelancer), 1_500);
+        let agreement = s.contract.get_agreement(&id);
+        assert_eq!(agreement.collected, 3);
+        assert_eq!(agreement.status, AgreementStatus::Completed);
+    }
+
+    #[test]
+    fn test_keeper_collects_for_freelancer() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&s);
+        let keeper = Address::generate(&env);
+        advance(&env, INTERVAL);
+
+        assert_eq!(
+            s.contract.try_collect(&id, &keeper),
+            Err(Ok(RecurringError::NotCollector.into()))
+        );
+        s.contract.grant_role(&s.admin, &Role::Keeper, &keeper);
+        s.contract.collect(&id, &keeper);
+        assert_eq!(s.token.balance(&s.freelancer), 500);
+    }
+
+    #[test]
+    fn test_cancel_pays_due_and_refunds_rest() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&s);
+        advance(&env, INTERVAL + INTERVAL / 2);
+
+        assert_eq!(s.contract.cancel(&id), 1_000);
+        assert_eq!(s.token.balance(&s.freelancer), 500);
+        assert_eq!(s.token.balance(&s.client), 10_000 - 500);
+
+        advance(&env, INTERVAL);
+        assert_eq!(
+            s.contract.try_collect(&id, &s.freelancer),
+            Err(Ok(RecurringError::NotActive.into()))
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_terms() {
+        let env = Env::default();
+        let s = setup(&env);
+        for (amount, interval, occurrences) in [(0, INTERVAL, 3), (500, 0, 3), (500, INTERVAL, 0)] {
+            assert_eq!(
+                s.contract.try_create_agreement(
+                    &s.client,
+                    &s.freelancer,
+                    &s.token.address,
+                    &amount,
+                    &interval,
+                    &occurrences
+                ),
+                Err(Ok(RecurringError::InvalidTerms.into()))
+            );
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! On-chain reputation scores.
+//!
+//! LancePay contracts report the outcome of escrows and disputes here:
+//! completed escrows raise a party's score, lost disputes and missed
+//! deadlines lower it. Only contracts the admin has registered as reporters
+//! may write. Reporting contracts go through [`report`], which discards
+//! failures so a misconfigured reputation contract never blocks a payout.
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, Address, Env,
+};
+
+use crate::admin;
+use crate::{events, ttl};
+
+/// Points added for each completed escrow.
+pub const COMPLETED_POINTS: u32 = 10;
+
+/// Points removed for each lost dispute.
+pub const DISPUTE_LOST_PENALTY: u32 = 15;
+
+/// Points removed for each missed deadline.
+pub const DEADLINE_MISSED_PENALTY: u32 = 5;
+
+/// Outcomes a reporter can record against an address.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Outcome {
+    /// Saw an escrow through to completion
+    EscrowCompleted = 0,
+    /// Was awarded the smaller share of a dispute
+    DisputeLost = 1,
+    /// Let a review or ruling deadline pass
+    DeadlineMissed = 2,
+}
+
+/// An address's score together with the outcomes behind it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct ReputationRecord {
+    /// Never drops below zero
+    pub score: u32,
+    pub escrows_completed: u32,
+    pub disputes_lost: u32,
+    pub deadlines_missed: u32,
+}
+
+/// Errors raised by the reputation contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ReputationError {
+    /// The caller is not a registered reporter
+    NotReporter = 100,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Contract allowed to record outcomes
+    Reporter(Address),
+    Record(Address),
+}
+
+/// Records `outcome` for `subject` with `reputation` on behalf of the
+/// current contract, ignoring any failure.
+pub fn report(env: &Env, reputation: &Address, subject: &Address, outcome: Outcome) {
+    let _ = ReputationClient::new(env, reputation).try_record(
+        &env.current_contract_address(),
+        subject,
+        &outcome,
+    );
+}
+
+#[contract]
+pub struct Reputation;
+
+#[contractimpl]
+impl Reputation {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Allows `reporter` to record outcomes. Admin only.
+    pub fn add_reporter(env: Env, admin: Address, reporter: Address) {
+        admin::require_admin_caller(&env, &admin);
+        ttl::set(&env, &DataKey::Reporter(reporter), &true);
+    }
+
+    /// Stops `reporter` from recording outcomes. Admin only.
+    pub fn remove_reporter(env: Env, admin: Address, reporter: Address) {
+        admin::require_admin_caller(&env, &admin);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Reporter(reporter));
+    }
+
+    /// Returns `true` if `reporter` may record outcomes.
+    pub fn is_reporter(env: Env, reporter: Address) -> bool {
+        ttl::get(&env, &DataKey::Reporter(reporter)).unwrap_or(false)
+    }
+
+    /// Records `outcome` against `subject` and returns the new score.
+    /// Requires `reporter`'s auth, which a contract calling in directly has
+    /// implicitly.
+    ///
+    /// # Panics
+    /// With `NotReporter` if `reporter` is not registered
+    pub fn record(env: Env, reporter: Address, subject: Address, outcome: Outcome) -> u32 {
+        reporter.require_auth();
+        if !Self::is_reporter(env.clone(), reporter.clone()) {
+            panic_with_error!(env, ReputationError::NotReporter);
+        }
+
+        let mut record = Self::get_reputation(env.clone(), subject.clone());
+        match outcome {
+            Outcome::EscrowCompleted => {
+                record.escrows_completed += 1;
+                record.score = record.score.saturating_add(COMPLETED_POINTS);
+            }
+            Outcome::DisputeLost => {
+                record.disputes_lost += 1;
+                record.score = record.score.saturating_sub(DISPUTE_LOST_PENALTY);
+            }
+            Outcome::DeadlineMissed => {
+                record.deadlines_missed += 1;
+                record.score = record.score.saturating_sub(DEADLINE_MISSED_PENALTY);
+            }
+        }
+        ttl::set(&env, &DataKey::Record(subject.clone()), &record);
+        events::emit_reputation_changed(&env, &subject, &reporter, outcome, record.score);
+        record.score
+    }
+
+    /// Returns `subject`'s score (zero for unknown addresses).
+    pub fn score(env: Env, subject: Address) -> u32 {
+        Self::get_reputation(env, subject).score
+    }
+
+    /// Returns `subject`'s score and outcome counts.
+    pub fn get_reputation(env: Env, subject: Address) -> ReputationRecord {
+        ttl::get(&env, &DataKey::Record(subject)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    fn setup(env: &Env) -> (ReputationClient<'_>, Address) {
+        env.mock_all_auths();
+        let reputation = ReputationClient::new(env, &env.register_contract(None, Reputation));
+        let admin = Address::generate(env);
+        reputation.initialize(&admin);
+        (reputation, admin)
+    }
+
+    #[test]
+    fn test_outcomes_move_score() {
+        let env = Env::default();
+        let (reputation, admin) = setup(&env);
+        let escrow = Address::generate(&env);
+        reputation.add_reporter(&admin, &escrow);
+        let user = Address::generate(&env);
+
+        reputation.record(&escrow, &user, &Outcome::EscrowCompleted);
+        reputation.record(&escrow, &user, &Outcome::EscrowCompleted);
+        assert_eq!(
+            reputation.record(&escrow, &user, &Outcome::DeadlineMissed),
+            15
+        );
+        // Scores bottom out at zero.
+        reputation.record(&escrow, &user, &Outcome::DisputeLost);
+        reputation.record(&escrow, &user, &Outcome::DisputeLost);
+
+        assert_eq!(
+            reputation.get_reputation(&user),
+            ReputationRecord {
+                score: 0,
+                escrows_completed: 2,
+                disputes_lost: 2,
+                deadlines_missed: 1,
+            }
+        );
+        assert_eq!(reputation.score(&Address::generate(&env)), 0);
+    }
+
+    #[test]
+    fn test_only_reporters_write() {
+        let env = Env::default();
+        let (reputation, admin) = setup(&env);
+        let contract = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        assert_eq!(
+            reputation.try_record(&contract, &user, &Outcome::EscrowCompleted),
+            Err(Ok(ReputationError::NotReporter.into()))
+        );
+        reputation.add_reporter(&admin, &contract);
+        assert!(reputation.is_reporter(&contract));
+        reputation.record(&contract, &user, &Outcome::EscrowCompleted);
+
+        reputation.remove_reporter(&admin, &contract);
+        assert!(reputation
+            .try_record(&contract, &user, &Outcome::EscrowCompleted)
+            .is_err());
+        assert_eq!(reputation.score(&user), COMPLETED_POINTS);
+    }
+}
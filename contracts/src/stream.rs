@@ -0,0 +1,466 @@
+//! Streaming payments that vest linearly over time.
+//!
+//! A client locks a lump sum for a freelancer together with a start and end
+//! ledger timestamp. Between the two the sum vests linearly, and the
+//! freelancer can withdraw whatever has vested at any time. Either party may
+//! cancel: the freelancer keeps what has vested and the client is refunded
+//! the rest.
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, Env,
+    String, Vec,
+};
+
+use crate::admin;
+use crate::assets::{self, AssetInfo};
+use crate::rbac::{self, Role};
+use crate::{events, pausable, ttl};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum StreamStatus {
+    /// Still vesting, or vested but not fully withdrawn
+    Active = 0,
+    /// Fully vested and withdrawn
+    Completed = 1,
+    /// Cancelled and split between the parties
+    Cancelled = 2,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Stream {
+    pub client: Address,
+    pub freelancer: Address,
+    pub token: Address,
+    /// Total locked by the client
+    pub amount: i128,
+    pub start_time: u64,
+    pub end_time: u64,
+    /// Paid out to the freelancer so far
+    pub withdrawn: i128,
+    pub status: StreamStatus,
+}
+
+/// Errors raised by the payment stream contract.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum StreamError {
+    /// No stream with this id
+    StreamNotFound = 100,
+    /// The amount is not positive
+    InvalidAmount = 101,
+    /// The end time is not after the start time
+    InvalidSchedule = 102,
+    /// The token is not a supported asset
+    UnsupportedToken = 103,
+    /// The stream is completed or cancelled
+    NotActive = 104,
+    /// Nothing has vested since the last withdrawal
+    NothingVested = 105,
+    /// The caller is neither the client nor the freelancer
+    NotParty = 106,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    StreamCount,
+    Stream(u64),
+}
+
+#[contract]
+pub struct PaymentStream;
+
+#[contractimpl]
+impl PaymentStream {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Grants `role` to `account`. `caller` must be the role admin.
+    pub fn grant_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::grant_role(&env, &caller, role, &account);
+    }
+
+    /// Revokes `role` from `account`. `caller` must be the role admin.
+    pub fn revoke_role(env: Env, caller: Address, role: Role, account: Address) {
+        rbac::revoke_role(&env, &caller, role, &account);
+    }
+
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        rbac::has_role(&env, role, &account)
+    }
+
+    /// Pauses new streams, withdrawals and cancellations. Admin or guardian
+    /// only.
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause. Admin or guardian only.
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Registers (or re-points) a supported asset. Admin or operator only.
+    pub fn register_asset(env: Env, caller: Address, asset_code: String, asset: AssetInfo) {
+        assets::register_asset(&env, &caller, asset_code, asset);
+    }
+
+    /// Returns every supported `(code, asset)`, ordered by code.
+    pub fn supported_assets(env: Env) -> Vec<(String, AssetInfo)> {
+        assets::supported_assets(&env)
+    }
+
+    /// Locks `amount` from the client, vesting linearly to the freelancer
+    /// from `start_time` to `end_time` (ledger timestamps).
+    ///
+    /// # Returns
+    /// The new stream's id
+    ///
+    /// # Panics
+    /// If the amount is not positive, the schedule is empty or the token is
+    /// not a supported asset
+    pub fn create_stream(
+        env: Env,
+        client: Address,
+        freelancer: Address,
+        token: Address,
+        amount: i128,
+        start_time: u64,
+        end_time: u64,
+    ) -> u64 {
+        pausable::require_not_paused(&env);
+        client.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(env, StreamError::InvalidAmount);
+        }
+        if end_time <= start_time {
+            panic_with_error!(env, StreamError::InvalidSchedule);
+        }
+        if !assets::is_empty(&env) && !assets::is_supported_token(&env, &token) {
+            panic_with_error!(env, StreamError::UnsupportedToken);
+        }
+
+        token::Client::new(&env, &token).transfer(
+            &client,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::StreamCount)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::StreamCount, &(id + 1));
+        Self::save(
+            &env,
+            id,
+            &Stream {
+                client,
+                freelancer,
+                token,
+                amount,
+                start_time,
+                end_time,
+                withdrawn: 0,
+                status: StreamStatus::Active,
+            },
+        );
+        id
+    }
+
+    /// Pays the freelancer everything vested since the last withdrawal.
+    /// Requires the freelancer's auth.
+    ///
+    /// # Returns
+    /// The amount paid out
+    ///
+    /// # Panics
+    /// If the stream is not active or nothing has vested
+    pub fn withdraw_vested(env: Env, stream_id: u64) -> i128 {
+        pausable::require_not_paused(&env);
+        let mut stream = Self::load(&env, stream_id);
+        stream.freelancer.require_auth();
+        if stream.status != StreamStatus::Active {
+            panic_with_error!(env, StreamError::NotActive);
+        }
+
+        let payout = Self::pay_vested(&env, &mut stream);
+        if payout == 0 {
+            panic_with_error!(env, StreamError::NothingVested);
+        }
+        if stream.withdrawn == stream.amount {
+            stream.status = StreamStatus::Completed;
+        }
+        Self::save(&env, stream_id, &stream);
+        events::emit_stream_withdrawn(&env, stream_id, payout);
+        payout
+    }
+
+    /// Ends a stream early. The freelancer receives whatever has vested but
+    /// not been withdrawn, and the client is refunded the unvested remainder.
+    /// `caller` must be the client or the freelancer.
+    ///
+    /// # Returns
+    /// The amount refunded to the client
+    ///
+    /// # Panics
+    /// If `caller` is not a party or the stream is not active
+    pub fn cancel(env: Env, stream_id: u64, caller: Address) -> i128 {
+        pausable::require_not_paused(&env);
+        caller.require_auth();
+        let mut stream = Self::load(&env, stream_id);
+        if caller != stream.client && caller != stream.freelancer {
+            panic_with_error!(env, StreamError::NotParty);
+        }
+        if stream.status != StreamStatus::Active {
+            panic_with_error!(env, StreamError::NotActive);
+        }
+
+        let payout = Self::pay_vested(&env, &mut stream);
+        let refund = stream.amount - stream.withdrawn;
+        if refund > 0 {
+            token::Client::new(&env, &stream.token).transfer(
+                &env.current_contract_address(),
+                &stream.client,
+                &refund,
+            );
+        }
+
+        stream.status = StreamStatus::Cancelled;
+        Self::save(&env, stream_id, &stream);
+        events::emit_stream_cancelled(&env, stream_id, &caller, payout, refund);
+        refund
+    }
+
+    /// Returns the total vested so far, including what was already withdrawn.
+    pub fn vested_amount(env: Env, stream_id: u64) -> i128 {
+        let stream = Self::load(&env, stream_id);
+        Self::vested(&env, &stream)
+    }
+
+    /// Returns what `withdraw_vested` would pay out right now.
+    pub fn withdrawable(env: Env, stream_id: u64) -> i128 {
+        let stream = Self::load(&env, stream_id);
+        if stream.status != StreamStatus::Active {
+            return 0;
+        }
+        Self::vested(&env, &stream) - stream.withdrawn
+    }
+
+    /// # Panics
+    /// If the stream does not exist
+    pub fn get_stream(env: Env, stream_id: u64) -> Stream {
+        Self::load(&env, stream_id)
+    }
+
+    /// Amount vested at the current ledger timestamp. Frozen once the stream
+    /// is cancelled.
+    fn vested(env: &Env, stream: &Stream) -> i128 {
+        if stream.status == StreamStatus::Cancelled {
+            return stream.withdrawn;
+        }
+        let now = env
+            .ledger()
+            .timestamp()
+            .clamp(stream.start_time, stream.end_time);
+        let elapsed = (now - stream.start_time) as i128;
+        let duration = (stream.end_time - stream.start_time) as i128;
+        stream.amount * elapsed / duration
+    }
+
+    /// Transfers the vested, unwithdrawn balance to the freelancer and
+    /// returns it.
+    fn pay_vested(env: &Env, stream: &mut Stream) -> i128 {
+        let payout = Self::vested(env, stream) - stream.withdrawn;
+        if payout > 0 {
+            token::Client::new(env, &stream.token).transfer(
+                &env.current_contract_address(),
+                &stream.freelancer,
+                &payout,
+            );
+            stream.withdrawn += payout;
+        }
+        payout
+    }
+
+    fn load(env: &Env, stream_id: u64) -> Stream {
+        ttl::get(env, &DataKey::Stream(stream_id))
+            .unwrap_or_else(|| panic_with_error!(env, StreamError::StreamNotFound))
+    }
+
+    fn save(env: &Env, stream_id: u64, stream: &Stream) {
+        ttl::set(env, &DataKey::Stream(stream_id), stream);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+    use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+    const START: u64 = 1_000;
+    const END: u64 = 2_000;
+
+    struct Setup<'a> {
+        contract: PaymentStreamClient<'a>,
+        token: TokenClient<'a>,
+        client: Address,
+        freelancer: Address,
+    }
+
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+        let contract = PaymentStreamClient::new(env, &env.register_contract(None, PaymentStream));
+        let admin = Address::generate(env);
+        contract.initialize(&admin);
+
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        let client = Address::generate(env);
+        StellarAssetClient::new(env, &token).mint(&client, &10_000);
+        Setup {
+            contract,
+            token: TokenClient::new(env, &token),
+            client,
+            freelancer: Address::generate(env),
+        }
+    }
+
+    fn create(s: &Setup) -> u64 {
+        s.contract.create_stream(
+            &s.client,
+            &s.freelancer,
+            &s.token.address,
+            &1_000,
+            &START,
+            &END,
+        )
+    }
+
+    fn set_time(env: &Env, timestamp: u64) {
+        env.ledger().with_mut(|l| l.timestamp = timestamp);
+    }
+
+    #[test]
+    fn test_vests_linearly() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&s);
+
+        set_time(&env, START - 10);
+        assert_eq!(s.contract.withdrawable(&id), 0);
+        assert_eq!(
+            s.contract.try_withdraw_vested(&id),
+            Err(Ok(StreamError::NothingVested.into()))
+        );
+
+        set_time(&env, START + 250);
+        assert_eq!(s.contract.withdraw_vested(&id), 250);
+
+        set_time(&env, START + 600);
+        assert_eq!(s.contract.vested_amount(&id), 600);
+        assert_eq!(s.contract.withdraw_vested(&id), 350);
+
+        set_time(&env, END + 500);
+        assert_eq!(s.contract.withdraw_vested(&id), 400);
+        assert_eq!(s.token.balance(&s.freelancer), 1_000);
+        assert_eq!(s.contract.get_stream(&id).status, StreamStatus::Completed);
+        assert_eq!(
+            s.contract.try_withdraw_vested(&id),
+            Err(Ok(StreamError::NotActive.into()))
+        );
+    }
+
+    #[test]
+    fn test_cancel_splits_pro_rata() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&s);
+
+        set_time(&env, START + 200);
+        s.contract.withdraw_vested(&id);
+        set_time(&env, START + 700);
+        assert_eq!(s.contract.cancel(&id, &s.freelancer), 300);
+
+        assert_eq!(s.token.balance(&s.freelancer), 700);
+        assert_eq!(s.token.balance(&s.client), 10_000 - 700);
+        assert_eq!(s.contract.get_stream(&id).status, StreamStatus::Cancelled);
+
+        // Vesting stops at cancellation.
+        set_time(&env, END);
+        assert_eq!(s.contract.withdrawable(&id), 0);
+    }
+
+    #[test]
+    fn test_only_parties_cancel() {
+        let env = Env::default();
+        let s = setup(&env);
+        let id = create(&s);
+
+        assert_eq!(
+            s.contract.try_cancel(&id, &Address::generate(&env)),
+            Err(Ok(StreamError::NotParty.into()))
+        );
+        // Cancelling before the start refunds everything.
+        assert_eq!(s.contract.cancel(&id, &s.client), 1_000);
+        assert_eq!(s.token.balance(&s.client), 10_000);
+    }
+
+    #[test]
+    fn test_rejects_invalid_streams() {
+        let env = Env::default();
+        let s = setup(&env);
+        assert_eq!(
+            s.contract.try_create_stream(
+                &s.client,
+                &s.freelancer,
+                &s.token.address,
+                &0,
+                &START,
+                &END
+            ),
+            Err(Ok(StreamError::InvalidAmount.into()))
+        );
+        assert_eq!(
+            s.contract.try_create_stream(
+                &s.client,
+                &s.freelancer,
+                &s.token.address,
+                &1_000,
+                &END,
+                &START
+            ),
+            Err(Ok(StreamError::InvalidSchedule.into()))
+        );
+    }
+}
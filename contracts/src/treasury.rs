@@ -0,0 +1,289 @@
+//! Platform treasury.
+//!
+//! Collects the platform fee that the escrow and dispute contracts deduct
+//! from releases and payouts, keeping a per-asset tally for accounting.
+//! Fee-charging contracts point their `Treasury` key role at this contract
+//! and pay through [`pay_fee`]; funds sent with a plain transfer (slashed
+//! stakes, forfeited filing fees) are held too but not counted as fees.
+//! Only the multisig can move funds out.
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, vec, Address,
+    Env, IntoVal, Symbol, Vec,
+};
+
+use crate::admin::{self, KeyRole, Roles};
+use crate::errors::CommonError;
+use crate::{events, ttl};
+
+/// Errors raised by the treasury.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TreasuryError {
+    /// The amount is not positive
+    InvalidAmount = 100,
+    /// The treasury holds less than the requested amount
+    InsufficientBalance = 101,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    /// Governance contract allowed to withdraw
+    Multisig,
+    /// Assets that fees have been deposited in, in order of first deposit
+    FeeAssets,
+    /// Total fees ever deposited in an asset
+    FeesCollected(Address),
+    /// Total ever withdrawn in an asset
+    Withdrawn(Address),
+}
+
+/// Pays a platform fee of `amount` in `token` from the current contract to
+/// `treasury` through `deposit_fee`, authorizing the token transfer the
+/// treasury makes on the current contract's behalf.
+pub fn pay_fee(env: &Env, treasury: &Address, token: &Address, amount: i128) {
+    let payer = env.current_contract_address();
+    env.authorize_as_current_contract(vec![
+        env,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: token.clone(),
+                fn_name: Symbol::new(env, "transfer"),
+                args: (payer.clone(), treasury.clone(), amount).into_val(env),
+            },
+            sub_invocations: vec![env],
+        }),
+    ]);
+    TreasuryClient::new(env, treasury).deposit_fee(&payer, token, &amount);
+}
+
+#[contract]
+pub struct Treasury;
+
+#[contractimpl]
+impl Treasury {
+    /// Initializes the treasury with its admin and the multisig that
+    /// controls withdrawals. Can only be called once.
+    pub fn initialize(env: Env, admin: Address, multisig: Address) {
+        admin::set_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::Multisig, &multisig);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Assigns a key role.
+    pub fn set_role(env: Env, role: KeyRole, address: Address) {
+        admin::set_role(&env, role, &address);
+    }
+
+    /// Returns the admin and every key role in a single read.
+    pub fn get_roles(env: Env) -> Roles {
+        admin::get_roles(&env)
+    }
+
+    /// Returns the multisig that controls withdrawals.
+    ///
+    /// # Panics
+    /// If the treasury is not initialized
+    pub fn multisig(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Multisig)
+            .unwrap_or_else(|| panic_with_error!(env, CommonError::NotInitialized))
+    }
+
+    /// Hands withdrawal control to a new multisig. Requires the current
+    /// multisig's auth.
+    pub fn set_multisig(env: Env, new_multisig: Address) {
+        Self::multisig(env.clone()).require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::Multisig, &new_multisig);
+    }
+
+    /// Pulls a platform fee of `amount` in `token` from `from` and adds it
+    /// to the asset's tally. Requires `from`'s auth, which a contract paying
+    /// its own fee has implicitly.
+    ///
+    /// # Panics
+    /// If `amount` is not positive
+    pub fn deposit_fee(env: Env, from: Address, token: Address, amount: i128) {
+        from.require_auth();
+        if amount <= 0 {
+            panic_with_error!(env, TreasuryError::InvalidAmount);
+        }
+        token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount);
+
+        let collected = Self::fees_collected(env.clone(), token.clone());
+        if collected == 0 {
+            let mut assets = Self::fee_assets(env.clone());
+            assets.push_back(token.clone());
+            ttl::set(&env, &DataKey::FeeAssets, &assets);
+        }
+        ttl::set(
+            &env,
+            &DataKey::FeesCollected(token.clone()),
+            &(collected + amount),
+        );
+        events::emit_fee_deposited(&env, &token, &from, amount);
+    }
+
+    /// Sends `amount` of `token` to `to`. Multisig only.
+    ///
+    /// # Panics
+    /// If `amount` is not positive or exceeds the treasury's balance
+    pub fn withdraw(env: Env, token: Address, to: Address, amount: i128) {
+        Self::multisig(env.clone()).require_auth();
+        if amount <= 0 {
+            panic_with_error!(env, TreasuryError::InvalidAmount);
+        }
+        let token_client = token::Client::new(&env, &token);
+        let treasury = env.current_contract_address();
+        if token_client.balance(&treasury) < amount {
+            panic_with_error!(env, TreasuryError::InsufficientBalance);
+        }
+        token_client.transfer(&treasury, &to, &amount);
+
+        let withdrawn = Self::withdrawn(env.clone(), token.clone());
+        ttl::set(
+            &env,
+            &DataKey::Withdrawn(token.clone()),
+            &(withdrawn + amount),
+        );
+        events::emit_treasury_withdrawal(&env, &token, &to, amount);
+    }
+
+    /// Returns the total platform fees ever deposited in `token`.
+    pub fn fees_collected(env: Env, token: Address) -> i128 {
+        ttl::get(&env, &DataKey::FeesCollected(token)).unwrap_or(0)
+    }
+
+    /// Returns the total ever withdrawn in `token`.
+    pub fn withdrawn(env: Env, token: Address) -> i128 {
+        ttl::get(&env, &DataKey::Withdrawn(token)).unwrap_or(0)
+    }
+
+    /// Returns what the treasury currently holds in `token`.
+    pub fn balance(env: Env, token: Address) -> i128 {
+        token::Client::new(&env, &token).balance(&env.current_contract_address())
+    }
+
+    /// Returns every asset fees have been deposited in, in order of first
+    /// deposit.
+    pub fn fee_assets(env: Env) -> Vec<Address> {
+        ttl::get(&env, &DataKey::FeeAssets).unwrap_or_else(|| Vec::new(&env))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::token::StellarAssetClient;
+
+    /// Stands in for a fee-charging contract such as the escrow.
+    #[contract]
+    struct FeePayer;
+
+    #[contractimpl]
+    impl FeePayer {
+        pub fn pay(env: Env, treasury: Address, token: Address, amount: i128) {
+            pay_fee(&env, &treasury, &token, amount);
+        }
+    }
+
+    fn setup(env: &Env) -> (TreasuryClient<'_>, Address) {
+        env.mock_all_auths();
+        let treasury = TreasuryClient::new(env, &env.register_contract(None, Treasury));
+        let multisig = Address::generate(env);
+        treasury.initialize(&Address::generate(env), &multisig);
+        (treasury, multisig)
+    }
+
+    fn new_token(env: &Env, holder: &Address) -> Address {
+        let token = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        StellarAssetClient::new(env, &token).mint(holder, &1_000);
+        token
+    }
+
+    #[test]
+    fn test_tallies_fees_per_asset() {
+        let env = Env::default();
+        let (treasury, _) = setup(&env);
+        let payer = Address::generate(&env);
+        let usdc = new_token(&env, &payer);
+        let xlm = new_token(&env, &payer);
+
+        treasury.deposit_fee(&payer, &usdc, &30);
+        treasury.deposit_fee(&payer, &xlm, &5);
+        treasury.deposit_fee(&payer, &usdc, &20);
+
+        assert_eq!(treasury.fees_collected(&usdc), 50);
+        assert_eq!(treasury.fees_collected(&xlm), 5);
+        assert_eq!(treasury.balance(&usdc), 50);
+        assert_eq!(treasury.fee_assets(), Vec::from_array(&env, [usdc, xlm]));
+        assert_eq!(
+            treasury.try_deposit_fee(&payer, &treasury.fee_assets().get(0).unwrap(), &0),
+            Err(Ok(TreasuryError::InvalidAmount.into()))
+        );
+    }
+
+    #[test]
+    fn test_contract_pays_fee_without_mocked_auth() {
+        let env = Env::default();
+        let (treasury, _) = setup(&env);
+        let payer = FeePayerClient::new(&env, &env.register_contract(None, FeePayer));
+        let token = new_token(&env, &payer.address);
+
+        env.set_auths(&[]);
+        payer.pay(&treasury.address, &token, &25);
+        assert_eq!(treasury.fees_collected(&token), 25);
+        assert_eq!(treasury.balance(&token), 25);
+    }
+
+    #[test]
+    fn test_only_multisig_withdraws() {
+        let env = Env::default();
+        let (treasury, multisig) = setup(&env);
+        let payer = Address::generate(&env);
+        let token = new_token(&env, &payer);
+        treasury.deposit_fee(&payer, &token, &100);
+
+        let to = Address::generate(&env);
+        env.set_auths(&[]);
+        assert!(treasury.try_withdraw(&token, &to, &40).is_err());
+
+        env.mock_all_auths();
+        treasury.withdraw(&token, &to, &40);
+        assert_eq!(
+            env.auths()[0].0,
+            multisig,
+            "withdrawals are authorized by the multisig"
+        );
+        assert_eq!(treasury.balance(&token), 60);
+        assert_eq!(treasury.withdrawn(&token), 40);
+        // Withdrawals do not reduce the accounting tally.
+        assert_eq!(treasury.fees_collected(&token), 100);
+        assert_eq!(
+            treasury.try_withdraw(&token, &to, &61),
+            Err(Ok(TreasuryError::InsufficientBalance.into()))
+        );
+    }
+}
@@ -1,45 +1,399 @@
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, Address, Env, String,
+    Vec,
+};
+
+use crate::assets::{self, AssetInfo};
+use crate::{admin, events, pausable};
+
+/// Maximum number of assets accepted by a single batch trustline setup.
+pub const MAX_TRUSTLINE_BATCH: u32 = 20;
+
+/// Outcome of setting up one trustline.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum TrustlineStatus {
+    /// The trustline was set up by this call
+    Created = 0,
+    /// The user already had the trustline
+    Existing = 1,
+    /// The asset code is registered to a different issuer
+    IssuerMismatch = 2,
+}
+
+/// Errors raised while setting up trustlines.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TrustlineError {
+    /// The issuer does not match the registered asset
+    IssuerMismatch = 100,
+    /// Too many assets were requested at once
+    BatchTooLarge = 101,
+}
+
+#[contracttype]
+pub enum DataKey {
+    /// Set once a user has a trustline for `(asset_code, issuer)`
+    Trustline(Address, String, Address),
+    /// Every `(asset_code, issuer)` a user has a trustline for, in setup order
+    UserTrustlines(Address),
+}
 
 #[contract]
 pub struct TrustlineHandler;
 
 #[contractimpl]
 impl TrustlineHandler {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Pauses every state-changing entrypoint. Admin or guardian only.
+    pub fn pause(env: Env, caller: Address) {
+        pausable::pause(&env, &caller);
+    }
+
+    /// Resumes normal operation after a pause. Admin or guardian only.
+    pub fn unpause(env: Env, caller: Address) {
+        pausable::unpause(&env, &caller);
+    }
+
+    /// Returns `true` while the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    /// Registers (or re-points) an asset that wallets may set up a trustline
+    /// for. Admin only.
+    pub fn register_asset(env: Env, admin: Address, asset_code: String, asset: AssetInfo) {
+        assets::register_asset(&env, &admin, asset_code, asset);
+    }
+
+    /// Returns a registered asset, if any.
+    pub fn get_asset(env: Env, asset_code: String) -> Option<AssetInfo> {
+        assets::get_asset(&env, asset_code)
+    }
+
+    /// Returns every registered `(asset_code, issuer)` pair, ordered by code.
+    pub fn available_assets(env: Env) -> Vec<(String, Address)> {
+        let mut available = Vec::new(&env);
+        for (code, asset) in assets::supported_assets(&env).iter() {
+            available.push_back((code, asset.issuer));
+        }
+        available
+    }
+
     /// Simulates the 'change_trust' operation for a specific asset.
     /// This ensures the user's wallet is ready to receive payments.
+    ///
+    /// Idempotent: if the trustline already exists this returns early
+    /// without emitting another event.
+    ///
+    /// # Panics
+    /// If `asset_code` is registered to a different issuer
     pub fn ensure_trustline(
         env: Env,
         user: Address,
         asset_code: String,
-        _asset_issuer: Address,
+        asset_issuer: Address,
     ) -> bool {
+        pausable::require_not_paused(&env);
+        user.require_auth();
+
+        if Self::setup_trustline(&env, &user, asset_code, asset_issuer)
+            == TrustlineStatus::IssuerMismatch
+        {
+            panic_with_error!(env, TrustlineError::IssuerMismatch);
+        }
+        true
+    }
+
+    /// Sets up several trustlines for `user` in one invocation, e.g. during
+    /// onboarding. Existing trustlines are skipped and an asset that cannot
+    /// be set up does not fail the rest of the batch.
+    ///
+    /// # Returns
+    /// The outcome for each asset, in the same order as the input
+    ///
+    /// # Panics
+    /// If more than `MAX_TRUSTLINE_BATCH` assets are given
+    pub fn ensure_trustlines_batch(
+        env: Env,
+        user: Address,
+        assets: Vec<(String, Address)>,
+    ) -> Vec<TrustlineStatus> {
+        pausable::require_not_paused(&env);
         user.require_auth();
 
+        if assets.len() > MAX_TRUSTLINE_BATCH {
+            panic_with_error!(env, TrustlineError::BatchTooLarge);
+        }
+        let mut results = Vec::new(&env);
+        for (asset_code, asset_issuer) in assets.iter() {
+            results.push_back(Self::setup_trustline(&env, &user, asset_code, asset_issuer));
+        }
+        results
+    }
+
+    fn setup_trustline(
+        env: &Env,
+        user: &Address,
+        asset_code: String,
+        asset_issuer: Address,
+    ) -> TrustlineStatus {
+        if assets::get_asset(env, asset_code.clone()).is_some_and(|a| a.issuer != asset_issuer) {
+            return TrustlineStatus::IssuerMismatch;
+        }
+
         // 1. Check if trustline already exists (Idempotency)
-        // let has_trust = env.storage().instance().get(&(user, asset_code)).unwrap_or(false);
-        // if has_trust { return true; }
+        let key = DataKey::Trustline(user.clone(), asset_code.clone(), asset_issuer.clone());
+        if env.storage().persistent().has(&key) {
+            return TrustlineStatus::Existing;
+        }
 
         // 2. Check minimal reserve (Mock)
         // In reality: Check if XLM balance > 0.5
         // if get_balance(user) < 0.5 { panic!("Insufficient reserve for trustline"); }
 
         // 3. Execute Change Trust (Simulated)
-        // env.storage().instance().set(&(user, asset_code), &true);
+        env.storage().persistent().set(&key, &true);
+        let mut trustlines = Self::list_trustlines(env.clone(), user.clone());
+        trustlines.push_back((asset_code.clone(), asset_issuer));
+        env.storage()
+            .persistent()
+            .set(&DataKey::UserTrustlines(user.clone()), &trustlines);
 
-        env.events().publish(
-            (String::from_str(&env, "trustline_configured"), user),
-            asset_code,
-        );
+        events::emit_trustline_configured(env, user, &asset_code);
 
-        true
+        TrustlineStatus::Created
     }
 
-    /// Helper to automate the setup specifically for the platform's USDC.
+    /// Returns `true` if `user` has a trustline for the asset.
+    pub fn has_trustline(
+        env: Env,
+        user: Address,
+        asset_code: String,
+        asset_issuer: Address,
+    ) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Trustline(user, asset_code, asset_issuer))
+    }
+
+    /// Returns every `(asset_code, issuer)` `user` has a trustline for, in
+    /// setup order.
+    pub fn list_trustlines(env: Env, user: Address) -> Vec<(String, Address)> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UserTrustlines(user))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Helper to automate the setup specifically for the platform's USDC,
+    /// using the registered issuer.
+    ///
+    /// # Panics
+    /// If USDC is not registered
     pub fn setup_usdc_trustline(env: Env, user: Address) {
         let usdc_code = String::from_str(&env, "USDC");
-        // Mock issuer address for simulation
-        let usdc_issuer = user.clone();
+        let usdc_issuer = assets::require_asset(&env, usdc_code.clone()).issuer;
 
         Self::ensure_trustline(env, user, usdc_code, usdc_issuer);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Events},
+        vec, Address, Env, String,
+    };
+
+    /// Registry entry for an asset issued by `issuer`.
+    fn asset(issuer: &Address) -> AssetInfo {
+        AssetInfo {
+            issuer: issuer.clone(),
+            token: Address::generate(issuer.env()),
+        }
+    }
+
+    #[test]
+    fn test_pause_blocks_trustline_setup() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, TrustlineHandler);
+        let client = TrustlineHandlerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let issuer = Address::generate(&env);
+        let usdc = String::from_str(&env, "USDC");
+        client.initialize(&admin);
+        client.register_asset(&admin, &usdc, &asset(&issuer));
+
+        client.pause(&admin);
+        assert!(client.try_ensure_trustline(&user, &usdc, &issuer).is_err());
+        assert!(client.try_setup_usdc_trustline(&user).is_err());
+
+        client.unpause(&admin);
+        assert!(client.ensure_trustline(&user, &usdc, &issuer));
+        client.setup_usdc_trustline(&user);
+    }
+
+    #[test]
+    fn test_usdc_setup_uses_registered_issuer() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, TrustlineHandler);
+        let client = TrustlineHandlerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let issuer = Address::generate(&env);
+        let usdc = String::from_str(&env, "USDC");
+        client.initialize(&admin);
+        assert!(client.try_setup_usdc_trustline(&user).is_err());
+
+        let usdc_asset = asset(&issuer);
+        client.register_asset(&admin, &usdc, &usdc_asset);
+        assert_eq!(client.get_asset(&usdc), Some(usdc_asset));
+        client.setup_usdc_trustline(&user);
+        assert!(client.has_trustline(&user, &usdc, &issuer));
+        assert!(!client.has_trustline(&user, &usdc, &user));
+    }
+
+    #[test]
+    fn test_available_assets_lists_registry() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, TrustlineHandler);
+        let client = TrustlineHandlerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let usdc_issuer = Address::generate(&env);
+        let eurc_issuer = Address::generate(&env);
+        let ngnc_issuer = Address::generate(&env);
+        client.initialize(&admin);
+        assert_eq!(client.available_assets(), vec![&env]);
+
+        let usdc = String::from_str(&env, "USDC");
+        let eurc = String::from_str(&env, "EURC");
+        let ngnc = String::from_str(&env, "NGNC");
+        client.register_asset(&admin, &usdc, &asset(&usdc_issuer));
+        client.register_asset(&admin, &eurc, &asset(&eurc_issuer));
+        client.register_asset(&admin, &ngnc, &asset(&ngnc_issuer));
+        assert!(client
+            .try_register_asset(&usdc_issuer, &usdc, &asset(&usdc_issuer))
+            .is_err());
+
+        assert_eq!(
+            client.available_assets(),
+            vec![
+                &env,
+                (eurc, eurc_issuer),
+                (ngnc, ngnc_issuer),
+                (usdc, usdc_issuer)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ensure_trustline_is_idempotent() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, TrustlineHandler);
+        let client = TrustlineHandlerClient::new(&env, &contract_id);
+
+        let user = Address::generate(&env);
+        let usdc_issuer = Address::generate(&env);
+        let eurc_issuer = Address::generate(&env);
+        let usdc = String::from_str(&env, "USDC");
+        let eurc = String::from_str(&env, "EURC");
+        assert!(!client.has_trustline(&user, &usdc, &usdc_issuer));
+
+        assert!(client.ensure_trustline(&user, &usdc, &usdc_issuer));
+        assert_eq!(env.events().all().len(), 1);
+        assert!(client.ensure_trustline(&user, &usdc, &usdc_issuer));
+        assert_eq!(env.events().all().len(), 1);
+
+        // The same code from another issuer is a separate trustline.
+        assert!(!client.has_trustline(&user, &usdc, &eurc_issuer));
+        client.ensure_trustline(&user, &eurc, &eurc_issuer);
+        assert!(client.has_trustline(&user, &usdc, &usdc_issuer));
+        assert_eq!(
+            client.list_trustlines(&user),
+            vec![&env, (usdc, usdc_issuer), (eurc, eurc_issuer)]
+        );
+        assert_eq!(client.list_trustlines(&Address::generate(&env)), vec![&env]);
+    }
+
+    #[test]
+    fn test_batch_trustline_setup_reports_per_asset() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, TrustlineHandler);
+        let client = TrustlineHandlerClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let usdc_issuer = Address::generate(&env);
+        let eurc_issuer = Address::generate(&env);
+        let spoofed = Address::generate(&env);
+        let usdc = String::from_str(&env, "USDC");
+        let eurc = String::from_str(&env, "EURC");
+        let lance = String::from_str(&env, "LANCE");
+        client.initialize(&admin);
+        client.register_asset(&admin, &eurc, &asset(&eurc_issuer));
+        client.ensure_trustline(&user, &usdc, &usdc_issuer);
+        assert!(client.try_ensure_trustline(&user, &eurc, &spoofed).is_err());
+
+        let results = client.ensure_trustlines_batch(
+            &user,
+            &vec![
+                &env,
+                (usdc.clone(), usdc_issuer.clone()),
+                (eurc.clone(), spoofed.clone()),
+                (eurc.clone(), eurc_issuer.clone()),
+                (lance.clone(), admin.clone()),
+            ],
+        );
+        assert_eq!(
+            results,
+            vec![
+                &env,
+                TrustlineStatus::Existing,
+                TrustlineStatus::IssuerMismatch,
+                TrustlineStatus::Created,
+                TrustlineStatus::Created
+            ]
+        );
+        assert_eq!(client.list_trustlines(&user).len(), 3);
+        assert!(!client.has_trustline(&user, &eurc, &spoofed));
+
+        let mut oversized = Vec::new(&env);
+        for _ in 0..=MAX_TRUSTLINE_BATCH {
+            oversized.push_back((usdc.clone(), usdc_issuer.clone()));
+        }
+        assert!(client
+            .try_ensure_trustlines_batch(&user, &oversized)
+            .is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! Time-to-live management for persistent entries.
+//!
+//! Escrows, disputes, proposals and quotas can outlive the network's
+//! default persistent TTL, so contracts read and write those entries through
+//! `get` and `set` here, which extend an entry's TTL whenever it has dropped
+//! below `BUMP_THRESHOLD`. `bump` lets a keeper do the same for entries that
+//! sit untouched for long stretches.
+use soroban_sdk::{panic_with_error, Env, IntoVal, TryFromVal, Val, Vec};
+
+use crate::errors::CommonError;
+
+/// Number of ledgers in one day (~5s close time).
+const LEDGERS_PER_DAY: u32 = 17_280;
+
+/// Remaining TTL below which an accessed entry is extended.
+pub const BUMP_THRESHOLD: u32 = 30 * LEDGERS_PER_DAY;
+
+/// TTL an entry is extended to, capped by the network maximum.
+pub const BUMP_TO: u32 = 120 * LEDGERS_PER_DAY;
+
+/// Maximum number of keys accepted by a single `bump_all`.
+pub const MAX_BUMP_BATCH: u32 = 50;
+
+/// Reads a persistent entry, extending its TTL if it is running low.
+pub fn get<K, V>(env: &Env, key: &K) -> Option<V>
+where
+    K: IntoVal<Env, Val>,
+    V: TryFromVal<Env, Val>,
+{
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        extend(env, key);
+    }
+    value
+}
+
+/// Writes a persistent entry and extends its TTL if it is running low.
+pub fn set<K, V>(env: &Env, key: &K, value: &V)
+where
+    K: IntoVal<Env, Val>,
+    V: IntoVal<Env, Val>,
+{
+    env.storage().persistent().set(key, value);
+    extend(env, key);
+}
+
+/// Extends the TTL of an existing persistent entry if it is running low.
+/// Returns `false` if there is no such entry.
+pub fn bump<K>(env: &Env, key: &K) -> bool
+where
+    K: IntoVal<Env, Val>,
+{
+    if !env.storage().persistent().has(key) {
+        return false;
+    }
+    extend(env, key);
+    true
+}
+
+/// Runs `bump` for every key and returns how many entries exist.
+///
+/// # Panics
+/// If more than `MAX_BUMP_BATCH` keys are given
+pub fn bump_all<K>(env: &Env, keys: &Vec<K>) -> u32
+where
+    K: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone,
+{
+    if keys.len() > MAX_BUMP_BATCH {
+        panic_with_error!(env, CommonError::BatchTooLarge);
+    }
+    keys.iter().filter(|key| bump(env, key)).count() as u32
+}
+
+fn extend<K>(env: &Env, key: &K)
+where
+    K: IntoVal<Env, Val>,
+{
+    env.storage()
+        .persistent()
+        .extend_ttl(key, BUMP_THRESHOLD, BUMP_TO);
+}
@@ -1,20 +1,308 @@
-use soroban_sdk::{contract, contractimpl, Env};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, Address, BytesN, Env,
+    Symbol, Vec,
+};
+
+use crate::errors::CommonError;
+
+use crate::{admin, events};
+
+/// Storage schema the upgrade handler's code expects. Bump it, and add a
+/// step to `UpgradeHandler::migration_step`, whenever its storage layout
+/// changes.
+///
+/// The schema is tracked per contract, and only `UpgradeHandler` tracks one
+/// so far; other contracts (escrow, dispute court, ...) are not guarded.
+/// A contract opts in by stamping its own schema constant in `initialize`,
+/// exposing a `migrate` entrypoint built on `run_migrations`, and calling
+/// `require_migrated` from its state-changing entrypoints.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Schema assumed for contracts deployed before schema tracking.
+pub const LEGACY_SCHEMA_VERSION: u32 = 1;
+
+/// Semantic version of the deployed contract code.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[contracttype]
+pub struct ContractVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+/// A named behaviour that turns on once the network reaches
+/// `min_protocol`, unless the admin has forced it on or off.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FeatureFlag {
+    pub min_protocol: u32,
+    /// Admin decision that takes precedence over the protocol check
+    pub admin_override: Option<bool>,
+}
+
+/// Errors raised by the upgrade handler.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum UpgradeError {
+    /// The new version is not above the current one
+    VersionNotIncreased = 100,
+    /// No feature flag is registered under the name
+    UnknownFeature = 101,
+}
+
+#[contracttype]
+pub enum DataKey {
+    /// `(protocol_version, first_seen_ledger)` for every version observed
+    VersionHistory,
+    /// Version of the code last installed through `upgrade`
+    ContractVersion,
+    /// Storage schema the stored entries are in
+    SchemaVersion,
+    Feature(Symbol),
+    /// Names of every registered feature flag, in order of registration
+    Features,
+}
+
+/// Returns `true` if `flag` is enabled in the feature registry at
+/// `registry`. Contracts branch on this rather than comparing protocol
+/// versions themselves, so a behaviour can be switched on or off across
+/// network upgrades without redeploying them.
+pub fn is_enabled(env: &Env, registry: &Address, flag: &Symbol) -> bool {
+    UpgradeHandlerClient::new(env, registry).is_enabled(flag)
+}
+
+/// Returns the schema the calling contract's stored entries are in.
+pub fn schema_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SchemaVersion)
+        .unwrap_or(LEGACY_SCHEMA_VERSION)
+}
+
+/// Marks freshly initialized storage as already being in schema `current`.
+pub fn stamp_schema_version(env: &Env, current: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::SchemaVersion, &current);
+}
+
+/// Guards a state-changing entrypoint of a contract that tracks its schema.
+///
+/// # Panics
+/// If stored entries have not been migrated to schema `current` yet
+pub fn require_migrated(env: &Env, current: u32) {
+    if schema_version(env) < current {
+        panic_with_error!(env, CommonError::MigrationPending);
+    }
+}
+
+/// Runs `step(env, version)` for every schema version after the stored one
+/// up to `current`, recording progress after each step. Steps must be
+/// idempotent. Returns the schema version reached.
+pub fn run_migrations<F: Fn(&Env, u32)>(env: &Env, current: u32, step: F) -> u32 {
+    let mut version = schema_version(env);
+    while version < current {
+        version += 1;
+        step(env, version);
+        stamp_schema_version(env, version);
+    }
+    version
+}
 
 #[contract]
 pub struct UpgradeHandler;
 
 #[contractimpl]
 impl UpgradeHandler {
+    /// Initializes the contract with its admin. Can only be called once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin::set_admin(&env, &admin);
+        stamp_schema_version(&env, SCHEMA_VERSION);
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        admin::get_admin(&env)
+    }
+
+    /// Proposes `new_admin` as the next admin; takes effect once accepted.
+    pub fn transfer_admin(env: Env, new_admin: Address) {
+        admin::transfer_admin(&env, &new_admin);
+    }
+
+    /// Accepts a pending admin transfer. Must be called by the proposed admin.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        admin::accept_admin(&env, &new_admin);
+    }
+
+    /// Replaces this contract's code with the uploaded wasm `new_wasm_hash`
+    /// and records `version`, which must be newer than the current one.
+    /// Admin only; make the multisig contract the admin to require its
+    /// approval for upgrades.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>, version: ContractVersion) {
+        require_migrated(&env, SCHEMA_VERSION);
+        admin::require_admin(&env);
+        if version <= Self::version(env.clone()) {
+            panic_with_error!(env, UpgradeError::VersionNotIncreased);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::ContractVersion, &version);
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        events::emit_contract_upgraded(
+            &env,
+            &env.current_contract_address(),
+            &new_wasm_hash,
+            (version.major, version.minor, version.patch),
+        );
+    }
+
+    /// Brings stored entries up to `SCHEMA_VERSION`, typically right after
+    /// an `upgrade`. Safe to call repeatedly. Admin only.
+    pub fn migrate(env: Env) -> u32 {
+        admin::require_admin(&env);
+        run_migrations(&env, SCHEMA_VERSION, Self::migration_step)
+    }
+
+    /// Returns the schema the stored entries are in.
+    pub fn schema_version(env: Env) -> u32 {
+        schema_version(&env)
+    }
+
+    /// Returns the installed code version, `0.0.0` before the first upgrade.
+    pub fn version(env: Env) -> ContractVersion {
+        env.storage()
+            .instance()
+            .get(&DataKey::ContractVersion)
+            .unwrap_or(ContractVersion {
+                major: 0,
+                minor: 0,
+                patch: 0,
+            })
+    }
+
     /// Checks if the current ledger protocol version meets the minimum requirement.
     /// Useful for ensuring contracts only run on V21+ if they rely on new features.
+    /// The first check under each protocol version is recorded in `version_history`.
     pub fn check_protocol_version(env: Env, min_version: u32) -> bool {
+        require_migrated(&env, SCHEMA_VERSION);
         let current_version = env.ledger().protocol_version();
+
+        let mut history = Self::version_history(env.clone());
+        if !history
+            .iter()
+            .any(|(version, _)| version == current_version)
+        {
+            history.push_back((current_version, env.ledger().sequence()));
+            env.storage()
+                .persistent()
+                .set(&DataKey::VersionHistory, &history);
+        }
+
         current_version >= min_version
     }
 
+    /// Registers `flag`, or changes the protocol version it needs, keeping
+    /// any override. Admin only.
+    pub fn set_feature(env: Env, flag: Symbol, min_protocol: u32) {
+        require_migrated(&env, SCHEMA_VERSION);
+        admin::require_admin(&env);
+        let key = DataKey::Feature(flag.clone());
+        let feature = match env.storage().instance().get::<_, FeatureFlag>(&key) {
+            Some(feature) => FeatureFlag {
+                min_protocol,
+                ..feature
+            },
+            None => {
+                let mut features = Self::features(env.clone());
+                features.push_back(flag.clone());
+                env.storage().instance().set(&DataKey::Features, &features);
+                FeatureFlag {
+                    min_protocol,
+                    admin_override: None,
+                }
+            }
+        };
+        env.storage().instance().set(&key, &feature);
+        events::emit_feature_flag_set(&env, &flag, &feature);
+    }
+
+    /// Forces `flag` on or off regardless of the protocol version, or with
+    /// `None` goes back to following it. Admin only.
+    ///
+    /// # Panics
+    /// With `UnknownFeature` if `flag` is not registered
+    pub fn set_feature_override(env: Env, flag: Symbol, admin_override: Option<bool>) {
+        require_migrated(&env, SCHEMA_VERSION);
+        admin::require_admin(&env);
+        let mut feature = Self::get_feature(env.clone(), flag.clone())
+            .unwrap_or_else(|| panic_with_error!(env, UpgradeError::UnknownFeature));
+        feature.admin_override = admin_override;
+        env.storage()
+            .instance()
+            .set(&DataKey::Feature(flag.clone()), &feature);
+        events::emit_feature_flag_set(&env, &flag, &feature);
+    }
+
+    /// Returns `true` if `flag` is forced on, or follows the protocol
+    /// version and the network has reached its minimum. Unknown flags are
+    /// disabled.
+    pub fn is_enabled(env: Env, flag: Symbol) -> bool {
+        match Self::get_feature(env.clone(), flag) {
+            Some(FeatureFlag {
+                admin_override: Some(enabled),
+                ..
+            }) => enabled,
+            Some(feature) => Self::check_protocol_version(env, feature.min_protocol),
+            None => false,
+        }
+    }
+
+    /// Returns a registered feature flag.
+    pub fn get_feature(env: Env, flag: Symbol) -> Option<FeatureFlag> {
+        env.storage().instance().get(&DataKey::Feature(flag))
+    }
+
+    /// Returns the name of every registered feature flag, in order of
+    /// registration.
+    pub fn features(env: Env) -> Vec<Symbol> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Features)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns each protocol version the contract has been checked under,
+    /// with the ledger it was first seen on, oldest first.
+    pub fn version_history(env: Env) -> Vec<(u32, u32)> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::VersionHistory)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Migrates stored entries from schema `version - 1` to `version`.
+    fn migration_step(env: &Env, version: u32) {
+        if version == 2 {
+            // The version history moved out of instance storage so it does
+            // not grow the instance entry loaded on every call.
+            let legacy: Option<Vec<(u32, u32)>> =
+                env.storage().instance().get(&DataKey::VersionHistory);
+            if let Some(history) = legacy {
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::VersionHistory, &history);
+                env.storage().instance().remove(&DataKey::VersionHistory);
+            }
+        }
+    }
+
     /// Manages State Archival by extending the Time-To-Live (TTL) of the contract instance.
     /// Crucial for V20+ to prevent data from being archived and becoming inaccessible without restoration.
     pub fn extend_instance_ttl(env: Env, threshold: u32, extend_to: u32) {
+        require_migrated(&env, SCHEMA_VERSION);
         // In a real V20+ environment:
         env.storage().instance().extend_ttl(threshold, extend_to);
     }
@@ -29,3 +317,154 @@ impl UpgradeHandler {
         true
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+        vec, Address, Env, IntoVal,
+    };
+
+    fn version(major: u32, minor: u32, patch: u32) -> ContractVersion {
+        ContractVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    #[test]
+    fn test_upgrade_is_admin_only_and_versioned() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, UpgradeHandler);
+        let client = UpgradeHandlerClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+        assert_eq!(client.version(), version(0, 0, 0));
+
+        let hash = BytesN::from_array(&env, &[7; 32]);
+        let next = version(1, 0, 0);
+        let outsider = Address::generate(&env);
+        assert!(client
+            .mock_auths(&[MockAuth {
+                address: &outsider,
+                invoke: &MockAuthInvoke {
+                    contract: &contract_id,
+                    fn_name: "upgrade",
+                    args: (hash.clone(), next.clone()).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_upgrade(&hash, &next)
+            .is_err());
+
+        env.mock_all_auths();
+        assert!(client.try_upgrade(&hash, &version(0, 0, 0)).is_err());
+        // The hash has not been uploaded, so the swap itself fails and the
+        // version is left untouched.
+        assert!(client.try_upgrade(&hash, &next).is_err());
+        assert_eq!(client.version(), version(0, 0, 0));
+        assert!(version(1, 2, 0) > version(1, 1, 9));
+    }
+
+    #[test]
+    fn test_version_history_records_distinct_versions() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, UpgradeHandler);
+        let client = UpgradeHandlerClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env));
+        assert_eq!(client.version_history(), vec![&env]);
+
+        env.ledger().with_mut(|l| {
+            l.protocol_version = 20;
+            l.sequence_number = 100;
+        });
+        assert!(!client.check_protocol_version(&21));
+        env.ledger().with_mut(|l| l.sequence_number = 150);
+        client.check_protocol_version(&21);
+
+        env.ledger().with_mut(|l| {
+            l.protocol_version = 21;
+            l.sequence_number = 200;
+        });
+        assert!(client.check_protocol_version(&21));
+        client.check_protocol_version(&20);
+
+        assert_eq!(client.version_history(), vec![&env, (20, 100), (21, 200)]);
+    }
+
+    #[test]
+    fn test_feature_flags_follow_protocol_unless_overridden() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, UpgradeHandler);
+        let client = UpgradeHandlerClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env));
+        env.ledger().with_mut(|l| l.protocol_version = 21);
+        let bls = Symbol::new(&env, "bls_verification");
+        let constructors = Symbol::new(&env, "constructor_support");
+
+        assert!(!client.is_enabled(&bls));
+        client.set_feature(&bls, &22);
+        client.set_feature(&constructors, &21);
+        assert!(!client.is_enabled(&bls));
+        assert!(client.is_enabled(&constructors));
+
+        client.set_feature_override(&bls, &Some(true));
+        client.set_feature_override(&constructors, &Some(false));
+        // Re-registering keeps the override.
+        client.set_feature(&bls, &23);
+        assert!(client.is_enabled(&bls));
+        assert!(!client.is_enabled(&constructors));
+
+        client.set_feature_override(&bls, &None);
+        env.ledger().with_mut(|l| l.protocol_version = 23);
+        assert!(client.is_enabled(&bls));
+        assert_eq!(client.features(), vec![&env, bls, constructors]);
+        assert_eq!(
+            client.try_set_feature_override(&Symbol::new(&env, "unknown"), &Some(true)),
+            Err(Ok(UpgradeError::UnknownFeature.into()))
+        );
+    }
+
+    #[test]
+    fn test_migrate_upgrades_legacy_storage() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|l| l.protocol_version = 20);
+        let contract_id = env.register_contract(None, UpgradeHandler);
+        let client = UpgradeHandlerClient::new(&env, &contract_id);
+
+        // A contract deployed before schema tracking, with its history in
+        // instance storage.
+        let admin = Address::generate(&env);
+        env.as_contract(&contract_id, || {
+            admin::set_admin(&env, &admin);
+            env.storage()
+                .instance()
+                .set(&DataKey::VersionHistory, &vec![&env, (20u32, 100u32)]);
+        });
+        assert_eq!(client.schema_version(), LEGACY_SCHEMA_VERSION);
+        // Every state-changing entrypoint waits for the migration.
+        let pending = Some(Ok(soroban_sdk::Error::from(CommonError::MigrationPending)));
+        assert_eq!(client.try_check_protocol_version(&20).err(), pending);
+        let flag = Symbol::new(&env, "bls_verification");
+        assert_eq!(client.try_set_feature(&flag, &22).err(), pending);
+        assert_eq!(
+            client.try_set_feature_override(&flag, &Some(true)).err(),
+            pending
+        );
+        assert_eq!(client.try_extend_instance_ttl(&10, &100).err(), pending);
+        let hash = BytesN::from_array(&env, &[7; 32]);
+        assert_eq!(client.try_upgrade(&hash, &version(1, 0, 0)).err(), pending);
+
+        assert_eq!(client.migrate(), SCHEMA_VERSION);
+        assert_eq!(client.version_history(), vec![&env, (20, 100)]);
+        assert!(client.check_protocol_version(&20));
+
+        // Running it again is a no-op.
+        assert_eq!(client.migrate(), SCHEMA_VERSION);
+        assert_eq!(client.version_history(), vec![&env, (20, 100)]);
+    }
+}